@@ -58,10 +58,15 @@
 #![allow(unused)]
 
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use futures::future::join_all;
 use glob::glob;
 use rdkafka::config::ClientConfig;
-use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::error::KafkaError;
+use rdkafka::message::{Header, Message as _, OwnedHeaders};
+use rdkafka::producer::{DeliveryFuture, FutureProducer, FutureRecord, Producer};
+use rdkafka::types::RDKafkaErrorCode;
 use serde_json::Value;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
@@ -74,9 +79,26 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 #[derive(Parser, Debug)]
 #[clap(
     name = "kafka-producer",
-    about = "Stream JSON data from files to Kafka topics"
+    about = "Stream JSON data to/from Kafka topics"
 )]
-struct Args {
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Stream JSON data from files into a Kafka topic (the original
+    /// behavior, now explicit)
+    Produce(ProduceArgs),
+    /// Consume messages back off a topic and write them to a file, to
+    /// validate that what was produced can be consumed or to snapshot a
+    /// topic
+    Consume(ConsumeArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct ProduceArgs {
     /// Path to JSON file or directory containing JSON files
     #[arg(short, long)]
     input: PathBuf,
@@ -85,10 +107,18 @@ struct Args {
     #[arg(short, long, default_value = "localhost:9092")]
     brokers: String,
 
-    /// Kafka topic to send messages to
+    /// Kafka topic to send messages to (the default/fallback when
+    /// --topic-template is unset or a record's template pointer doesn't resolve)
     #[arg(short, long)]
     topic: String,
 
+    /// Topic template: a string with {ptr} placeholders (JSON pointers like
+    /// {/event/type}) resolved per record to pick its destination topic, e.g.
+    /// "events.{/event/type}". Falls back to --topic when unset or when a
+    /// placeholder's pointer doesn't resolve against a given record.
+    #[arg(long)]
+    topic_template: Option<String>,
+
     /// Delay between messages in milliseconds (0 = no delay)
     #[arg(short, long, default_value_t = 0)]
     delay_ms: u64,
@@ -109,6 +139,44 @@ struct Args {
     #[arg(long)]
     continue_on_error: bool,
 
+    /// JSON pointer(s) (e.g. /user/id) used to extract the Kafka record key
+    /// from each message, so all messages for the same entity land on the
+    /// same partition. Join multiple pointers with --key-separator for a
+    /// composite key. Unset (the default) sends an empty key, forcing
+    /// round-robin partitioning.
+    #[arg(long)]
+    key_field: Option<String>,
+
+    /// Separator used both to split --key-field into multiple JSON
+    /// pointers and to join their resolved values into a composite key
+    #[arg(long, default_value = ",")]
+    key_separator: String,
+
+    /// Maximum number of produce requests kept outstanding at once. Messages
+    /// are enqueued without waiting for delivery up to this cap, then the
+    /// batch drains, so throughput isn't bottlenecked by per-message
+    /// round-trip latency.
+    #[arg(long, default_value_t = 1000)]
+    max_in_flight: usize,
+
+    /// Kafka topic to republish a message to if it still fails after
+    /// --max-retries. The original payload is wrapped in an envelope
+    /// recording the source file, record index, error, and timestamp.
+    /// Unset (the default) drops failed messages after retries are
+    /// exhausted, as before.
+    #[arg(long)]
+    dlq_topic: Option<String>,
+
+    /// Number of times to retry a failed send before giving up on it (and
+    /// publishing to --dlq-topic, if set)
+    #[arg(long, default_value_t = 3)]
+    max_retries: u32,
+
+    /// Base delay in milliseconds between retries, doubled after each
+    /// attempt (exponential backoff)
+    #[arg(long, default_value_t = 100)]
+    retry_backoff_ms: u64,
+
     /// Dry run (don't actually send messages)
     #[arg(long)]
     dry_run: bool,
@@ -117,11 +185,120 @@ struct Args {
     #[arg(long, default_value = "rde-kafka-producer")]
     client_id: String,
 
+    /// Compression codec applied to produced messages
+    #[arg(long, value_enum, default_value_t = Compression::None)]
+    compression: Compression,
+
+    /// Delay in milliseconds to batch messages before sending (librdkafka's
+    /// linger.ms)
+    #[arg(long)]
+    linger_ms: Option<u64>,
+
+    /// Maximum number of messages batched into a single produce request
+    /// (librdkafka's batch.num.messages)
+    #[arg(long)]
+    batch_num_messages: Option<usize>,
+
+    /// Number of broker acknowledgments required before a produce is
+    /// considered successful (librdkafka's acks), e.g. "0", "1", "all"
+    #[arg(long)]
+    acks: Option<String>,
+
+    /// Arbitrary librdkafka producer property as key=value, repeatable.
+    /// Forwarded straight into ClientConfig, applied after the flags above so
+    /// it can override them.
+    #[arg(short = 'X', long = "config", value_parser = parse_key_val)]
+    extra_config: Vec<(String, String)>,
+
+    /// JSON pointer to a field used as the record's Kafka timestamp instead
+    /// of ingestion time, e.g. /event/occurred_at. Accepts either an
+    /// epoch-millis number or an RFC3339 string. Unresolved or unparseable
+    /// values fall back to the broker/client assigning the timestamp as
+    /// usual.
+    #[arg(long)]
+    timestamp_field: Option<String>,
+
+    /// Kafka header built from a message field, as name=/json/pointer,
+    /// repeatable. A pointer that doesn't resolve for a given message simply
+    /// omits that header rather than erroring.
+    #[arg(long = "header-field", value_parser = parse_key_val)]
+    header_fields: Vec<(String, String)>,
+
     /// Show progress every N messages
     #[arg(long, default_value_t = 100)]
     progress_interval: usize,
 }
 
+#[derive(clap::Args, Debug)]
+struct ConsumeArgs {
+    /// Kafka brokers (comma-separated)
+    #[arg(short, long, default_value = "localhost:9092")]
+    brokers: String,
+
+    /// Kafka topic to consume from
+    #[arg(short, long)]
+    topic: String,
+
+    /// Consumer group ID
+    #[arg(long, default_value = "rde-kafka-consumer")]
+    group_id: String,
+
+    /// Where to start consuming from when the group has no committed offset
+    /// (or --offset stored finds none)
+    #[arg(long, value_enum, default_value_t = OffsetReset::Beginning)]
+    offset: OffsetReset,
+
+    /// Stop after consuming this many messages (default: run until the idle
+    /// timeout)
+    #[arg(long)]
+    max_messages: Option<usize>,
+
+    /// Stop after this many milliseconds with no new message
+    #[arg(long, default_value_t = 5000)]
+    idle_timeout_ms: u64,
+
+    /// Output format for the consumed messages
+    #[arg(short = 'f', long, value_enum, default_value_t = ConsumeOutputFormat::Ndjson)]
+    output_format: ConsumeOutputFormat,
+
+    /// File to write consumed messages to
+    #[arg(short, long)]
+    output: PathBuf,
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum OffsetReset {
+    /// Start from the earliest available offset
+    Beginning,
+    /// Start from the latest offset (only new messages)
+    End,
+    /// Resume from the group's last committed offset
+    Stored,
+}
+
+impl OffsetReset {
+    /// librdkafka's `auto.offset.reset` fallback value for this variant, used
+    /// only when the group has no committed offset to resume from yet.
+    /// `"stored"` is not a legal `auto.offset.reset` value (that setting only
+    /// controls the no-committed-offset fallback, not the "resume from
+    /// stored" behavior itself, which the consumer group already does by
+    /// default) so `Stored` falls back to the earliest offset.
+    fn as_auto_offset_reset(&self) -> &'static str {
+        match self {
+            OffsetReset::Beginning | OffsetReset::Stored => "earliest",
+            OffsetReset::End => "latest",
+        }
+    }
+}
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum ConsumeOutputFormat {
+    /// Newline-delimited JSON (one object per line)
+    Ndjson,
+    /// JSON array of objects
+    JsonArray,
+}
+
 #[derive(Debug, Clone, clap::ValueEnum)]
 enum JsonFormat {
     /// Automatically detect format
@@ -134,10 +311,133 @@ enum JsonFormat {
     Ndjson,
 }
 
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum Compression {
+    None,
+    Gzip,
+    Snappy,
+    Lz4,
+    Zstd,
+}
+
+impl Compression {
+    /// librdkafka's `compression.codec` value for this variant
+    fn as_codec(&self) -> &'static str {
+        match self {
+            Compression::None => "none",
+            Compression::Gzip => "gzip",
+            Compression::Snappy => "snappy",
+            Compression::Lz4 => "lz4",
+            Compression::Zstd => "zstd",
+        }
+    }
+}
+
+/// Parses a `-X key=value` producer property
+fn parse_key_val(raw: &str) -> Result<(String, String)> {
+    let (key, value) = raw
+        .split_once('=')
+        .with_context(|| format!("invalid -X value '{raw}', expected key=value"))?;
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// One piece of a compiled `--topic-template`: either literal text copied
+/// through as-is, or a `{pointer}` placeholder resolved against each message
+enum TopicTemplateSegment {
+    Literal(String),
+    Pointer(String),
+}
+
+/// Splits a topic template like `events.{/event/type}` into literal and
+/// `{json-pointer}` placeholder segments
+fn compile_topic_template(template: &str) -> Result<Vec<TopicTemplateSegment>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            segments.push(TopicTemplateSegment::Literal(rest[..start].to_string()));
+        }
+        let after_brace = &rest[start + 1..];
+        let end = after_brace
+            .find('}')
+            .with_context(|| format!("unterminated '{{' in topic template: {template}"))?;
+        segments.push(TopicTemplateSegment::Pointer(after_brace[..end].to_string()));
+        rest = &after_brace[end + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(TopicTemplateSegment::Literal(rest.to_string()));
+    }
+
+    Ok(segments)
+}
+
+/// Routes each message to a destination topic: renders `--topic-template`
+/// against the message if one is configured, falling back to the static
+/// `--topic` when the template is unset or a placeholder's pointer doesn't
+/// resolve. Rendered topic strings are cached by the resolved placeholder
+/// values so repeated values (e.g. the same `event.type` across a batch)
+/// aren't re-rendered on every message.
+struct TopicRouter {
+    default_topic: String,
+    template: Option<Vec<TopicTemplateSegment>>,
+    cache: std::collections::HashMap<Vec<String>, String>,
+}
+
+impl TopicRouter {
+    fn new(default_topic: String, template: Option<&str>) -> Result<Self> {
+        let template = template.map(compile_topic_template).transpose()?;
+        Ok(Self {
+            default_topic,
+            template,
+            cache: std::collections::HashMap::new(),
+        })
+    }
+
+    fn topic_for(&mut self, message: &Value) -> &str {
+        let Some(segments) = &self.template else {
+            return &self.default_topic;
+        };
+
+        let mut placeholder_values = Vec::new();
+        for segment in segments {
+            if let TopicTemplateSegment::Pointer(pointer) = segment {
+                match message.pointer(pointer) {
+                    Some(Value::String(s)) => placeholder_values.push(s.clone()),
+                    Some(Value::Null) | None => {
+                        warn!(
+                            "topic template pointer '{pointer}' not found in message, falling back to default topic '{}'",
+                            self.default_topic
+                        );
+                        return &self.default_topic;
+                    }
+                    Some(value) => placeholder_values.push(value.to_string()),
+                }
+            }
+        }
+
+        if !self.cache.contains_key(&placeholder_values) {
+            let mut rendered = String::new();
+            let mut next_placeholder = placeholder_values.iter();
+            for segment in segments {
+                match segment {
+                    TopicTemplateSegment::Literal(literal) => rendered.push_str(literal),
+                    TopicTemplateSegment::Pointer(_) => {
+                        rendered.push_str(next_placeholder.next().expect("one value per pointer segment"))
+                    }
+                }
+            }
+            self.cache.insert(placeholder_values.clone(), rendered);
+        }
+        self.cache.get(&placeholder_values).expect("just inserted")
+    }
+}
+
 struct ProducerStats {
     total_messages: usize,
     successful_messages: usize,
     failed_messages: usize,
+    dlq_messages: usize,
     total_bytes: usize,
 }
 
@@ -147,6 +447,7 @@ impl ProducerStats {
             total_messages: 0,
             successful_messages: 0,
             failed_messages: 0,
+            dlq_messages: 0,
             total_bytes: 0,
         }
     }
@@ -156,6 +457,7 @@ impl ProducerStats {
         info!("  Total messages: {}", self.total_messages);
         info!("  Successful: {}", self.successful_messages);
         info!("  Failed: {}", self.failed_messages);
+        info!("  Dead-lettered: {}", self.dlq_messages);
         info!("  Total bytes sent: {}", self.total_bytes);
     }
 }
@@ -168,14 +470,38 @@ async fn main() -> Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    let args = Args::parse();
+    match Cli::parse().command {
+        Command::Produce(args) => run_produce(args).await,
+        Command::Consume(args) => run_consume(args).await,
+    }
+}
+
+async fn run_produce(args: ProduceArgs) -> Result<()> {
     info!("Starting Kafka producer with args: {:?}", args);
 
     // Create Kafka producer
-    let producer: FutureProducer = ClientConfig::new()
+    let mut client_config = ClientConfig::new();
+    client_config
         .set("bootstrap.servers", &args.brokers)
         .set("client.id", &args.client_id)
         .set("message.timeout.ms", "30000")
+        .set("compression.codec", args.compression.as_codec());
+    if let Some(linger_ms) = args.linger_ms {
+        client_config.set("linger.ms", linger_ms.to_string());
+    }
+    if let Some(batch_num_messages) = args.batch_num_messages {
+        client_config.set("batch.num.messages", batch_num_messages.to_string());
+    }
+    if let Some(acks) = &args.acks {
+        client_config.set("acks", acks);
+    }
+    // Applied last so -X can override any of the flags set above, including
+    // bootstrap.servers/client.id/compression.codec if a user really wants to.
+    for (key, value) in &args.extra_config {
+        client_config.set(key, value);
+    }
+
+    let producer: FutureProducer = client_config
         .create()
         .context("Failed to create Kafka producer")?;
 
@@ -188,6 +514,22 @@ async fn main() -> Result<()> {
 
     info!("Found {} JSON files to process", json_files.len());
 
+    let key_pointers: Vec<String> = args
+        .key_field
+        .as_deref()
+        .map(|raw| raw.split(&args.key_separator).filter(|p| !p.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default();
+
+    let mut router = TopicRouter::new(args.topic.clone(), args.topic_template.as_deref())?;
+    let mut pipeline = SendPipeline::new(
+        args.max_in_flight,
+        args.max_retries,
+        args.retry_backoff_ms,
+        args.dlq_topic.clone(),
+        args.timestamp_field.clone(),
+        args.header_fields.clone(),
+    );
+
     let mut stats = ProducerStats::new();
 
     // Process each file
@@ -202,13 +544,16 @@ async fn main() -> Result<()> {
         match process_json_file(
             &producer,
             file_path,
-            &args.topic,
+            &mut router,
+            &mut pipeline,
             &args.format,
             args.delay_ms,
             args.batch_size,
             args.continue_on_error,
             args.dry_run,
             args.progress_interval,
+            &key_pointers,
+            &args.key_separator,
             &mut stats,
         )
         .await
@@ -225,6 +570,9 @@ async fn main() -> Result<()> {
         }
     }
 
+    // Wait for every remaining in-flight delivery before flushing/reporting
+    pipeline.drain(&producer, &mut stats).await?;
+
     // Flush any remaining messages
     if !args.dry_run {
         if let Err(e) = producer.flush(Duration::from_secs(10)) {
@@ -238,6 +586,75 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+async fn run_consume(args: ConsumeArgs) -> Result<()> {
+    info!("Starting Kafka consumer with args: {:?}", args);
+
+    let consumer: StreamConsumer = ClientConfig::new()
+        .set("bootstrap.servers", &args.brokers)
+        .set("group.id", &args.group_id)
+        .set("enable.auto.commit", "true")
+        .set("auto.offset.reset", args.offset.as_auto_offset_reset())
+        .create()
+        .context("Failed to create Kafka consumer")?;
+
+    consumer
+        .subscribe(&[args.topic.as_str()])
+        .with_context(|| format!("Failed to subscribe to topic: {}", args.topic))?;
+
+    info!(
+        "Consuming from topic '{}' (offset: {:?}, idle timeout: {}ms)",
+        args.topic, args.offset, args.idle_timeout_ms
+    );
+
+    let mut messages = Vec::new();
+    loop {
+        if args.max_messages.is_some_and(|max| messages.len() >= max) {
+            info!("Reached --max-messages ({}), stopping", messages.len());
+            break;
+        }
+
+        match tokio::time::timeout(Duration::from_millis(args.idle_timeout_ms), consumer.recv()).await {
+            Ok(Ok(message)) => match message.payload() {
+                Some(payload) => match serde_json::from_slice::<Value>(payload) {
+                    Ok(value) => messages.push(value),
+                    Err(e) => warn!("Skipping non-JSON message at offset {}: {}", message.offset(), e),
+                },
+                None => warn!("Skipping tombstone/empty message at offset {}", message.offset()),
+            },
+            Ok(Err(e)) => {
+                error!("Error while consuming: {}", e);
+                return Err(e.into());
+            }
+            Err(_) => {
+                info!("Idle timeout reached after {} messages, stopping", messages.len());
+                break;
+            }
+        }
+    }
+
+    write_consumed_messages(&args.output, &args.output_format, &messages)?;
+    info!("Wrote {} messages to {}", messages.len(), args.output.display());
+
+    Ok(())
+}
+
+/// Writes consumed messages out as NDJSON or a JSON array, per `format`
+fn write_consumed_messages(output: &Path, format: &ConsumeOutputFormat, messages: &[Value]) -> Result<()> {
+    let content = match format {
+        ConsumeOutputFormat::Ndjson => {
+            let mut content = String::new();
+            for message in messages {
+                content.push_str(&serde_json::to_string(message)?);
+                content.push('\n');
+            }
+            content
+        }
+        ConsumeOutputFormat::JsonArray => serde_json::to_string_pretty(messages)?,
+    };
+
+    std::fs::write(output, content).with_context(|| format!("Failed to write output file: {}", output.display()))
+}
+
 fn get_json_files(input: &Path, glob_pattern: &str) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
 
@@ -281,18 +698,22 @@ fn get_json_files(input: &Path, glob_pattern: &str) -> Result<Vec<PathBuf>> {
 async fn process_json_file(
     producer: &FutureProducer,
     file_path: &Path,
-    topic: &str,
+    router: &mut TopicRouter,
+    pipeline: &mut SendPipeline,
     format: &JsonFormat,
     delay_ms: u64,
     batch_size: usize,
     continue_on_error: bool,
     dry_run: bool,
     progress_interval: usize,
+    key_pointers: &[String],
+    key_separator: &str,
     stats: &mut ProducerStats,
 ) -> Result<()> {
     let file = File::open(file_path)
         .with_context(|| format!("Failed to open file: {}", file_path.display()))?;
     let reader = BufReader::new(file);
+    let source_file = file_path.display().to_string();
 
     // Detect format if auto
     let actual_format = if matches!(format, JsonFormat::Auto) {
@@ -309,58 +730,58 @@ async fn process_json_file(
             let content = std::fs::read_to_string(file_path)?;
             let value: Value = serde_json::from_str(&content)
                 .with_context(|| format!("Failed to parse JSON from {}", file_path.display()))?;
-            
-            send_message(producer, topic, &value, dry_run, stats).await?;
+
+            pipeline.enqueue(producer, router, &value, &source_file, 0, dry_run, key_pointers, key_separator, continue_on_error, stats).await?;
         }
         JsonFormat::Array => {
             // Read entire file as JSON array
             let content = std::fs::read_to_string(file_path)?;
             let array: Vec<Value> = serde_json::from_str(&content)
                 .with_context(|| format!("Failed to parse JSON array from {}", file_path.display()))?;
-            
+
             let mut batch = Vec::new();
             for (idx, value) in array.into_iter().enumerate() {
-                batch.push(value);
-                
+                batch.push((idx, value));
+
                 if batch.len() >= batch_size {
-                    send_batch(producer, topic, &batch, dry_run, delay_ms, stats).await?;
+                    send_batch(producer, router, pipeline, &source_file, &batch, dry_run, delay_ms, key_pointers, key_separator, continue_on_error, stats).await?;
                     batch.clear();
                 }
-                
+
                 if (idx + 1) % progress_interval == 0 {
                     info!("Progress: {} messages processed", idx + 1);
                 }
             }
-            
+
             // Send remaining messages
             if !batch.is_empty() {
-                send_batch(producer, topic, &batch, dry_run, delay_ms, stats).await?;
+                send_batch(producer, router, pipeline, &source_file, &batch, dry_run, delay_ms, key_pointers, key_separator, continue_on_error, stats).await?;
             }
         }
         JsonFormat::Ndjson | JsonFormat::Auto => {
             // Process line by line
             let mut batch = Vec::new();
             let mut line_num = 0;
-            
+
             for line in reader.lines() {
                 line_num += 1;
                 let line = line?;
                 let trimmed = line.trim();
-                
+
                 // Skip empty lines
                 if trimmed.is_empty() {
                     continue;
                 }
-                
+
                 match serde_json::from_str::<Value>(trimmed) {
                     Ok(value) => {
-                        batch.push(value);
-                        
+                        batch.push((line_num, value));
+
                         if batch.len() >= batch_size {
-                            send_batch(producer, topic, &batch, dry_run, delay_ms, stats).await?;
+                            send_batch(producer, router, pipeline, &source_file, &batch, dry_run, delay_ms, key_pointers, key_separator, continue_on_error, stats).await?;
                             batch.clear();
                         }
-                        
+
                         if stats.total_messages % progress_interval == 0 && stats.total_messages > 0 {
                             info!("Progress: {} messages processed", stats.total_messages);
                         }
@@ -374,10 +795,10 @@ async fn process_json_file(
                     }
                 }
             }
-            
+
             // Send remaining messages
             if !batch.is_empty() {
-                send_batch(producer, topic, &batch, dry_run, delay_ms, stats).await?;
+                send_batch(producer, router, pipeline, &source_file, &batch, dry_run, delay_ms, key_pointers, key_separator, continue_on_error, stats).await?;
             }
         }
     }
@@ -387,15 +808,20 @@ async fn process_json_file(
 
 async fn send_batch(
     producer: &FutureProducer,
-    topic: &str,
-    messages: &[Value],
+    router: &mut TopicRouter,
+    pipeline: &mut SendPipeline,
+    source_file: &str,
+    messages: &[(usize, Value)],
     dry_run: bool,
     delay_ms: u64,
+    key_pointers: &[String],
+    key_separator: &str,
+    continue_on_error: bool,
     stats: &mut ProducerStats,
 ) -> Result<()> {
-    for message in messages {
-        send_message(producer, topic, message, dry_run, stats).await?;
-        
+    for (record_index, message) in messages {
+        pipeline.enqueue(producer, router, message, source_file, *record_index, dry_run, key_pointers, key_separator, continue_on_error, stats).await?;
+
         if delay_ms > 0 {
             sleep(Duration::from_millis(delay_ms)).await;
         }
@@ -403,41 +829,323 @@ async fn send_batch(
     Ok(())
 }
 
-async fn send_message(
-    producer: &FutureProducer,
-    topic: &str,
-    message: &Value,
-    dry_run: bool,
-    stats: &mut ProducerStats,
-) -> Result<()> {
-    let payload = serde_json::to_string(message)?;
-    let payload_bytes = payload.as_bytes();
-    
-    stats.total_messages += 1;
-    stats.total_bytes += payload_bytes.len();
-    
-    if dry_run {
-        info!("DRY RUN: Would send message to topic '{}': {}", topic, payload);
-        stats.successful_messages += 1;
-        return Ok(());
+/// Extracts this message's Kafka record key from `key_pointers` (JSON
+/// pointers like `/user/id`), joining multiple pointers with
+/// `key_separator` for a composite key. Falls back to the empty key
+/// (round-robin partitioning) if `key_pointers` is empty, or if a pointer
+/// doesn't resolve and `continue_on_error` is set; otherwise an
+/// unresolved pointer is an error.
+fn resolve_message_key(message: &Value, key_pointers: &[String], key_separator: &str, continue_on_error: bool) -> Result<String> {
+    if key_pointers.is_empty() {
+        return Ok(String::new());
     }
-    
-    let record = FutureRecord::to(topic)
-        .payload(payload_bytes)
-        .key("");
-    
-    match producer.send(record, Duration::from_secs(0)).await {
-        Ok(_) => {
+
+    let mut parts = Vec::with_capacity(key_pointers.len());
+    for pointer in key_pointers {
+        match message.pointer(pointer) {
+            Some(Value::String(s)) => parts.push(s.clone()),
+            Some(Value::Null) | None => {
+                if continue_on_error {
+                    warn!("key field '{pointer}' not found in message, falling back to empty key");
+                    return Ok(String::new());
+                }
+                anyhow::bail!("key field '{pointer}' not found in message");
+            }
+            Some(value) => parts.push(value.to_string()),
+        }
+    }
+    Ok(parts.join(key_separator))
+}
+
+/// Resolves `timestamp_field`'s pointer against `message` into epoch
+/// milliseconds, accepting either a numeric epoch-millis value or an
+/// RFC3339 string. Returns `None` (falling back to the broker/client's own
+/// timestamp assignment) if the field is unset, unresolved, or unparseable.
+fn resolve_message_timestamp(message: &Value, timestamp_field: Option<&str>) -> Option<i64> {
+    let pointer = timestamp_field?;
+    match message.pointer(pointer)? {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => chrono::DateTime::parse_from_rfc3339(s)
+            .ok()
+            .map(|dt| dt.timestamp_millis()),
+        _ => None,
+    }
+}
+
+/// Builds Kafka message headers from `header_fields` (name, JSON pointer)
+/// pairs, resolving each pointer against `message`. A pointer that doesn't
+/// resolve simply omits that header rather than erroring, since headers are
+/// supplementary metadata. Returns `None` if no fields are configured.
+fn build_message_headers(message: &Value, header_fields: &[(String, String)]) -> Option<OwnedHeaders> {
+    if header_fields.is_empty() {
+        return None;
+    }
+
+    let mut headers = OwnedHeaders::new();
+    for (name, pointer) in header_fields {
+        match message.pointer(pointer) {
+            Some(Value::Null) | None => {}
+            Some(value) => {
+                let header_value = match value {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                headers = headers.insert(Header {
+                    key: name.as_str(),
+                    value: Some(header_value.as_str()),
+                });
+            }
+        }
+    }
+    Some(headers)
+}
+
+/// What's needed to retry or dead-letter a message if its delivery
+/// ultimately fails: the original payload plus where it came from, for the
+/// DLQ envelope
+struct PendingSend {
+    message: Value,
+    source_file: String,
+    record_index: usize,
+}
+
+/// A pending delivery alongside everything [`SendPipeline`] needs to retry
+/// or dead-letter it if that delivery fails
+struct InFlightSend {
+    future: DeliveryFuture,
+    pending: PendingSend,
+    topic: String,
+    key: String,
+}
+
+/// Enqueues messages onto a producer without waiting for each delivery,
+/// capping how many produce requests are outstanding at once
+/// (`--max-in-flight`), and retries/dead-letters deliveries that ultimately
+/// fail (`--max-retries`/`--retry-backoff-ms`/`--dlq-topic`)
+struct SendPipeline {
+    max_in_flight: usize,
+    max_retries: u32,
+    retry_backoff_ms: u64,
+    dlq_topic: Option<String>,
+    timestamp_field: Option<String>,
+    header_fields: Vec<(String, String)>,
+    in_flight: Vec<InFlightSend>,
+}
+
+impl SendPipeline {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        max_in_flight: usize,
+        max_retries: u32,
+        retry_backoff_ms: u64,
+        dlq_topic: Option<String>,
+        timestamp_field: Option<String>,
+        header_fields: Vec<(String, String)>,
+    ) -> Self {
+        Self {
+            max_in_flight,
+            max_retries,
+            retry_backoff_ms,
+            dlq_topic,
+            timestamp_field,
+            header_fields,
+            in_flight: Vec::new(),
+        }
+    }
+
+    /// Enqueues `message` onto `producer` without waiting for delivery, then
+    /// drains outstanding deliveries once `max_in_flight` is reached.
+    /// `total_messages`/`total_bytes` are counted here at enqueue time for
+    /// progress reporting; success/failure/dlq counts are tallied later, once
+    /// each delivery resolves, by [`SendPipeline::drain`] — except for a
+    /// synchronous enqueue-time failure (anything other than `QueueFull`,
+    /// which is retried in place below), which goes straight through
+    /// [`SendPipeline::retry_or_dead_letter`] itself, since there's no
+    /// delivery future for `drain` to ever see.
+    #[allow(clippy::too_many_arguments)]
+    async fn enqueue(
+        &mut self,
+        producer: &FutureProducer,
+        router: &mut TopicRouter,
+        message: &Value,
+        source_file: &str,
+        record_index: usize,
+        dry_run: bool,
+        key_pointers: &[String],
+        key_separator: &str,
+        continue_on_error: bool,
+        stats: &mut ProducerStats,
+    ) -> Result<()> {
+        let payload = serde_json::to_string(message)?;
+        let payload_bytes = payload.as_bytes();
+
+        stats.total_messages += 1;
+        stats.total_bytes += payload_bytes.len();
+
+        let key = resolve_message_key(message, key_pointers, key_separator, continue_on_error)?;
+        let topic = router.topic_for(message).to_string();
+
+        if dry_run {
+            info!("DRY RUN: Would send message to topic '{}' with key '{}': {}", topic, key, payload);
             stats.successful_messages += 1;
-            Ok(())
+            return Ok(());
+        }
+
+        let mut record = FutureRecord::to(&topic).payload(payload_bytes).key(&key);
+        if let Some(timestamp_ms) = resolve_message_timestamp(message, self.timestamp_field.as_deref()) {
+            record = record.timestamp(timestamp_ms);
         }
-        Err((e, _)) => {
-            stats.failed_messages += 1;
-            Err(anyhow::anyhow!("Failed to send message: {}", e))
+        if let Some(headers) = build_message_headers(message, &self.header_fields) {
+            record = record.headers(headers);
+        }
+        loop {
+            match producer.send_result(record) {
+                Ok(future) => {
+                    self.in_flight.push(InFlightSend {
+                        future,
+                        pending: PendingSend {
+                            message: message.clone(),
+                            source_file: source_file.to_string(),
+                            record_index,
+                        },
+                        topic,
+                        key,
+                    });
+                    break;
+                }
+                Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), returned_record)) => {
+                    warn!("producer queue full, backing off before retrying enqueue");
+                    sleep(Duration::from_millis(50)).await;
+                    record = returned_record;
+                }
+                Err((e, _)) => {
+                    warn!("failed to enqueue message to topic '{topic}': {e}");
+                    let pending = PendingSend {
+                        message: message.clone(),
+                        source_file: source_file.to_string(),
+                        record_index,
+                    };
+                    return self.retry_or_dead_letter(producer, pending, &topic, &key, e.to_string(), stats).await;
+                }
+            }
+        }
+
+        if self.in_flight.len() >= self.max_in_flight {
+            self.drain(producer, stats).await?;
+        }
+        Ok(())
+    }
+
+    /// Awaits every pending delivery, tallying `successful_messages` from the
+    /// resolved results and routing failures through
+    /// [`SendPipeline::retry_or_dead_letter`], then clears the in-flight list
+    async fn drain(&mut self, producer: &FutureProducer, stats: &mut ProducerStats) -> Result<()> {
+        if self.in_flight.is_empty() {
+            return Ok(());
+        }
+
+        let pending = self.in_flight.drain(..).collect::<Vec<_>>();
+        let resolved = join_all(pending.into_iter().map(|send| async move {
+            let result = send.future.await;
+            (result, send.pending, send.topic, send.key)
+        }))
+        .await;
+
+        for (result, pending_send, topic, key) in resolved {
+            match result {
+                Ok(Ok(_)) => stats.successful_messages += 1,
+                Ok(Err((e, _))) => {
+                    warn!("Failed to deliver message: {}", e);
+                    self.retry_or_dead_letter(producer, pending_send, &topic, &key, e.to_string(), stats).await?;
+                }
+                Err(_) => {
+                    warn!("Delivery future cancelled");
+                    self.retry_or_dead_letter(producer, pending_send, &topic, &key, "delivery future cancelled".to_string(), stats).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Retries a failed delivery up to `max_retries` times with exponential
+    /// backoff; if it still fails, publishes it to `dlq_topic` (if set) and
+    /// counts it as failed
+    async fn retry_or_dead_letter(
+        &self,
+        producer: &FutureProducer,
+        pending: PendingSend,
+        topic: &str,
+        key: &str,
+        initial_error: String,
+        stats: &mut ProducerStats,
+    ) -> Result<()> {
+        let mut last_error = initial_error;
+        for attempt in 1..=self.max_retries {
+            let backoff = self.retry_backoff_ms.saturating_mul(2u64.saturating_pow(attempt - 1));
+            warn!(
+                "retrying delivery to '{topic}' (attempt {attempt}/{}) after {backoff}ms: {last_error}",
+                self.max_retries
+            );
+            sleep(Duration::from_millis(backoff)).await;
+
+            let payload = serde_json::to_string(&pending.message)?;
+            let mut record = FutureRecord::to(topic).payload(payload.as_bytes()).key(key);
+            if let Some(timestamp_ms) = resolve_message_timestamp(&pending.message, self.timestamp_field.as_deref()) {
+                record = record.timestamp(timestamp_ms);
+            }
+            if let Some(headers) = build_message_headers(&pending.message, &self.header_fields) {
+                record = record.headers(headers);
+            }
+            match producer.send(record, Duration::from_secs(0)).await {
+                Ok(_) => {
+                    stats.successful_messages += 1;
+                    return Ok(());
+                }
+                Err((e, _)) => last_error = e.to_string(),
+            }
+        }
+
+        stats.failed_messages += 1;
+        self.publish_to_dlq(producer, &pending, &last_error, stats).await
+    }
+
+    /// Wraps `pending` in an envelope recording the source file, record
+    /// index, error, and timestamp, and republishes it to `dlq_topic` so a
+    /// bad-record run can be reprocessed later instead of silently dropping
+    /// the message. A no-op if `--dlq-topic` wasn't set.
+    async fn publish_to_dlq(&self, producer: &FutureProducer, pending: &PendingSend, error: &str, stats: &mut ProducerStats) -> Result<()> {
+        let Some(dlq_topic) = &self.dlq_topic else {
+            return Ok(());
+        };
+
+        let envelope = serde_json::json!({
+            "source_file": pending.source_file,
+            "record_index": pending.record_index,
+            "error": error,
+            "timestamp_ms": current_timestamp_ms(),
+            "payload": pending.message,
+        });
+        let payload = serde_json::to_string(&envelope)?;
+        let record = FutureRecord::to(dlq_topic).payload(payload.as_bytes());
+
+        match producer.send(record, Duration::from_secs(0)).await {
+            Ok(_) => {
+                stats.dlq_messages += 1;
+                Ok(())
+            }
+            Err((e, _)) => Err(anyhow::anyhow!("Failed to publish to dead-letter topic '{dlq_topic}': {}", e)),
         }
     }
 }
 
+/// Current wall-clock time in epoch milliseconds, for DLQ envelope timestamps
+fn current_timestamp_ms() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
 fn detect_json_format(file_path: &Path) -> Result<JsonFormat> {
     let file = File::open(file_path)?;
     let mut reader = BufReader::new(file);
@@ -470,3 +1178,132 @@ fn detect_json_format(file_path: &Path) -> Result<JsonFormat> {
     
     Ok(JsonFormat::Ndjson)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdkafka::message::Headers;
+    use serde_json::json;
+
+    #[test]
+    fn resolve_message_key_is_empty_with_no_key_pointers() {
+        let message = json!({"id": "abc"});
+        assert_eq!(resolve_message_key(&message, &[], ".", false).unwrap(), "");
+    }
+
+    #[test]
+    fn resolve_message_key_joins_multiple_pointers_with_the_separator() {
+        let message = json!({"user": {"id": "42"}, "event": "click"});
+        let pointers = vec!["/user/id".to_string(), "/event".to_string()];
+        assert_eq!(resolve_message_key(&message, &pointers, "-", false).unwrap(), "42-click");
+    }
+
+    #[test]
+    fn resolve_message_key_stringifies_non_string_values() {
+        let message = json!({"id": 42});
+        let pointers = vec!["/id".to_string()];
+        assert_eq!(resolve_message_key(&message, &pointers, ".", false).unwrap(), "42");
+    }
+
+    #[test]
+    fn resolve_message_key_errors_on_an_unresolved_pointer_by_default() {
+        let message = json!({"id": "abc"});
+        let pointers = vec!["/missing".to_string()];
+        assert!(resolve_message_key(&message, &pointers, ".", false).is_err());
+    }
+
+    #[test]
+    fn resolve_message_key_errors_on_a_null_pointer_value_by_default() {
+        let message = json!({"id": null});
+        let pointers = vec!["/id".to_string()];
+        assert!(resolve_message_key(&message, &pointers, ".", false).is_err());
+    }
+
+    #[test]
+    fn resolve_message_key_falls_back_to_empty_when_continue_on_error_is_set() {
+        let message = json!({"id": "abc"});
+        let pointers = vec!["/missing".to_string()];
+        assert_eq!(resolve_message_key(&message, &pointers, ".", true).unwrap(), "");
+    }
+
+    #[test]
+    fn resolve_message_timestamp_is_none_with_no_timestamp_field() {
+        let message = json!({"ts": 1_700_000_000_000i64});
+        assert_eq!(resolve_message_timestamp(&message, None), None);
+    }
+
+    #[test]
+    fn resolve_message_timestamp_reads_an_epoch_millis_number() {
+        let message = json!({"ts": 1_700_000_000_000i64});
+        assert_eq!(resolve_message_timestamp(&message, Some("/ts")), Some(1_700_000_000_000));
+    }
+
+    #[test]
+    fn resolve_message_timestamp_parses_an_rfc3339_string() {
+        let message = json!({"ts": "2023-11-14T22:13:20+00:00"});
+        assert_eq!(resolve_message_timestamp(&message, Some("/ts")), Some(1_700_000_000_000));
+    }
+
+    #[test]
+    fn resolve_message_timestamp_is_none_for_an_unparseable_or_unresolved_field() {
+        let message = json!({"ts": "not a timestamp"});
+        assert_eq!(resolve_message_timestamp(&message, Some("/ts")), None);
+        assert_eq!(resolve_message_timestamp(&message, Some("/missing")), None);
+    }
+
+    #[test]
+    fn build_message_headers_is_none_with_no_header_fields() {
+        let message = json!({"name": "alice"});
+        assert!(build_message_headers(&message, &[]).is_none());
+    }
+
+    #[test]
+    fn build_message_headers_resolves_each_configured_pointer() {
+        let message = json!({"user": {"name": "alice"}, "count": 3});
+        let fields = vec![("x-user".to_string(), "/user/name".to_string()), ("x-count".to_string(), "/count".to_string())];
+        let headers = build_message_headers(&message, &fields).unwrap();
+        assert_eq!(headers.count(), 2);
+        assert_eq!(headers.get(0).key, "x-user");
+        assert_eq!(headers.get(0).value, Some(b"alice".as_slice()));
+        assert_eq!(headers.get(1).key, "x-count");
+        assert_eq!(headers.get(1).value, Some(b"3".as_slice()));
+    }
+
+    #[test]
+    fn build_message_headers_omits_a_null_or_unresolved_pointer() {
+        let message = json!({"name": "alice", "missing_field": null});
+        let fields = vec![
+            ("x-name".to_string(), "/name".to_string()),
+            ("x-missing".to_string(), "/missing_field".to_string()),
+            ("x-absent".to_string(), "/absent".to_string()),
+        ];
+        let headers = build_message_headers(&message, &fields).unwrap();
+        assert_eq!(headers.count(), 1);
+        assert_eq!(headers.get(0).key, "x-name");
+    }
+
+    #[test]
+    fn topic_router_uses_the_default_topic_with_no_template() {
+        let mut router = TopicRouter::new("default-topic".to_string(), None).unwrap();
+        assert_eq!(router.topic_for(&json!({"event": {"type": "click"}})), "default-topic");
+    }
+
+    #[test]
+    fn topic_router_renders_the_template_against_each_message() {
+        let mut router = TopicRouter::new("default-topic".to_string(), Some("events.{/event/type}")).unwrap();
+        assert_eq!(router.topic_for(&json!({"event": {"type": "click"}})), "events.click");
+        assert_eq!(router.topic_for(&json!({"event": {"type": "view"}})), "events.view");
+    }
+
+    #[test]
+    fn topic_router_falls_back_to_the_default_topic_on_a_null_pointer_value() {
+        let mut router = TopicRouter::new("default-topic".to_string(), Some("events.{/event/type}")).unwrap();
+        assert_eq!(router.topic_for(&json!({"event": {"type": null}})), "default-topic");
+    }
+
+    #[test]
+    fn topic_router_falls_back_to_the_default_topic_on_an_unresolved_pointer() {
+        let mut router = TopicRouter::new("default-topic".to_string(), Some("events.{/event/type}")).unwrap();
+        assert_eq!(router.topic_for(&json!({"other": "field"})), "default-topic");
+    }
+}
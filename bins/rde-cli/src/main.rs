@@ -36,13 +36,13 @@
 //!
 //! See the `examples/` directory for sample pipeline configurations.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use datafusion::arrow::datatypes::SchemaRef;
 use clap::Parser;
 use rde_core::PipelineSpec;
 use rde_core::SourceSpec;
 use glob;
-use rde_io::{sink_parquet::ParquetDirSink, sink_stdout::StdoutSink, sink_iceberg::IcebergSink, source_csv::CsvSource, source_kafka::KafkaPipelineSource};
+use rde_io::{sink_parquet::ParquetDirSink, sink_stdout::StdoutSink, sink_iceberg::IcebergSink, source_csv::CsvSource, source_kafka::KafkaPipelineSource, source_http::HttpSource, source_websocket::WebSocketSource};
 use rde_tx::create_transform;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -84,6 +84,45 @@ struct Args {
     #[arg(help = "Buffer size for operator message channels")]
     channel_capacity: usize,
 }
+/// Builds the span report for one finished `Source`/`Transform`/`Sink::run`
+/// invocation from its adjacent edges' counters and reports it: published to
+/// Kafka if `reporter` is configured, logged through `tracing` either way.
+fn finish_span(
+    reporter: &Option<Arc<rde_io::trace::TraceReporter>>,
+    trace_id: Arc<str>,
+    operator: String,
+    kind: &'static str,
+    started: std::time::Instant,
+    input: Option<Arc<rde_io::trace::ChannelCounters>>,
+    output: Option<Arc<rde_io::trace::ChannelCounters>>,
+) {
+    use std::sync::atomic::Ordering;
+
+    let max_backpressure = [&input, &output]
+        .into_iter()
+        .flatten()
+        .map(|c| c.max_backpressure.load(Ordering::Relaxed))
+        .max()
+        .unwrap_or(0);
+
+    let report = rde_io::trace::OperatorSpanReport {
+        trace_id,
+        operator,
+        kind,
+        duration_ms: started.elapsed().as_millis() as u64,
+        input_batches: input.as_ref().map_or(0, |c| c.batches.load(Ordering::Relaxed)),
+        input_rows: input.as_ref().map_or(0, |c| c.rows.load(Ordering::Relaxed)),
+        output_batches: output.as_ref().map_or(0, |c| c.batches.load(Ordering::Relaxed)),
+        output_rows: output.as_ref().map_or(0, |c| c.rows.load(Ordering::Relaxed)),
+        max_backpressure,
+    };
+
+    match reporter {
+        Some(reporter) => reporter.report(report),
+        None => rde_io::trace::log_span(&report),
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::registry()
@@ -99,7 +138,18 @@ async fn main() -> Result<()> {
 
     // v0: assume single source -> transforms -> single sink
     let cancel = CancellationToken::new();
-    
+
+    // One trace id per pipeline run, stamped onto every batch as it crosses
+    // its first edge (see `rde_io::trace`) so every operator's spans and
+    // every downstream batch can be correlated back to this run.
+    let trace_id: Arc<str> = Arc::from(uuid::Uuid::new_v4().to_string());
+    let trace_reporter: Option<Arc<rde_io::trace::TraceReporter>> = spec
+        .tracing
+        .as_ref()
+        .map(|tracing_spec| rde_io::trace::TraceReporter::spawn(tracing_spec, cancel.child_token()))
+        .transpose()?
+        .map(Arc::new);
+
     // Create channels for the pipeline
     // We need: source -> transform1 -> transform2 -> ... -> sink
     // For n transforms, we need n+1 channels total:
@@ -112,6 +162,38 @@ async fn main() -> Result<()> {
         channels.push(mpsc::channel(args.channel_capacity));
     }
 
+    // Edges correspond positionally to `channels`: source->transform1,
+    // transform1->transform2, ..., transformN->sink. An edge with
+    // `capture_to` set gets its channel replaced with a teed pair that also
+    // publishes every message to that Kafka topic.
+    for (i, channel) in channels.iter_mut().enumerate() {
+        let Some(edge) = spec.edges.get(i) else { continue };
+        let Some(topic) = &edge.capture_to else { continue };
+        let brokers = spec
+            .capture_brokers
+            .as_ref()
+            .context("edge has capture_to set but pipeline.capture_brokers is missing")?;
+        let (tap_tx, out_rx) =
+            rde_io::capture::spawn_tee(brokers.clone(), topic.clone(), args.channel_capacity, cancel.child_token())?;
+        *channel = (tap_tx, out_rx);
+    }
+
+    // Every edge is also tapped for tracing metrics (batch/row counts and
+    // observed backpressure), regardless of whether it captures to Kafka.
+    let mut edge_counters = channels
+        .iter_mut()
+        .map(|channel| {
+            let (tap_tx, out_rx, counters) = rde_io::trace::spawn_metrics_tee(
+                trace_id.clone(),
+                args.channel_capacity,
+                cancel.child_token(),
+            );
+            *channel = (tap_tx, out_rx);
+            counters
+        })
+        .collect::<Vec<_>>()
+        .into_iter();
+
     // Infer schema upfront so we can pass it to transform and sink
     let schema: SchemaRef = match &spec.sources[0] {
         SourceSpec::Csv(csv) => {
@@ -204,12 +286,58 @@ async fn main() -> Result<()> {
                 Arc::new(datafusion::arrow::datatypes::Schema::empty())
             }
         }
+        SourceSpec::Http(_) => {
+            // HTTP events arrive schema-less; the first flushed batch establishes it
+            Arc::new(datafusion::arrow::datatypes::Schema::empty())
+        }
+        SourceSpec::WebSocket(_) => {
+            // WebSocket frames arrive schema-less; the first flushed batch establishes it
+            Arc::new(datafusion::arrow::datatypes::Schema::empty())
+        }
+        SourceSpec::Replay(_) => {
+            // A replayed batch carries its own Arrow IPC schema; the first one establishes it
+            Arc::new(datafusion::arrow::datatypes::Schema::empty())
+        }
+        SourceSpec::Iceberg(iceberg) => rde_io::source_iceberg::resolve_schema(iceberg).await?,
     };
     
+    // Optional Arrow Flight server tapping the Kafka source's output; only
+    // wired up when the pipeline spec asks for it and the source is Kafka
+    let flight_server: Option<(std::net::SocketAddr, String, rde_io::flight_server::FlightTap)> =
+        match (&spec.flight, &spec.sources[0]) {
+            (Some(flight), SourceSpec::Kafka(kafka)) => {
+                let addr: std::net::SocketAddr = flight
+                    .bind_addr
+                    .parse()
+                    .context("invalid flight.bind_addr")?;
+                Some((addr, kafka.topic.clone(), rde_io::flight_server::FlightTap::new()))
+            }
+            (Some(_), _) => {
+                anyhow::bail!("flight server is only supported for Kafka sources");
+            }
+            (None, _) => None,
+        };
+
     // Build source from spec with the inferred schema
     let mut source: Box<dyn rde_core::Source> = match &spec.sources[0] {
-        SourceSpec::Csv(csv) => Box::new(CsvSource::try_new(csv.clone())?.with_schema(schema.clone())),
-        SourceSpec::Kafka(kafka) => Box::new(KafkaPipelineSource::new(kafka.clone()).with_schema(schema.clone())),
+        SourceSpec::Csv(csv) => Box::new(CsvSource::try_new(csv.clone())?.with_schema(schema.clone())?),
+        SourceSpec::Kafka(kafka) => {
+            let mut kafka_source = KafkaPipelineSource::new(kafka.clone()).with_schema(schema.clone());
+            if let Some((_, _, tap)) = &flight_server {
+                kafka_source = kafka_source.with_flight_tap(tap.clone());
+            }
+            Box::new(kafka_source)
+        }
+        SourceSpec::Http(http) => Box::new(HttpSource::new(http.clone()).with_schema(schema.clone())),
+        SourceSpec::WebSocket(websocket) => {
+            Box::new(WebSocketSource::new(websocket.clone()).with_schema(schema.clone()))
+        }
+        SourceSpec::Replay(replay) => {
+            Box::new(rde_io::source_replay::ReplaySource::new(replay.clone()).with_schema(schema.clone()))
+        }
+        SourceSpec::Iceberg(iceberg) => Box::new(
+            rde_io::source_iceberg::IcebergSource::new(iceberg.clone()).with_schema(schema.clone()),
+        ),
     };
 
     // Build transforms
@@ -224,58 +352,91 @@ async fn main() -> Result<()> {
 
     // Build sink with the final schema
     let mut sink: Box<dyn rde_core::Sink> = match &spec.sinks[0] {
-        rde_core::SinkSpec::Stdout { id } => Box::new(StdoutSink::new(id.clone(), current_schema.clone())),
-        rde_core::SinkSpec::ParquetDir { id, path } => Box::new(ParquetDirSink::new(
+        rde_core::SinkSpec::Stdout { id, format } => Box::new(StdoutSink::with_format(id.clone(), current_schema.clone(), *format)),
+        rde_core::SinkSpec::ParquetDir { id, path, writer } => Box::new(ParquetDirSink::with_writer_spec(
             id.clone(),
             PathBuf::from(path),
             current_schema.clone(),
+            writer.clone(),
         )),
-        rde_core::SinkSpec::Iceberg(iceberg) => Box::new(IcebergSink::new(
-            iceberg.id.clone(),
-            current_schema.clone(),
-            iceberg.table_name.clone(),
-            iceberg.bucket.clone(),
-            iceberg.endpoint.clone(),
-            iceberg.access_key.clone(),
-            iceberg.secret_key.clone(),
-            iceberg.region.clone(),
-        )),
+        rde_core::SinkSpec::Iceberg(iceberg) => Box::new(
+            IcebergSink::new(
+                iceberg.id.clone(),
+                current_schema.clone(),
+                iceberg.table_name.clone(),
+                iceberg.bucket.clone(),
+                iceberg.endpoint.clone(),
+                iceberg.access_key.clone(),
+                iceberg.secret_key.clone(),
+                iceberg.region.clone(),
+            )
+            .with_partition_spec(iceberg.partition_by.clone())
+            .with_catalog(iceberg.catalog.clone())
+            .with_upsert_keys(iceberg.upsert_keys.clone()),
+        ),
+        rde_core::SinkSpec::Kafka(kafka) => Box::new(rde_io::sink_kafka::KafkaSink::new(kafka.clone(), current_schema.clone())),
     };
 
     // Spawn tasks
     let mut handles = Vec::new();
-    
+
+    if let Some((addr, topic, tap)) = flight_server {
+        let c_flight = cancel.child_token();
+        let flight_handle = tokio::spawn(async move {
+            rde_io::flight_server::serve(addr, topic, tap, c_flight).await
+        });
+        handles.push(flight_handle);
+    }
+
     // Handle the case where there are no transforms
     if transforms.is_empty() {
         // Simple case: source -> sink
         let (source_tx, sink_rx) = channels.remove(0);
-        
+        let edge = edge_counters.next().unwrap();
+
         // Source task
         let c1 = cancel.child_token();
-        let src_handle = tokio::spawn(async move { 
-            source.run(source_tx, c1).await 
+        let src_name = source.name().to_string();
+        let (reporter, tid, edge_out) = (trace_reporter.clone(), trace_id.clone(), edge.clone());
+        let src_handle = tokio::spawn(async move {
+            let started = std::time::Instant::now();
+            let result = source.run(source_tx, c1).await;
+            finish_span(&reporter, tid, src_name, "source", started, None, Some(edge_out));
+            result
         });
         handles.push(src_handle);
 
         // Sink task
         let c_sink = cancel.child_token();
-        let sink_handle = tokio::spawn(async move { 
-            sink.run(sink_rx, c_sink).await 
+        let sink_name = sink.name().to_string();
+        let (reporter, tid) = (trace_reporter.clone(), trace_id.clone());
+        let sink_handle = tokio::spawn(async move {
+            let started = std::time::Instant::now();
+            let result = sink.run(sink_rx, c_sink).await;
+            finish_span(&reporter, tid, sink_name, "sink", started, Some(edge), None);
+            result
         });
         handles.push(sink_handle);
     } else {
         // Multi-transform case: source -> transform1 -> transform2 -> ... -> sink
-        
+
         // Source task: source -> transform1
         let (source_tx, transform1_rx) = channels.remove(0);
+        let source_out = edge_counters.next().unwrap();
         let c_source = cancel.child_token();
-        let src_handle = tokio::spawn(async move { 
-            source.run(source_tx, c_source).await 
+        let src_name = source.name().to_string();
+        let (reporter, tid, edge_out) = (trace_reporter.clone(), trace_id.clone(), source_out.clone());
+        let src_handle = tokio::spawn(async move {
+            let started = std::time::Instant::now();
+            let result = source.run(source_tx, c_source).await;
+            finish_span(&reporter, tid, src_name, "source", started, None, Some(edge_out));
+            result
         });
         handles.push(src_handle);
 
         // Transform tasks: transform1 -> transform2 -> ... -> transformN
         let mut current_rx = transform1_rx;
+        let mut current_in = source_out;
         let num_transforms = transforms.len();
         for (i, mut transform) in transforms.into_iter().enumerate() {
             let (transform_tx, next_rx) = if i == num_transforms - 1 {
@@ -285,20 +446,33 @@ async fn main() -> Result<()> {
                 // Intermediate transform: transformN -> transformN+1
                 channels.remove(0)
             };
-            
+            let next_out = edge_counters.next().unwrap();
+
             let c_transform = cancel.child_token();
-            let transform_handle = tokio::spawn(async move { 
-                transform.run(current_rx, transform_tx, c_transform).await 
+            let t_name = transform.name().to_string();
+            let (reporter, tid, edge_in, edge_out) =
+                (trace_reporter.clone(), trace_id.clone(), current_in.clone(), next_out.clone());
+            let transform_handle = tokio::spawn(async move {
+                let started = std::time::Instant::now();
+                let result = transform.run(current_rx, transform_tx, c_transform).await;
+                finish_span(&reporter, tid, t_name, "transform", started, Some(edge_in), Some(edge_out));
+                result
             });
             handles.push(transform_handle);
-            
+
             current_rx = next_rx;
+            current_in = next_out;
         }
 
         // Sink task: transformN -> sink
         let c_sink = cancel.child_token();
-        let sink_handle = tokio::spawn(async move { 
-            sink.run(current_rx, c_sink).await 
+        let sink_name = sink.name().to_string();
+        let (reporter, tid) = (trace_reporter.clone(), trace_id.clone());
+        let sink_handle = tokio::spawn(async move {
+            let started = std::time::Instant::now();
+            let result = sink.run(current_rx, c_sink).await;
+            finish_span(&reporter, tid, sink_name, "sink", started, Some(current_in), None);
+            result
         });
         handles.push(sink_handle);
     }
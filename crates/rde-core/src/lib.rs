@@ -33,6 +33,7 @@ use datafusion::arrow::array::RecordBatch;
 use datafusion::arrow::datatypes::SchemaRef;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
@@ -210,27 +211,84 @@ pub trait Sink: Operator {
 ///     id: "iceberg-sink"
 ///     table_name: "output_table"
 /// edges:
-///   - ["kafka-source", "passthrough"]
-///   - ["passthrough", "iceberg-sink"]
+///   - from: "kafka-source"
+///     to: "passthrough"
+///   - from: "passthrough"
+///     to: "iceberg-sink"
+///     capture_to: "passthrough-to-iceberg-capture"
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PipelineSpec {
     /// Human-readable name of the pipeline
     pub name: String,
-    
+
     /// List of data source specifications
     pub sources: Vec<SourceSpec>,
-    
-    /// List of data transformation specifications  
+
+    /// List of data transformation specifications
     pub transforms: Vec<TransformSpec>,
-    
+
     /// List of data sink specifications
     pub sinks: Vec<SinkSpec>,
-    
-    /// Directed edges defining data flow between operators
-    /// 
-    /// Each tuple represents (from_operator_id, to_operator_id)
-    pub edges: Vec<(String, String)>,
+
+    /// Directed edges defining data flow between operators, in source ->
+    /// transform(s) -> sink order
+    pub edges: Vec<EdgeSpec>,
+
+    /// Optional Arrow Flight server exposing a Kafka source's batches to
+    /// external Arrow-native clients, keyed by Kafka topic name
+    #[serde(default)]
+    pub flight: Option<FlightServerSpec>,
+
+    /// Kafka brokers used for any edge with `capture_to` set; required if
+    /// any edge captures, unused otherwise
+    #[serde(default)]
+    pub capture_brokers: Option<String>,
+
+    /// Optional distributed-tracing reporter. When set, the completed span
+    /// for every `Source`/`Transform`/`Sink::run` invocation (operator name,
+    /// input/output batch and row counts, throughput, and observed channel
+    /// backpressure) is published to a Kafka topic in the background, in
+    /// addition to the existing `tracing` subscriber. When unset, spans
+    /// still surface through `tracing` alone.
+    #[serde(default)]
+    pub tracing: Option<TracingSpec>,
+}
+
+/// Configuration for the optional Kafka-based span reporter (see
+/// [`PipelineSpec::tracing`])
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TracingSpec {
+    /// Kafka brokers to publish span reports to
+    pub brokers: String,
+
+    /// Topic that completed operator span reports are published to
+    pub kafka_topic: String,
+}
+
+/// A directed edge between two operators, naming the upstream operator's
+/// `id` and the downstream operator's `id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeSpec {
+    /// `id` of the upstream operator this edge reads from
+    pub from: String,
+
+    /// `id` of the downstream operator this edge feeds into
+    pub to: String,
+
+    /// When set, every message flowing over this edge is also published to
+    /// this Kafka topic (see [`PipelineSpec::capture_brokers`]), so it can
+    /// later be deterministically replayed via `SourceSpec::Replay`
+    #[serde(default)]
+    pub capture_to: Option<String>,
+}
+
+/// Configuration for the optional Arrow Flight server that lets external
+/// clients pull `KafkaPipelineSource` batches directly as a Flight stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlightServerSpec {
+    /// Address to bind the Flight gRPC server to, e.g. "0.0.0.0:50051"
+    pub bind_addr: String,
 }
 
 /// Configuration for CSV file data sources
@@ -256,11 +314,44 @@ pub struct CsvSourceSpec {
     pub has_header: bool,
     
     /// Number of rows to process in each batch
-    /// 
+    ///
     /// Controls memory usage and processing granularity.
     /// Larger batches are more efficient but use more memory.
     #[serde(default = "default_batch_rows")]
     pub batch_rows: usize,
+
+    /// Optional column projection
+    ///
+    /// When set, only these columns are read from each CSV file; all other
+    /// columns are skipped at parse time. Names must match the inferred schema.
+    #[serde(default)]
+    pub projection: Option<Vec<String>>,
+
+    /// When true, treat `path` as a FIFO/named pipe (or a continuously appended
+    /// file) and never emit `Message::Eos`: re-open and keep polling for new
+    /// rows instead of stopping when the reader is drained.
+    #[serde(default)]
+    pub streaming: bool,
+
+    /// Column holding event-time values used to derive watermarks in streaming mode
+    #[serde(default)]
+    pub event_time_column: Option<String>,
+
+    /// How often, in milliseconds, to emit `Message::Watermark` in streaming mode
+    /// when `event_time_column` is set
+    #[serde(default = "default_watermark_interval_ms")]
+    pub watermark_interval_ms: u64,
+
+    /// Bounded out-of-orderness allowance, in milliseconds, subtracted from the
+    /// highest event-time value seen so far before it is emitted as a watermark.
+    /// Rows with an event time up to this far behind the max are still
+    /// considered on-time. Watermarks are always emitted non-decreasing.
+    #[serde(default)]
+    pub allowed_lateness_ms: i64,
+}
+
+fn default_watermark_interval_ms() -> u64 {
+    5_000
 }
 
 /// Configuration for Kafka data sources
@@ -294,82 +385,166 @@ pub struct KafkaSourceSpec {
     pub schema: Option<SchemaConfig>,
     
     /// Optional topic-to-Iceberg table mapping configuration
-    /// 
+    ///
     /// Enables direct streaming from Kafka topics to Iceberg tables with
     /// automatic schema evolution and SQL transformations.
     #[serde(default)]
     pub topic_mapping: Option<TopicMapping>,
+
+    /// Optional JSON Schema validation, with rejected messages routed to a
+    /// dead-letter topic instead of being dropped
+    #[serde(default)]
+    pub validation: Option<ValidationConfig>,
+
+    /// Maximum rows buffered before a combined `RecordBatch` is flushed
+    #[serde(default = "default_batch_rows")]
+    pub max_batch_rows: usize,
+
+    /// Target serialized byte size that triggers an early flush, estimated
+    /// from the buffered rows' field values
+    #[serde(default = "default_max_batch_bytes")]
+    pub max_batch_bytes: usize,
+
+    /// Maximum time, in milliseconds, to hold a partial batch before flushing it
+    #[serde(default = "default_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+
+    /// How often, in milliseconds, to commit consumer offsets for batches
+    /// that have already been accepted by the downstream channel. Offsets
+    /// are also committed on EOS and cancellation regardless of this interval.
+    #[serde(default = "default_commit_interval_ms")]
+    pub commit_interval_ms: u64,
+
+    /// Column holding event-time values (epoch milliseconds) used to derive
+    /// watermarks; see [`CsvSourceSpec::event_time_column`]
+    #[serde(default)]
+    pub event_time_column: Option<String>,
+
+    /// How often, in milliseconds, to emit `Message::Watermark` when
+    /// `event_time_column` is set
+    #[serde(default = "default_watermark_interval_ms")]
+    pub watermark_interval_ms: u64,
+
+    /// Bounded out-of-orderness allowance, in milliseconds; see
+    /// [`CsvSourceSpec::allowed_lateness_ms`]
+    #[serde(default)]
+    pub allowed_lateness_ms: i64,
+}
+
+fn default_max_batch_bytes() -> usize {
+    4 * 1024 * 1024
+}
+
+fn default_commit_interval_ms() -> u64 {
+    5_000
 }
 
-/// Configuration for direct Kafka topic to Iceberg table mapping
+/// Configuration for validating decoded Kafka payloads against JSON Schema
+/// (Draft 7/2020-12) before they are converted to Arrow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationConfig {
+    /// Directory of `*.json` JSON Schema files, loaded and compiled at startup
+    #[serde(default)]
+    pub schema_dir: Option<String>,
+
+    /// Inline JSON Schemas, keyed by name, merged with anything loaded from `schema_dir`
+    #[serde(default)]
+    pub schemas: HashMap<String, serde_json::Value>,
+
+    /// Payload field used to select which named schema validates a given message
+    ///
+    /// When unset, or when the field is absent from a message, `default_schema`
+    /// is used instead.
+    #[serde(default)]
+    pub schema_key_field: Option<String>,
+
+    /// Schema name to use when `schema_key_field` is unset or absent from a message
+    #[serde(default)]
+    pub default_schema: Option<String>,
+
+    /// Kafka topic that rejected messages (original payload + validation errors)
+    /// are produced to
+    pub dead_letter_topic: String,
+}
+
+/// Configuration for bootstrapping a Kafka source's schema from an existing
+/// Iceberg table's metadata, instead of inferring it from the first message
 ///
-/// This powerful feature enables streaming data directly from Kafka topics to Iceberg tables
-/// with automatic schema evolution, SQL transformations, and partitioning strategies.
-/// It bypasses the need for separate transform and sink operators.
+/// This only covers schema bootstrapping (see its one read site in
+/// `bins/rde-cli/src/main.rs`); it does not route rows into that table —
+/// for that, pair this Kafka source with a separate `IcebergSink`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TopicMapping {
     /// Name of the target Iceberg table
-    /// 
+    ///
     /// The table will be created if it doesn't exist, with schema automatically
     /// inferred from the incoming Kafka messages.
     pub iceberg_table: String,
-    
+
     /// S3-compatible bucket name for storing Iceberg data
-    /// 
+    ///
     /// Example: "iceberg-data" or "my-data-lake"
     pub bucket: String,
-    
+
     /// S3-compatible endpoint URL
-    /// 
-    /// Examples: 
+    ///
+    /// Examples:
     /// - Local MinIO: "http://localhost:9000"
     /// - AWS S3: "https://s3.amazonaws.com"
     /// - Custom S3: "https://s3.my-company.com"
     pub endpoint: String,
-    
+
     /// S3 access key for authentication
     pub access_key: String,
-    
+
     /// S3 secret key for authentication
     pub secret_key: String,
-    
+
     /// AWS region for S3 operations
-    /// 
+    ///
     /// Examples: "us-east-1", "eu-west-1", "ap-southeast-1"
     pub region: String,
-    
-    /// Enable automatic schema evolution
-    /// 
-    /// When true, new fields in JSON messages will automatically be added to the
-    /// Iceberg table schema. Existing field types can be safely promoted
-    /// (e.g., int32 to int64, float32 to float64).
-    #[serde(default)]
-    pub auto_schema_evolution: bool,
-    
-    /// Optional SQL transformation applied to each message
-    /// 
-    /// Uses DataFusion SQL syntax. The input data is available as `input_data`.
-    /// 
-    /// Example:
-    /// ```sql
-    /// SELECT 
-    ///   *,
-    ///   CURRENT_TIMESTAMP as ingestion_time,
-    ///   DATE(timestamp) as partition_date
-    /// FROM input_data
-    /// WHERE amount > 0
-    /// ```
-    #[serde(default)]
-    pub sql_transform: Option<String>,
-    
-    /// Partition columns for optimized queries
-    /// 
-    /// Specifies which columns to use for partitioning the Iceberg table.
-    /// Partitioning improves query performance by allowing predicate pushdown.
-    /// 
-    /// Example: ["partition_date", "region", "event_type"]
-    #[serde(default)]
-    pub partition_by: Vec<String>,
+}
+
+/// A single partition field: a source column plus the Iceberg transform
+/// applied to it when computing that column's contribution to a row's
+/// partition value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartitionFieldConfig {
+    /// Name of the source column this partition field is derived from
+    pub column: String,
+
+    /// Iceberg partition transform: `identity`, `bucket[N]`, `truncate[W]`,
+    /// `year`, `month`, `day`, or `hour`
+    pub transform: String,
+}
+
+/// Backend that owns an Iceberg table's metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum CatalogConfig {
+    /// A `metadata.json` file on the same object store as the table data
+    /// (the default) — simple, but exclusive to this crate
+    #[serde(rename = "file")]
+    File,
+
+    /// An Iceberg REST Catalog, so this table can be shared with other
+    /// engines pointed at the same warehouse
+    #[serde(rename = "rest")]
+    Rest {
+        /// Base URL of the catalog server, e.g. "http://localhost:8181"
+        uri: String,
+        /// Bearer token sent as `Authorization: Bearer <token>`, if the
+        /// catalog requires authentication
+        #[serde(default)]
+        token: Option<String>,
+    },
+}
+
+impl Default for CatalogConfig {
+    fn default() -> Self {
+        CatalogConfig::File
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -378,12 +553,45 @@ pub struct SchemaConfig {
     pub fields: Vec<FieldConfig>,
     #[serde(default)]
     pub auto_infer: bool,
+
+    /// Base URL of a Confluent-compatible Schema Registry, e.g.
+    /// "http://localhost:8081". Required when `encoding` is `Avro`.
+    #[serde(default)]
+    pub registry_url: Option<String>,
+
+    /// Wire encoding of Kafka message values
+    #[serde(default)]
+    pub encoding: MessageEncoding,
+
+    /// Number of leading messages to sample before committing to an inferred
+    /// schema, merging per-field types across the sample instead of
+    /// replacing the whole schema on every field change. `0` disables
+    /// sampling and infers from the first message only, as before.
+    #[serde(default)]
+    pub sample_size: usize,
+}
+
+/// Wire encoding of Kafka message payloads
+///
+/// Deliberately has no `Protobuf` variant: an earlier attempt added one that
+/// unconditionally `bail!`ed (no descriptor-registry plumbing exists to
+/// actually decode Protobuf-encoded values), so it was removed rather than
+/// kept as a variant nothing could satisfy. Confluent Schema Registry
+/// support is Avro-only here; Protobuf decoding is descoped, not delivered.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageEncoding {
+    /// Plain JSON (the default, as before)
+    #[default]
+    Json,
+    /// Confluent wire-format Avro: `0x00` magic byte + 4-byte schema ID + Avro body
+    Avro,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldConfig {
     pub name: String,
-    pub data_type: String, // "int64", "float64", "string", "boolean"
+    pub data_type: String, // "int64", "float64", "string", "boolean", "timestamp", "decimal"
     #[serde(default)]
     pub nullable: bool,
 }
@@ -403,6 +611,151 @@ pub enum SourceSpec {
     Csv(CsvSourceSpec),
     #[serde(rename = "kafka")]
     Kafka(KafkaSourceSpec),
+    #[serde(rename = "http")]
+    Http(HttpSourceSpec),
+    #[serde(rename = "iceberg")]
+    Iceberg(IcebergSourceSpec),
+    #[serde(rename = "websocket")]
+    WebSocket(WebSocketSourceSpec),
+    #[serde(rename = "replay")]
+    Replay(ReplaySourceSpec),
+}
+
+/// Configuration for a source that deterministically replays a `Message`
+/// stream previously captured to Kafka by an edge's `capture_to`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaySourceSpec {
+    /// Unique identifier for this source operator
+    pub id: String,
+
+    /// Kafka topic the captured stream was written to
+    pub topic: String,
+
+    /// Comma-separated list of Kafka broker addresses
+    pub brokers: String,
+}
+
+/// Configuration for scanning an existing Iceberg table as a pipeline source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IcebergSourceSpec {
+    /// Unique identifier for this source operator
+    pub id: String,
+
+    /// Name of the source Iceberg table (also its location prefix, matching
+    /// the layout written by [`IcebergSinkSpec`])
+    pub table_name: String,
+
+    /// S3-compatible bucket name backing the table
+    pub bucket: String,
+
+    /// S3-compatible endpoint URL
+    pub endpoint: String,
+
+    /// S3 access key for authentication
+    pub access_key: String,
+
+    /// S3 secret key for authentication
+    pub secret_key: String,
+
+    /// AWS region for S3 operations
+    pub region: String,
+
+    /// Optional column projection; when set, only these columns are scanned
+    #[serde(default)]
+    pub projection: Option<Vec<String>>,
+
+    /// Optional SQL predicate applied as a `WHERE` clause during the scan
+    #[serde(default)]
+    pub filter: Option<String>,
+
+    /// Time-travel: read the table as of this exact snapshot id instead of
+    /// its current snapshot. Takes precedence over `as_of_timestamp`.
+    #[serde(default)]
+    pub snapshot_id: Option<i64>,
+
+    /// Time-travel: read the table as of the latest snapshot in
+    /// `snapshot_log` at or before this epoch-millis timestamp
+    #[serde(default)]
+    pub as_of_timestamp: Option<i64>,
+}
+
+/// Configuration for the push-based HTTP ingestion source
+///
+/// Accepts NDJSON or a JSON array posted to `path` and parses records into
+/// `RecordBatch`es against the pipeline schema.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpSourceSpec {
+    /// Unique identifier for this source operator
+    pub id: String,
+
+    /// Address to bind the HTTP server to, e.g. "0.0.0.0:8080"
+    pub bind_addr: String,
+
+    /// URL path that accepts POSTed events, e.g. "/events"
+    #[serde(default = "default_http_path")]
+    pub path: String,
+
+    /// Maximum number of rows to buffer before forwarding a batch
+    #[serde(default = "default_batch_rows")]
+    pub batch_rows: usize,
+
+    /// Maximum time, in milliseconds, to hold a partial batch before flushing it
+    #[serde(default = "default_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+}
+
+fn default_http_path() -> String {
+    "/events".to_string()
+}
+
+fn default_flush_interval_ms() -> u64 {
+    1_000
+}
+
+/// Configuration for a streaming WebSocket ingestion source
+///
+/// Maintains an open connection to `url`, optionally sending `subscribe` as
+/// a JSON text frame right after connecting, and extracts each frame's
+/// payload via `json_pointer` before parsing it into rows against the
+/// pipeline schema. On disconnect or a parse error the source reconnects
+/// with exponential backoff (starting at `backoff_initial_ms`, doubling up
+/// to `backoff_max_ms`) and re-sends `subscribe`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSocketSourceSpec {
+    /// Unique identifier for this source operator
+    pub id: String,
+
+    /// WebSocket URL to connect to, e.g. "wss://stream.example.com/ws"
+    pub url: String,
+
+    /// Optional JSON message sent as a text frame immediately after connecting
+    #[serde(default)]
+    pub subscribe: Option<serde_json::Value>,
+
+    /// JSON Pointer (RFC 6901) into each received frame locating the row
+    /// payload, e.g. "/data" or "" for the whole frame
+    #[serde(default)]
+    pub json_pointer: String,
+
+    /// Maximum number of rows to buffer before forwarding a batch
+    #[serde(default = "default_batch_rows")]
+    pub batch_rows: usize,
+
+    /// Initial delay before the first reconnect attempt, in milliseconds
+    #[serde(default = "default_ws_backoff_initial_ms")]
+    pub backoff_initial_ms: u64,
+
+    /// Cap on the reconnect backoff delay, in milliseconds
+    #[serde(default = "default_ws_backoff_max_ms")]
+    pub backoff_max_ms: u64,
+}
+
+fn default_ws_backoff_initial_ms() -> u64 {
+    500
+}
+
+fn default_ws_backoff_max_ms() -> u64 {
+    30_000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -435,24 +788,167 @@ pub enum TransformSpec {
         partition_format: String,
     },
     #[serde(rename = "sql_transform")]
-    SqlTransform { 
+    SqlTransform {
         id: String,
         query: String,
         #[serde(default)]
         window_size: usize,
+        /// Event-time window driving when buffered panes are flushed. When
+        /// set (together with `timestamp_column`), rows are assigned to
+        /// panes by event time and a pane is only queried once a
+        /// `Message::Watermark` advances past its end, superseding
+        /// `window_size`'s count-based flushing.
+        #[serde(default)]
+        window: Option<WindowSpec>,
+        /// Name of the column holding each row's event-time (epoch
+        /// milliseconds, or an Arrow `Timestamp(Millisecond, _)`), required
+        /// when `window` is set
+        #[serde(default)]
+        timestamp_column: Option<String>,
+        /// Maintain query state across batches instead of recomputing `query`
+        /// from scratch each window; only takes effect for query shapes the
+        /// transform recognizes as incrementally maintainable (plain
+        /// filter/projection, or single-level `GROUP BY` with `COUNT`/`SUM`/
+        /// `MIN`/`MAX`), otherwise falls back to the batched path
+        #[serde(default)]
+        incremental: bool,
     },
     #[serde(rename = "clean_data")]
-    CleanData { 
+    CleanData {
         id: String,
         #[serde(default)]
         remove_nulls: bool,
+        /// Columns checked when dropping null-bearing rows for
+        /// `remove_nulls`; defaults to every column when unset
+        #[serde(default)]
+        remove_nulls_columns: Option<Vec<String>>,
         #[serde(default)]
         trim_strings: bool,
         #[serde(default)]
         normalize_case: Option<String>, // "lower", "upper", "title"
+        /// Replace Float32/Float64 NaN and +/-Infinity values with null
+        #[serde(default)]
+        normalize_nan_inf: bool,
+    },
+    #[serde(rename = "window_aggregate")]
+    WindowAggregate {
+        id: String,
+        /// Name of the column holding each row's event-time (epoch
+        /// milliseconds)
+        timestamp_column: String,
+        window: WindowSpec,
+        /// Columns to group by within each window; empty means one group
+        /// (the whole window) per window
+        #[serde(default)]
+        group_by: Vec<String>,
+        aggregates: Vec<WindowAggregateSpec>,
     },
 }
 
+/// One aggregate computed per group per window by
+/// `TransformSpec::WindowAggregate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowAggregateSpec {
+    /// Source column to aggregate; ignored (may be left empty) for "count"
+    #[serde(default)]
+    pub column: String,
+    /// "count" | "sum" | "min" | "max" | "avg"
+    pub func: String,
+    pub alias: String,
+}
+
+/// Event-time window assignment for `TransformSpec::SqlTransform`. A
+/// tumbling window is a sliding window whose `slide_ms` equals `size_ms`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum WindowSpec {
+    #[serde(rename = "tumbling")]
+    Tumbling { size_ms: i64 },
+    #[serde(rename = "sliding")]
+    Sliding { size_ms: i64, slide_ms: i64 },
+}
+
+impl WindowSpec {
+    /// Returns `(size_ms, slide_ms)`, treating a tumbling window as sliding
+    /// with `slide_ms == size_ms`
+    pub fn size_and_slide_ms(&self) -> (i64, i64) {
+        match self {
+            WindowSpec::Tumbling { size_ms } => (*size_ms, *size_ms),
+            WindowSpec::Sliding { size_ms, slide_ms } => (*size_ms, *slide_ms),
+        }
+    }
+}
+
+/// Tuning knobs for the Parquet `ArrowWriter` used by [`SinkSpec::ParquetDir`]
+///
+/// Lets pipelines trade off file size vs. scan speed without rebuilding, by
+/// exposing the subset of `WriterProperties` that operators tend to tune.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParquetWriterSpec {
+    /// Compression codec: `uncompressed`, `snappy`, `gzip(level)`, `zstd(level)`,
+    /// `lz4`, or `brotli(level)` (case-insensitive, level optional)
+    #[serde(default = "default_compression")]
+    pub compression: String,
+
+    /// Whether to enable dictionary encoding for applicable columns
+    #[serde(default = "default_true")]
+    pub dictionary_enabled: bool,
+
+    /// Target uncompressed size in bytes for a single data page
+    #[serde(default)]
+    pub data_pagesize_limit: Option<usize>,
+
+    /// Number of rows to process per write batch internally
+    #[serde(default)]
+    pub write_batch_size: Option<usize>,
+
+    /// Maximum number of rows per row group
+    #[serde(default)]
+    pub max_row_group_size: Option<usize>,
+
+    /// Parquet writer version: `"1.0"` or `"2.0"`
+    #[serde(default = "default_writer_version")]
+    pub writer_version: String,
+}
+
+impl Default for ParquetWriterSpec {
+    fn default() -> Self {
+        Self {
+            compression: default_compression(),
+            dictionary_enabled: default_true(),
+            data_pagesize_limit: None,
+            write_batch_size: None,
+            max_row_group_size: None,
+            writer_version: default_writer_version(),
+        }
+    }
+}
+
+fn default_compression() -> String {
+    "snappy".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_writer_version() -> String {
+    "1.0".to_string()
+}
+
+/// Output rendering for [`SinkSpec::Stdout`]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StdoutFormat {
+    /// Pretty ASCII grid (the default), via Arrow's `pretty_format_batches`
+    #[default]
+    Table,
+    /// One JSON object per row, newline-delimited
+    Ndjson,
+    /// CSV rows, including a header on the first batch
+    Csv,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IcebergSinkSpec {
     pub id: String,
@@ -462,15 +958,97 @@ pub struct IcebergSinkSpec {
     pub access_key: String,
     pub secret_key: String,
     pub region: String,
+
+    /// How to partition this table; see [`PartitionFieldConfig`]. Leave
+    /// empty for an unpartitioned table.
+    #[serde(default)]
+    pub partition_by: Vec<PartitionFieldConfig>,
+
+    /// Where this table's Iceberg metadata is stored and how commits to it
+    /// are coordinated; see [`CatalogConfig`]
+    #[serde(default)]
+    pub catalog: CatalogConfig,
+
+    /// Identity columns for a change-data-capture upsert stream. When
+    /// non-empty, each batch must carry a `_cdc_op` string column
+    /// (`"I"`/`"U"`/`"D"`) and rows are written as inserts/equality-delete
+    /// files instead of plain appends. Leave empty for append-only writes.
+    #[serde(default)]
+    pub upsert_keys: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum SinkSpec {
     #[serde(rename = "stdout_pretty")]
-    Stdout { id: String },
+    Stdout {
+        id: String,
+        #[serde(default)]
+        format: StdoutFormat,
+    },
     #[serde(rename = "parquet_dir")]
-    ParquetDir { id: String, path: String },
+    ParquetDir {
+        id: String,
+        path: String,
+        #[serde(default)]
+        writer: ParquetWriterSpec,
+    },
     #[serde(rename = "iceberg")]
     Iceberg(IcebergSinkSpec),
+    #[serde(rename = "kafka")]
+    Kafka(KafkaSinkSpec),
+}
+
+/// Configuration for an exactly-once transactional Kafka sink
+///
+/// Rows are produced to `topic` inside a Kafka transaction, using
+/// rdkafka's transactional producer (`transactional_id` must be stable
+/// across restarts of the same logical sink). A transaction is committed
+/// whenever a `Message::Watermark` or `Message::Eos` arrives, and the
+/// committed watermark is written to `progress_topic` in the same
+/// transaction so a restart can resume exactly where it left off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KafkaSinkSpec {
+    /// Unique identifier for this sink operator
+    pub id: String,
+
+    /// Comma-separated list of Kafka broker addresses
+    pub brokers: String,
+
+    /// Name of the Kafka topic to produce rows to
+    pub topic: String,
+
+    /// Stable transactional.id for the producer; must be unique to this
+    /// sink and unchanged across restarts for exactly-once semantics to hold
+    pub transactional_id: String,
+
+    /// Compacted topic holding the highest committed watermark, used to
+    /// resume after a crash without reprocessing already-committed rows
+    pub progress_topic: String,
+
+    /// Column used as the Kafka message key; when unset, messages are
+    /// produced with no key
+    #[serde(default)]
+    pub key_column: Option<String>,
+
+    /// Column holding each row's event-time (epoch milliseconds), used to
+    /// skip rows already covered by the last committed watermark on resume
+    ///
+    /// When unset, the sink cannot tell already-committed rows apart from
+    /// new ones after a restart and will reprocess everything upstream
+    /// redelivers, so this should be set whenever `progress_topic` is.
+    #[serde(default)]
+    pub timestamp_column: Option<String>,
+
+    /// Wire encoding used to serialize each row
+    ///
+    /// Only `MessageEncoding::Json` is implemented by `KafkaSink`;
+    /// `MessageEncoding::Avro` is rejected at `run` time. Producing
+    /// Confluent wire-format Avro needs a write-schema and a
+    /// register-schema-with-the-registry call, and no such registration
+    /// path exists anywhere in this crate — `SchemaRegistryClient` only
+    /// fetches an already-registered schema by id, for `KafkaSource`'s
+    /// decode direction. Won't-do until that registration path exists.
+    #[serde(default)]
+    pub encoding: MessageEncoding,
 }
@@ -0,0 +1,273 @@
+#![allow(unused)]
+
+//! Arrow Flight server exposing pipeline batches to external Arrow-native clients
+//!
+//! Lets BI tools and other Arrow-native services pull `RecordBatch`es directly
+//! out of a running `KafkaPipelineSource`, keyed by Kafka topic name as the
+//! Flight ticket/descriptor, instead of re-serializing pipeline output to JSON.
+
+use anyhow::{Context, Result};
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo,
+    HandshakeRequest, HandshakeResponse, PollInfo, PutResult, SchemaAsIpc, SchemaResult, Ticket,
+};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::ipc::writer::IpcWriteOptions;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use rde_core::{Batch, Message};
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_util::sync::CancellationToken;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{info, warn};
+
+const BROADCAST_CAPACITY: usize = 1024;
+
+/// Taps a pipeline source's output: every `Message::Batch` observed is
+/// broadcast to any number of Arrow Flight subscribers (and its schema
+/// cached for `get_flight_info`/`get_schema`), without affecting the normal
+/// downstream channel the source also sends to.
+#[derive(Clone)]
+pub struct FlightTap {
+    schema: Arc<RwLock<Option<SchemaRef>>>,
+    sender: broadcast::Sender<Batch>,
+}
+
+impl FlightTap {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(BROADCAST_CAPACITY);
+        Self {
+            schema: Arc::new(RwLock::new(None)),
+            sender,
+        }
+    }
+
+    /// Records a message flowing through the tapped source. `Message::Batch`
+    /// is broadcast to subscribers; other message kinds are ignored.
+    pub fn observe(&self, message: &Message) {
+        if let Message::Batch(batch) = message {
+            *self.schema.write().unwrap() = Some(batch.schema());
+            // No subscribers yet (or a lagging one) is not an error: Flight
+            // clients may simply not be connected at this point in the stream.
+            let _ = self.sender.send(batch.clone());
+        }
+    }
+
+    fn flight_service(&self, topic: String) -> PipelineFlightService {
+        PipelineFlightService {
+            topic,
+            schema: self.schema.clone(),
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl Default for FlightTap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `FlightService` exposing one tapped source's batches under `topic` as the
+/// sole ticket/descriptor path this server serves
+#[derive(Clone)]
+struct PipelineFlightService {
+    topic: String,
+    schema: Arc<RwLock<Option<SchemaRef>>>,
+    sender: broadcast::Sender<Batch>,
+}
+
+impl PipelineFlightService {
+    fn current_schema(&self) -> Result<SchemaRef, Status> {
+        self.schema
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| Status::unavailable("no batches produced yet; schema not known"))
+    }
+
+    fn flight_info(&self) -> Result<FlightInfo, Status> {
+        let schema = self.current_schema()?;
+        let descriptor = FlightDescriptor::new_path(vec![self.topic.clone()]);
+        let endpoint = FlightEndpoint::new().with_ticket(Ticket::new(self.topic.clone().into_bytes()));
+        FlightInfo::new()
+            .try_with_schema(schema.as_ref())
+            .map_err(|e| Status::internal(e.to_string()))
+            .map(|info| info.with_descriptor(descriptor).with_endpoint(endpoint))
+    }
+}
+
+#[tonic::async_trait]
+impl FlightService for PipelineFlightService {
+    type HandshakeStream = BoxStream<'static, Result<HandshakeResponse, Status>>;
+    type ListFlightsStream = BoxStream<'static, Result<FlightInfo, Status>>;
+    type DoGetStream = BoxStream<'static, Result<FlightData, Status>>;
+    type DoPutStream = BoxStream<'static, Result<PutResult, Status>>;
+    type DoActionStream = BoxStream<'static, Result<arrow_flight::Result, Status>>;
+    type ListActionsStream = BoxStream<'static, Result<ActionType, Status>>;
+    type DoExchangeStream = BoxStream<'static, Result<FlightData, Status>>;
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented("handshake is not required by this server"))
+    }
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        let info = self.flight_info()?;
+        Ok(Response::new(futures::stream::iter(vec![Ok(info)]).boxed()))
+    }
+
+    async fn get_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Ok(Response::new(self.flight_info()?))
+    }
+
+    async fn poll_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<PollInfo>, Status> {
+        let info = self.flight_info()?;
+        Ok(Response::new(PollInfo {
+            info: Some(info),
+            flight_descriptor: None,
+            progress: Some(1.0),
+            expiration_time: None,
+        }))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        let schema = self.current_schema()?;
+        let options = IpcWriteOptions::default();
+        SchemaAsIpc::new(&schema, &options)
+            .try_into()
+            .map(Response::new)
+            .map_err(|e: arrow_flight::error::FlightError| Status::internal(e.to_string()))
+    }
+
+    async fn do_get(&self, request: Request<Ticket>) -> Result<Response<Self::DoGetStream>, Status> {
+        let ticket = request.into_inner();
+        let requested_topic = String::from_utf8(ticket.ticket.to_vec()).unwrap_or_default();
+        if requested_topic != self.topic {
+            return Err(Status::not_found(format!(
+                "no such Flight stream: '{requested_topic}' (this server serves '{}')",
+                self.topic
+            )));
+        }
+
+        let schema = self.current_schema()?;
+        let receiver = self.sender.subscribe();
+        let batches = BroadcastStream::new(receiver).filter_map(|batch| async move {
+            match batch {
+                Ok(batch) => Some(Ok(batch)),
+                Err(tokio_stream::wrappers::errors::BroadcastStreamRecvError::Lagged(n)) => {
+                    warn!("Flight subscriber lagged by {} batches, dropping them", n);
+                    None
+                }
+            }
+        });
+
+        let stream = FlightDataEncoderBuilder::new()
+            .with_schema(schema)
+            .build(batches)
+            .map(|result| result.map_err(|e| Status::internal(e.to_string())));
+
+        Ok(Response::new(stream.boxed()))
+    }
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("this server only streams pipeline output out via do_get"))
+    }
+
+    async fn do_action(&self, _request: Request<Action>) -> Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented("no custom actions are supported"))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        Ok(Response::new(futures::stream::empty().boxed()))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented("bidirectional exchange is not supported"))
+    }
+}
+
+/// Runs the Flight gRPC server until `cancel` fires, serving `tap`'s batches
+/// under `topic`'s name as the only ticket this server will hand out
+pub async fn serve(addr: SocketAddr, topic: String, tap: FlightTap, cancel: CancellationToken) -> Result<()> {
+    let service = tap.flight_service(topic);
+    info!("Arrow Flight server listening on {addr}");
+    tonic::transport::Server::builder()
+        .add_service(FlightServiceServer::new(service))
+        .serve_with_shutdown(addr, async move { cancel.cancelled().await })
+        .await
+        .context("Arrow Flight server failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FlightTap;
+    use datafusion::arrow::array::{Int64Array, RecordBatch};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use rde_core::Message;
+    use std::sync::Arc;
+
+    fn batch() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from(vec![1, 2]))]).unwrap()
+    }
+
+    #[test]
+    fn no_schema_until_a_batch_is_observed() {
+        let tap = FlightTap::new();
+        assert!(tap.flight_service("topic".to_string()).current_schema().is_err());
+    }
+
+    #[test]
+    fn observing_a_batch_caches_its_schema() {
+        let tap = FlightTap::new();
+        tap.observe(&Message::Batch(batch()));
+        let schema = tap.flight_service("topic".to_string()).current_schema().unwrap();
+        assert_eq!(schema.field_with_name("id").unwrap().data_type(), &DataType::Int64);
+    }
+
+    #[test]
+    fn non_batch_messages_are_ignored() {
+        let tap = FlightTap::new();
+        tap.observe(&Message::Watermark(0));
+        tap.observe(&Message::Eos);
+        assert!(tap.flight_service("topic".to_string()).current_schema().is_err());
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_observed_batches() {
+        let tap = FlightTap::new();
+        let mut rx = tap.sender.subscribe();
+        tap.observe(&Message::Batch(batch()));
+        let received = rx.recv().await.unwrap();
+        assert_eq!(received.num_rows(), 2);
+    }
+}
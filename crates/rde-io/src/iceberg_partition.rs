@@ -0,0 +1,363 @@
+#![allow(unused)]
+
+//! Shared Iceberg partition-transform evaluation, used by `sink_iceberg`'s
+//! Iceberg sink to split an incoming `RecordBatch` into one sub-batch per
+//! distinct partition value and compute each sub-batch's Hive-style
+//! partition path.
+
+use anyhow::{Context, Result};
+use datafusion::arrow::array::{
+    Array, Date32Array, Float64Array, Int64Array, RecordBatch, StringArray,
+    TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
+    TimestampSecondArray, UInt32Array,
+};
+use datafusion::arrow::array::BooleanArray;
+use datafusion::arrow::datatypes::{DataType, SchemaRef, TimeUnit};
+use rde_core::PartitionFieldConfig;
+use std::collections::HashMap;
+
+/// Splits `batch` into one sub-batch per distinct partition value, keyed by
+/// the Hive-style partition path a writer should place it under, e.g.
+/// `"region=us/event_time_day=19723"`. Returns a single `("", batch)` entry,
+/// unchanged, when `partition_by` is empty.
+pub fn partition_batch(
+    partition_by: &[PartitionFieldConfig],
+    batch: &RecordBatch,
+) -> Result<Vec<(String, RecordBatch)>> {
+    if partition_by.is_empty() {
+        return Ok(vec![(String::new(), batch.clone())]);
+    }
+
+    let schema = batch.schema();
+    let mut groups: Vec<(String, Vec<u32>)> = Vec::new();
+    let mut index_by_path: HashMap<String, usize> = HashMap::new();
+
+    for row in 0..batch.num_rows() {
+        let path = partition_path_for_row(partition_by, &schema, batch, row)?;
+        let index = *index_by_path.entry(path.clone()).or_insert_with(|| {
+            groups.push((path, Vec::new()));
+            groups.len() - 1
+        });
+        groups[index].1.push(row as u32);
+    }
+
+    groups
+        .into_iter()
+        .map(|(path, rows)| {
+            let indices = UInt32Array::from(rows);
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|column| {
+                    datafusion::arrow::compute::take(column, &indices, None)
+                        .with_context(|| format!("building sub-batch for partition '{path}'"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let sub_batch = RecordBatch::try_new(schema.clone(), columns)?;
+            Ok((path, sub_batch))
+        })
+        .collect()
+}
+
+/// Computes the partition path segment(s) for one row, e.g.
+/// `"region=us/event_time_day=19723"`, by evaluating each `partition_by`
+/// entry's transform against that column
+fn partition_path_for_row(
+    partition_by: &[PartitionFieldConfig],
+    schema: &SchemaRef,
+    batch: &RecordBatch,
+    row: usize,
+) -> Result<String> {
+    partition_by
+        .iter()
+        .map(|config| {
+            let (col_idx, field) = schema
+                .column_with_name(&config.column)
+                .with_context(|| format!("partition column '{}' is not in the batch schema", config.column))?;
+            let value = partition_value(batch.column(col_idx), row, field.data_type(), &config.transform)?;
+            Ok(format!("{}={value}", partition_field_name(&config.column, &config.transform)))
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(|segments| segments.join("/"))
+}
+
+/// Iceberg's conventional partition field name: the bare column name for
+/// `identity`, otherwise `<column>_<transform>` with any `[N]`/`[W]`
+/// argument stripped, e.g. `"region"` stays `"region"` but `"id"` with
+/// `"bucket[16]"` becomes `"id_bucket"`.
+pub fn partition_field_name(column: &str, transform: &str) -> String {
+    if transform == "identity" {
+        return column.to_string();
+    }
+    let transform_kind = transform.split('[').next().unwrap_or(transform);
+    format!("{column}_{transform_kind}")
+}
+
+/// Evaluates `transform` against `array[row]`, returning the partition
+/// value as a string suitable for a Hive-style partition path segment
+pub fn partition_value(array: &dyn Array, row: usize, data_type: &DataType, transform: &str) -> Result<String> {
+    if array.is_null(row) {
+        return Ok("null".to_string());
+    }
+
+    if let Some(width) = transform.strip_prefix("bucket[").and_then(|rest| rest.strip_suffix(']')) {
+        let buckets: u32 = width.parse().with_context(|| format!("invalid bucket transform '{transform}'"))?;
+        let hash = murmur3_32(&partition_source_bytes(array, row, data_type)?, 0);
+        return Ok(((hash & 0x7fff_ffff) % buckets).to_string());
+    }
+
+    if let Some(width) = transform.strip_prefix("truncate[").and_then(|rest| rest.strip_suffix(']')) {
+        let width: i64 = width.parse().with_context(|| format!("invalid truncate transform '{transform}'"))?;
+        return truncate_value(array, row, data_type, width);
+    }
+
+    match transform {
+        "identity" => identity_value(array, row, data_type),
+        "year" | "month" | "day" | "hour" => temporal_value(array, row, data_type, transform),
+        other => anyhow::bail!("unsupported partition transform '{other}'"),
+    }
+}
+
+fn identity_value(array: &dyn Array, row: usize, data_type: &DataType) -> Result<String> {
+    Ok(match data_type {
+        DataType::Int64 => array.as_any().downcast_ref::<Int64Array>().unwrap().value(row).to_string(),
+        DataType::Float64 => array.as_any().downcast_ref::<Float64Array>().unwrap().value(row).to_string(),
+        DataType::Utf8 => array.as_any().downcast_ref::<StringArray>().unwrap().value(row).to_string(),
+        DataType::Boolean => array.as_any().downcast_ref::<BooleanArray>().unwrap().value(row).to_string(),
+        other => anyhow::bail!("identity partition transform does not support column type {other:?}"),
+    })
+}
+
+fn truncate_value(array: &dyn Array, row: usize, data_type: &DataType, width: i64) -> Result<String> {
+    anyhow::ensure!(width > 0, "truncate width must be positive, got {width}");
+    match data_type {
+        DataType::Utf8 => {
+            let value = array.as_any().downcast_ref::<StringArray>().unwrap().value(row);
+            Ok(value.chars().take(width as usize).collect())
+        }
+        DataType::Int64 => {
+            let value = array.as_any().downcast_ref::<Int64Array>().unwrap().value(row);
+            // Floor division truncation, per the Iceberg spec, so negative
+            // values truncate towards negative infinity rather than zero
+            Ok((value - (((value % width) + width) % width)).to_string())
+        }
+        other => anyhow::bail!("truncate partition transform does not support column type {other:?}"),
+    }
+}
+
+/// Evaluates the `year`/`month`/`day`/`hour` transforms, each expressed (per
+/// the Iceberg spec) as a count since the epoch rather than a calendar field
+fn temporal_value(array: &dyn Array, row: usize, data_type: &DataType, transform: &str) -> Result<String> {
+    let epoch_seconds: i64 = match data_type {
+        DataType::Date32 => array.as_any().downcast_ref::<Date32Array>().unwrap().value(row) as i64 * 86_400,
+        DataType::Timestamp(unit, _) => {
+            let (raw, divisor): (i64, i64) = match unit {
+                TimeUnit::Second => {
+                    (array.as_any().downcast_ref::<TimestampSecondArray>().unwrap().value(row), 1)
+                }
+                TimeUnit::Millisecond => {
+                    (array.as_any().downcast_ref::<TimestampMillisecondArray>().unwrap().value(row), 1_000)
+                }
+                TimeUnit::Microsecond => {
+                    (array.as_any().downcast_ref::<TimestampMicrosecondArray>().unwrap().value(row), 1_000_000)
+                }
+                TimeUnit::Nanosecond => {
+                    (array.as_any().downcast_ref::<TimestampNanosecondArray>().unwrap().value(row), 1_000_000_000)
+                }
+            };
+            raw.div_euclid(divisor)
+        }
+        other => anyhow::bail!("{transform} partition transform does not support column type {other:?}"),
+    };
+
+    let epoch_days = epoch_seconds.div_euclid(86_400);
+
+    Ok(match transform {
+        "day" => epoch_days.to_string(),
+        "hour" => epoch_seconds.div_euclid(3_600).to_string(),
+        "year" | "month" => {
+            let (year, month, _) = civil_from_days(epoch_days);
+            if transform == "year" { (year - 1970).to_string() } else { ((year - 1970) * 12 + month as i64 - 1).to_string() }
+        }
+        other => unreachable!("temporal_value called with non-temporal transform '{other}'"),
+    })
+}
+
+/// Canonical bytes for hashing a column value under the `bucket[N]`
+/// transform: 8-byte little-endian for numeric types, raw UTF-8 for strings,
+/// matching the Iceberg spec's definition of the murmur3 transform input
+fn partition_source_bytes(array: &dyn Array, row: usize, data_type: &DataType) -> Result<Vec<u8>> {
+    Ok(match data_type {
+        DataType::Int64 => array.as_any().downcast_ref::<Int64Array>().unwrap().value(row).to_le_bytes().to_vec(),
+        DataType::Float64 => array.as_any().downcast_ref::<Float64Array>().unwrap().value(row).to_le_bytes().to_vec(),
+        DataType::Utf8 => array.as_any().downcast_ref::<StringArray>().unwrap().value(row).as_bytes().to_vec(),
+        other => anyhow::bail!("bucket partition transform does not support column type {other:?}"),
+    })
+}
+
+/// 32-bit MurmurHash3 (x86 variant), the hash Iceberg's `bucket[N]` transform
+/// is defined in terms of. Hand-rolled rather than pulling in a hashing
+/// crate for this one call site.
+fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e2d51;
+    const C2: u32 = 0x1b873593;
+
+    let mut hash = seed;
+    let chunks = data.chunks_exact(4);
+    let remainder = chunks.remainder();
+
+    for chunk in chunks {
+        let mut k = u32::from_le_bytes(chunk.try_into().unwrap());
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+
+        hash ^= k;
+        hash = hash.rotate_left(13).wrapping_mul(5).wrapping_add(0xe6546b64);
+    }
+
+    if !remainder.is_empty() {
+        let mut k = 0u32;
+        for (i, &byte) in remainder.iter().enumerate().rev() {
+            k ^= (byte as u32) << (8 * i);
+        }
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
+    }
+
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85ebca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2ae35);
+    hash ^= hash >> 16;
+    hash
+}
+
+/// Howard Hinnant's `civil_from_days` algorithm: converts a day count since
+/// 1970-01-01 into a proleptic-Gregorian `(year, month, day)`, without
+/// pulling in a datetime crate for a handful of partition transforms.
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = (z - era * 146_097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (if m <= 2 { y + 1 } else { y }, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{civil_from_days, murmur3_32, partition_batch, partition_field_name, partition_value, temporal_value};
+    use datafusion::arrow::array::{Date32Array, Int64Array, RecordBatch, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use rde_core::PartitionFieldConfig;
+    use std::sync::Arc;
+
+    /// Published MurmurHash3 x86_32 (seed 0) test vectors, cross-checked
+    /// against independent implementations rather than derived from this
+    /// one, so a regression in `murmur3_32` doesn't slip through unnoticed.
+    #[test]
+    fn murmur3_32_matches_known_vectors() {
+        assert_eq!(murmur3_32(b"", 0), 0x0000_0000);
+        assert_eq!(murmur3_32(b"test", 0), 0xba6b_d213);
+        assert_eq!(murmur3_32(b"Hello, world!", 0), 0xc036_3e43);
+        assert_eq!(murmur3_32(b"The quick brown fox jumps over the lazy dog", 0), 0x2e4f_f723);
+    }
+
+    #[test]
+    fn civil_from_days_matches_known_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(1), (1970, 1, 2));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_017), (2000, 3, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29)); // leap day
+        assert_eq!(civil_from_days(-25_567), (1900, 1, 1));
+        assert_eq!(civil_from_days(-365), (1969, 1, 1));
+    }
+
+    #[test]
+    fn temporal_value_day_year_month_hour_on_and_around_epoch() {
+        let array = Date32Array::from(vec![11_017, -365]);
+
+        assert_eq!(temporal_value(&array, 0, &DataType::Date32, "day").unwrap(), "11017");
+        assert_eq!(temporal_value(&array, 0, &DataType::Date32, "year").unwrap(), "30");
+        assert_eq!(temporal_value(&array, 0, &DataType::Date32, "month").unwrap(), "362");
+        assert_eq!(temporal_value(&array, 0, &DataType::Date32, "hour").unwrap(), "264408");
+
+        assert_eq!(temporal_value(&array, 1, &DataType::Date32, "day").unwrap(), "-365");
+        assert_eq!(temporal_value(&array, 1, &DataType::Date32, "year").unwrap(), "-1");
+        assert_eq!(temporal_value(&array, 1, &DataType::Date32, "month").unwrap(), "-12");
+    }
+
+    #[test]
+    fn partition_field_name_strips_transform_arguments() {
+        assert_eq!(partition_field_name("region", "identity"), "region");
+        assert_eq!(partition_field_name("id", "bucket[16]"), "id_bucket");
+        assert_eq!(partition_field_name("name", "truncate[4]"), "name_truncate");
+        assert_eq!(partition_field_name("event_time", "day"), "event_time_day");
+    }
+
+    #[test]
+    fn partition_value_truncate_truncates_strings_and_floor_divides_negative_ints() {
+        let strings = StringArray::from(vec!["hello world"]);
+        assert_eq!(partition_value(&strings, 0, &DataType::Utf8, "truncate[5]").unwrap(), "hello");
+
+        let ints = Int64Array::from(vec![-7, 7]);
+        assert_eq!(partition_value(&ints, 0, &DataType::Int64, "truncate[5]").unwrap(), "-10");
+        assert_eq!(partition_value(&ints, 1, &DataType::Int64, "truncate[5]").unwrap(), "5");
+    }
+
+    #[test]
+    fn partition_value_bucket_is_stable_and_within_range() {
+        let ids = Int64Array::from(vec![42]);
+        let bucket = partition_value(&ids, 0, &DataType::Int64, "bucket[8]").unwrap();
+        let bucket_again = partition_value(&ids, 0, &DataType::Int64, "bucket[8]").unwrap();
+        assert_eq!(bucket, bucket_again, "bucket assignment must be deterministic");
+        assert!(bucket.parse::<u32>().unwrap() < 8);
+    }
+
+    #[test]
+    fn partition_value_null_row_is_the_literal_string_null() {
+        let ids = Int64Array::from(vec![None]);
+        assert_eq!(partition_value(&ids, 0, &DataType::Int64, "identity").unwrap(), "null");
+    }
+
+    #[test]
+    fn partition_batch_groups_rows_by_distinct_partition_value() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("region", DataType::Utf8, false),
+            Field::new("amount", DataType::Int64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["us", "eu", "us"])),
+                Arc::new(Int64Array::from(vec![10, 20, 30])),
+            ],
+        )
+        .unwrap();
+
+        let partition_by = vec![PartitionFieldConfig { column: "region".to_string(), transform: "identity".to_string() }];
+        let partitions = partition_batch(&partition_by, &batch).unwrap();
+
+        assert_eq!(partitions.len(), 2);
+        let us_partition = partitions.iter().find(|(path, _)| path == "region=us").unwrap();
+        assert_eq!(us_partition.1.num_rows(), 2);
+        let eu_partition = partitions.iter().find(|(path, _)| path == "region=eu").unwrap();
+        assert_eq!(eu_partition.1.num_rows(), 1);
+    }
+
+    #[test]
+    fn partition_batch_is_a_no_op_when_unpartitioned() {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from(vec![1, 2]))]).unwrap();
+
+        let partitions = partition_batch(&[], &batch).unwrap();
+        assert_eq!(partitions.len(), 1);
+        assert_eq!(partitions[0].0, "");
+        assert_eq!(partitions[0].1.num_rows(), 2);
+    }
+}
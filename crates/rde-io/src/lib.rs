@@ -16,7 +16,6 @@
 //! - **Stdout**: Development and debugging output
 //!
 //! ### Advanced Features
-//! - **Topic Mapping**: Direct Kafka-to-Iceberg streaming with schema evolution
 //! - **Dynamic Schema Management**: Automatic schema inference and evolution
 //! - **SQL Transformations**: Topic-specific data transformations using DataFusion
 //!
@@ -48,14 +47,41 @@ pub mod sink_parquet;
 /// Standard output sink for debugging
 pub mod sink_stdout;
 
-/// Apache Iceberg table sink implementation  
+/// Apache Iceberg table sink implementation
 pub mod sink_iceberg;
 
+/// Exactly-once transactional Kafka sink implementation
+pub mod sink_kafka;
+
 /// CSV file source implementation
 pub mod source_csv;
 
 /// Apache Kafka source implementation
 pub mod source_kafka;
 
-/// Topic-to-table mapping management
-pub mod topic_mapping;
+/// Push-based HTTP ingestion source implementation
+pub mod source_http;
+
+/// Streaming WebSocket source implementation with auto-reconnect
+pub mod source_websocket;
+
+/// Tees a captured `Message` stream to Kafka for deterministic replay
+pub mod capture;
+
+/// Source that deterministically replays a stream captured by [`capture`]
+pub mod source_replay;
+
+/// Apache Iceberg table scan source implementation
+pub mod source_iceberg;
+
+/// Distributed-tracing instrumentation and optional Kafka span reporter
+pub mod trace;
+
+/// Shared Avro object-container encoding for Iceberg manifests/manifest lists
+pub(crate) mod iceberg_avro;
+
+/// Shared Iceberg partition-transform evaluation and batch splitting
+pub(crate) mod iceberg_partition;
+
+/// Arrow Flight server exposing pipeline batches to external clients
+pub mod flight_server;
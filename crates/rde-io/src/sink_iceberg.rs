@@ -1,25 +1,32 @@
 #![allow(unused)]
 
-use anyhow::Result;
-use datafusion::arrow::array::RecordBatch;
-use datafusion::arrow::datatypes::SchemaRef;
+use anyhow::{Context, Result};
+use datafusion::arrow::array::{
+    Array, ArrayRef, BooleanArray, Date32Array, Float32Array, Float64Array, Int32Array, Int64Array,
+    RecordBatch, StringArray, TimestampMicrosecondArray, TimestampMillisecondArray,
+    TimestampNanosecondArray, TimestampSecondArray,
+};
+use datafusion::arrow::compute::{filter_record_batch, max, max_string, min, min_string};
+use datafusion::arrow::datatypes::{DataType, SchemaRef, TimeUnit};
 use async_trait::async_trait;
 use std::time::{SystemTime, UNIX_EPOCH};
 use object_store::{
-    aws::AmazonS3Builder, path::Path as ObjectPath, ObjectStore, PutOptions,
+    aws::AmazonS3Builder, path::Path as ObjectPath, ObjectStore, PutMode, PutOptions, UpdateVersion,
 };
 use datafusion::parquet::arrow::arrow_writer::ArrowWriter;
 use datafusion::parquet::file::properties::WriterProperties;
 use rde_core::{BatchRx, Message, Operator, Sink};
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
-use tracing::info;
+use tracing::{info, warn};
 use uuid::Uuid;
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
+use crate::iceberg_avro;
+use crate::iceberg_partition;
 
 // Iceberg metadata structures
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 struct IcebergTableMetadata {
     format_version: i32,
     table_uuid: String,
@@ -42,6 +49,13 @@ struct IcebergTableMetadata {
     metadata_log: Vec<IcebergMetadataLogEntry>,
     current_snapshot_id: Option<i64>,
     refs: HashMap<String, IcebergSnapshotRef>,
+
+    /// `name -> field_id` assignments, so a column keeps the same permanent
+    /// ID across schema evolutions no matter where it sorts positionally.
+    /// Only ever grows: a dropped column's ID stays retired here rather than
+    /// being handed to whatever field happens to take its old position.
+    #[serde(default)]
+    column_ids: HashMap<String, i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -187,6 +201,7 @@ struct IcebergManifest {
 struct IcebergManifestEntry {
     status: i32, // 1 for existing, 2 for added
     snapshot_id: i64,
+    sequence_number: Option<i64>,
     data_file: IcebergDataFile,
 }
 
@@ -211,6 +226,578 @@ struct IcebergDataFile {
     sort_order_id: Option<i32>,
 }
 
+/// Per-column statistics gathered while writing a Parquet file, keyed by
+/// the same field ids [`IcebergSink::convert_arrow_schema_to_iceberg`]
+/// assigns, ready to merge directly into an [`IcebergDataFile`]
+struct ParquetFileStats {
+    column_sizes: HashMap<i32, i64>,
+    value_counts: HashMap<i32, i64>,
+    null_value_counts: HashMap<i32, i64>,
+    lower_bounds: HashMap<i32, Vec<u8>>,
+    upper_bounds: HashMap<i32, Vec<u8>>,
+}
+
+/// A manifest ready to be listed in a snapshot's manifest list: its
+/// location, byte length, [`IcebergDataFile::content`] (`0` data / `1`
+/// deletes), the file/row counts of everything it references, and a
+/// per-partition-spec-field summary of the partition values those files
+/// carry (the manifest list's `partitions` column, used for partition
+/// pruning at the manifest-list level before a reader opens any manifest)
+struct ManifestRef {
+    path: String,
+    length: i64,
+    content: i32,
+    file_count: i32,
+    row_count: i64,
+    partitions: Vec<IcebergPartitionSummary>,
+}
+
+/// Avro's map type requires string keys, but Iceberg's per-column
+/// statistics are keyed by integer field id; the spec represents them as
+/// an array of `{key, value}` records instead. These wire types exist only
+/// to give [`IcebergDataFile`]'s stats maps that shape when encoding to
+/// Avro; [`IcebergDataFile`] itself keeps the more ergonomic `HashMap`.
+#[derive(Debug, Serialize, Clone)]
+struct AvroI64Entry {
+    key: i32,
+    value: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct AvroBinaryEntry {
+    key: i32,
+    #[serde(with = "serde_bytes")]
+    value: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct AvroDataFile {
+    content: i32,
+    file_path: String,
+    file_format: String,
+    /// One entry per current partition spec field (see
+    /// [`IcebergSink::partition_record_schema`]), keyed by that field's
+    /// name and `None` when this file has no value for it, so the shape
+    /// always matches the dynamically-generated `partition` sub-schema
+    /// field-for-field instead of whatever keys `IcebergDataFile::partition`
+    /// happened to carry.
+    partition: HashMap<String, Option<String>>,
+    record_count: i64,
+    file_size_in_bytes: i64,
+    column_sizes: Vec<AvroI64Entry>,
+    value_counts: Vec<AvroI64Entry>,
+    null_value_counts: Vec<AvroI64Entry>,
+    nan_value_counts: Vec<AvroI64Entry>,
+    distinct_counts: Vec<AvroI64Entry>,
+    lower_bounds: Vec<AvroBinaryEntry>,
+    upper_bounds: Vec<AvroBinaryEntry>,
+    split_offsets: Vec<i64>,
+    equality_ids: Vec<i32>,
+    sort_order_id: Option<i32>,
+}
+
+/// One partition spec field's manifest-list-level summary (Iceberg v2
+/// `field_summary`): whether any referenced file has a null or NaN value
+/// for that field, and the min/max of the values it does have, as the UTF-8
+/// bytes of the rendered partition values (see
+/// `IcebergSink::partition_summaries`).
+#[derive(Debug, Serialize, Clone)]
+struct AvroPartitionSummary {
+    contains_null: bool,
+    contains_nan: Option<bool>,
+    #[serde(with = "serde_bytes")]
+    lower_bound: Option<Vec<u8>>,
+    #[serde(with = "serde_bytes")]
+    upper_bound: Option<Vec<u8>>,
+}
+
+impl From<&IcebergPartitionSummary> for AvroPartitionSummary {
+    fn from(summary: &IcebergPartitionSummary) -> Self {
+        Self {
+            contains_null: summary.contains_null,
+            contains_nan: Some(summary.contains_nan),
+            lower_bound: summary.lower_bound.clone(),
+            upper_bound: summary.upper_bound.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct AvroManifestEntry {
+    status: i32,
+    snapshot_id: i64,
+    sequence_number: Option<i64>,
+    data_file: AvroDataFile,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct AvroManifestFileEntry {
+    manifest_path: String,
+    manifest_length: i64,
+    partition_spec_id: i32,
+    content: i32,
+    sequence_number: i64,
+    min_sequence_number: i64,
+    added_snapshot_id: i64,
+    added_data_files_count: i32,
+    existing_data_files_count: i32,
+    deleted_data_files_count: i32,
+    added_rows_count: i64,
+    existing_rows_count: i64,
+    deleted_rows_count: i64,
+    partitions: Vec<AvroPartitionSummary>,
+}
+
+/// `manifest_entry` Avro schema (Iceberg v2) template, with `field-id`
+/// annotations matching the spec's reserved field ids for the manifest and
+/// `data_file` structs. The `__PARTITION_SCHEMA__` placeholder is filled in
+/// per-table by [`IcebergSink::manifest_entry_schema`] with the `partition`
+/// record generated from that table's actual partition spec — it can't be
+/// a fixed schema like the rest of this one, since its fields depend on how
+/// many partition columns the table has and what they're named.
+const MANIFEST_ENTRY_SCHEMA_TEMPLATE: &str = r#"{
+  "type": "record",
+  "name": "manifest_entry",
+  "fields": [
+    {"name": "status", "type": "int", "field-id": 0},
+    {"name": "snapshot_id", "type": ["null", "long"], "default": null, "field-id": 1},
+    {"name": "sequence_number", "type": ["null", "long"], "default": null, "field-id": 3},
+    {"name": "data_file", "field-id": 2, "type": {
+      "type": "record",
+      "name": "r2",
+      "fields": [
+        {"name": "content", "type": "int", "field-id": 134},
+        {"name": "file_path", "type": "string", "field-id": 100},
+        {"name": "file_format", "type": "string", "field-id": 101},
+        {"name": "partition", "field-id": 102, "type": __PARTITION_SCHEMA__},
+        {"name": "record_count", "type": "long", "field-id": 103},
+        {"name": "file_size_in_bytes", "type": "long", "field-id": 104},
+        {"name": "column_sizes", "field-id": 108, "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k117_v118", "fields": [
+            {"name": "key", "type": "int"}, {"name": "value", "type": "long"}
+          ]}}], "default": null},
+        {"name": "value_counts", "field-id": 109, "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k119_v120", "fields": [
+            {"name": "key", "type": "int"}, {"name": "value", "type": "long"}
+          ]}}], "default": null},
+        {"name": "null_value_counts", "field-id": 110, "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k121_v122", "fields": [
+            {"name": "key", "type": "int"}, {"name": "value", "type": "long"}
+          ]}}], "default": null},
+        {"name": "nan_value_counts", "field-id": 137, "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k138_v139", "fields": [
+            {"name": "key", "type": "int"}, {"name": "value", "type": "long"}
+          ]}}], "default": null},
+        {"name": "distinct_counts", "field-id": 111, "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k123_v124", "fields": [
+            {"name": "key", "type": "int"}, {"name": "value", "type": "long"}
+          ]}}], "default": null},
+        {"name": "lower_bounds", "field-id": 125, "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k126_v127", "fields": [
+            {"name": "key", "type": "int"}, {"name": "value", "type": "bytes"}
+          ]}}], "default": null},
+        {"name": "upper_bounds", "field-id": 128, "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k129_v130", "fields": [
+            {"name": "key", "type": "int"}, {"name": "value", "type": "bytes"}
+          ]}}], "default": null},
+        {"name": "split_offsets", "field-id": 132, "type": ["null", {"type": "array", "items": "long"}], "default": null},
+        {"name": "equality_ids", "field-id": 135, "type": ["null", {"type": "array", "items": "int"}], "default": null},
+        {"name": "sort_order_id", "type": ["null", "int"], "default": null, "field-id": 140}
+      ]
+    }}
+  ]
+}"#;
+
+/// `manifest_file` Avro schema (Iceberg v2), used for the manifest list
+const MANIFEST_FILE_SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "manifest_file",
+  "fields": [
+    {"name": "manifest_path", "type": "string", "field-id": 500},
+    {"name": "manifest_length", "type": "long", "field-id": 501},
+    {"name": "partition_spec_id", "type": "int", "field-id": 502},
+    {"name": "content", "type": "int", "field-id": 517},
+    {"name": "sequence_number", "type": "long", "field-id": 515},
+    {"name": "min_sequence_number", "type": "long", "field-id": 516},
+    {"name": "added_snapshot_id", "type": "long", "field-id": 503},
+    {"name": "added_data_files_count", "type": "int", "field-id": 504},
+    {"name": "existing_data_files_count", "type": "int", "field-id": 505},
+    {"name": "deleted_data_files_count", "type": "int", "field-id": 506},
+    {"name": "added_rows_count", "type": "long", "field-id": 512},
+    {"name": "existing_rows_count", "type": "long", "field-id": 513},
+    {"name": "deleted_rows_count", "type": "long", "field-id": 514},
+    {"name": "partitions", "field-id": 507, "type": ["null", {"type": "array", "items": {
+      "type": "record", "name": "r508", "fields": [
+        {"name": "contains_null", "type": "boolean", "field-id": 509},
+        {"name": "contains_nan", "type": ["null", "boolean"], "default": null, "field-id": 518},
+        {"name": "lower_bound", "type": ["null", "bytes"], "default": null, "field-id": 510},
+        {"name": "upper_bound", "type": ["null", "bytes"], "default": null, "field-id": 511}
+      ]
+    }}], "default": null}
+  ]
+}"#;
+
+/// Change-event marker column a batch must carry when
+/// [`IcebergSink::with_upsert_keys`] is set, holding one of `"I"`
+/// (insert), `"U"` (update), or `"D"` (delete) per row
+const CDC_OP_COLUMN: &str = "_cdc_op";
+
+/// Returned when `version-hint.text` no longer matches the ETag this writer
+/// last read it at, so the caller knows to reload the latest metadata and
+/// retry instead of treating it as a hard failure
+#[derive(Debug)]
+struct CatalogConflict;
+
+impl std::fmt::Display for CatalogConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "table metadata was concurrently modified by another writer")
+    }
+}
+
+impl std::error::Error for CatalogConflict {}
+
+/// A table's metadata as loaded from a [`Catalog`], paired with the version
+/// token [`Catalog::commit_table`] must still match for the commit to succeed
+struct LoadedTable {
+    metadata: IcebergTableMetadata,
+    version: TableVersion,
+}
+
+/// Opaque marker a [`Catalog`] uses to detect that a table changed between
+/// `load_table` and `commit_table`
+enum TableVersion {
+    /// [`StorageCatalog`]: the metadata file name this was read at, plus the
+    /// `version-hint.text` pointer's ETag
+    Storage { file_name: String, hint_etag: Option<String> },
+    /// [`RestCatalog`]: no client-side token needed — the server checks
+    /// `commit_table`'s `requirements` against its own copy
+    Rest,
+}
+
+/// Appends `base`'s own metadata file to `metadata.metadata_log`, recording
+/// it as an ancestor of the version about to be committed. Only
+/// [`TableVersion::Storage`] exposes a client-side file name to record;
+/// [`RestCatalog`] tracks this history server-side instead.
+fn record_metadata_log_entry(metadata: &mut IcebergTableMetadata, base: &LoadedTable) {
+    if let TableVersion::Storage { file_name, .. } = &base.version {
+        metadata.metadata_log.push(IcebergMetadataLogEntry {
+            timestamp_ms: base.metadata.last_updated_ms,
+            metadata_file: file_name.clone(),
+        });
+    }
+}
+
+/// Backend that owns an Iceberg table's metadata, independent of where the
+/// table's data files themselves live (those always go straight to
+/// `IcebergSink::object_store`). [`StorageCatalog`] writes versioned
+/// metadata objects to the table's own object store location (this sink's
+/// original behavior, made safe under concurrent writers); [`RestCatalog`]
+/// delegates to a shared Iceberg REST Catalog server so the table can be
+/// registered alongside ones written by other engines.
+///
+/// This supersedes what the now-deleted `TopicMappingManager` attempted: a
+/// `FileCatalog`/`RestCatalog` pair scoped to `topic_mapping.rs`, which was
+/// dropped wholesale as dead code (no call sites) before either backend saw
+/// real use.
+#[async_trait]
+trait Catalog: Send + Sync {
+    /// Loads the current metadata for `table`, or `None` if it doesn't exist yet
+    async fn load_table(&self, table: &str) -> Result<Option<LoadedTable>>;
+
+    /// Registers a brand-new table with its initial metadata. Fails with
+    /// [`CatalogConflict`] if another writer created it first.
+    async fn create_table(&self, table: &str, metadata: &IcebergTableMetadata) -> Result<()>;
+
+    /// Persists `new_metadata` for an existing table, first verifying that
+    /// `base` (what the caller read the change from) is still current.
+    /// Returns a [`CatalogConflict`] error if another writer committed first.
+    async fn commit_table(&self, table: &str, base: &LoadedTable, new_metadata: &IcebergTableMetadata) -> Result<()>;
+}
+
+/// [`Catalog`] backed by versioned `{table}/metadata/v<N>-<uuid>.metadata.json`
+/// objects plus a `{table}/metadata/version-hint.text` pointer, following the
+/// Iceberg metadata file layout. A commit writes the new version file, then
+/// conditionally swaps the pointer with an `If-Match` on the ETag it was read
+/// at, so two concurrent writers can't silently clobber each other.
+///
+/// This is the surviving implementation of the optimistic-concurrency
+/// requirement-check protocol `TopicMappingManager` attempted in
+/// `topic_mapping.rs` before that manager was deleted wholesale as dead
+/// code with no call sites.
+struct StorageCatalog {
+    object_store: Arc<dyn ObjectStore>,
+}
+
+impl StorageCatalog {
+    fn new(object_store: Arc<dyn ObjectStore>) -> Self {
+        Self { object_store }
+    }
+
+    fn version_hint_path(table: &str) -> ObjectPath {
+        ObjectPath::from(format!("{table}/metadata/version-hint.text").as_str())
+    }
+
+    fn metadata_file_path(table: &str, file_name: &str) -> ObjectPath {
+        ObjectPath::from(format!("{table}/metadata/{file_name}").as_str())
+    }
+}
+
+#[async_trait]
+impl Catalog for StorageCatalog {
+    async fn load_table(&self, table: &str) -> Result<Option<LoadedTable>> {
+        let hint_path = Self::version_hint_path(table);
+        let hint = match self.object_store.get(&hint_path).await {
+            Ok(hint) => hint,
+            Err(_) => return Ok(None),
+        };
+        let hint_etag = hint.meta.e_tag.clone();
+        let file_name = String::from_utf8(hint.bytes().await?.to_vec())?.trim().to_string();
+
+        let metadata_path = Self::metadata_file_path(table, &file_name);
+        let data = self
+            .object_store
+            .get(&metadata_path)
+            .await
+            .with_context(|| format!("reading metadata for table {table} at {file_name}"))?;
+        let metadata: IcebergTableMetadata = serde_json::from_str(&String::from_utf8(data.bytes().await?.to_vec())?)?;
+
+        Ok(Some(LoadedTable { metadata, version: TableVersion::Storage { file_name, hint_etag } }))
+    }
+
+    async fn create_table(&self, table: &str, metadata: &IcebergTableMetadata) -> Result<()> {
+        let file_name = format!("v1-{}.metadata.json", Uuid::new_v4());
+        let metadata_json = serde_json::to_string_pretty(metadata)?;
+        self.object_store
+            .put_opts(
+                &Self::metadata_file_path(table, &file_name),
+                metadata_json.into(),
+                PutOptions { mode: PutMode::Create, ..Default::default() },
+            )
+            .await
+            .map_err(|_| anyhow::Error::new(CatalogConflict))?;
+
+        self.object_store
+            .put_opts(
+                &Self::version_hint_path(table),
+                file_name.into(),
+                PutOptions { mode: PutMode::Create, ..Default::default() },
+            )
+            .await
+            .map_err(|_| anyhow::Error::new(CatalogConflict))?;
+
+        Ok(())
+    }
+
+    async fn commit_table(&self, table: &str, base: &LoadedTable, new_metadata: &IcebergTableMetadata) -> Result<()> {
+        let TableVersion::Storage { file_name: base_file_name, hint_etag } = &base.version else {
+            anyhow::bail!("StorageCatalog received a version token from a different catalog backend");
+        };
+        let new_version = base_file_name
+            .strip_prefix('v')
+            .and_then(|rest| rest.split('-').next())
+            .and_then(|prefix| prefix.parse::<u64>().ok())
+            .with_context(|| format!("parsing version from metadata file name: {base_file_name}"))?
+            + 1;
+        let file_name = format!("v{new_version}-{}.metadata.json", Uuid::new_v4());
+
+        let metadata_json = serde_json::to_string_pretty(new_metadata)?;
+        self.object_store
+            .put_opts(
+                &Self::metadata_file_path(table, &file_name),
+                metadata_json.into(),
+                PutOptions { mode: PutMode::Create, ..Default::default() },
+            )
+            .await
+            .map_err(|_| anyhow::Error::new(CatalogConflict))?;
+
+        let hint_mode = match hint_etag {
+            Some(e_tag) => PutMode::Update(UpdateVersion { e_tag: Some(e_tag.clone()), version: None }),
+            None => PutMode::Create,
+        };
+        self.object_store
+            .put_opts(
+                &Self::version_hint_path(table),
+                file_name.into(),
+                PutOptions { mode: hint_mode, ..Default::default() },
+            )
+            .await
+            .map_err(|_| anyhow::Error::new(CatalogConflict))?;
+
+        Ok(())
+    }
+}
+
+/// [`Catalog`] backed by an Iceberg REST Catalog server, speaking the
+/// `GET /v1/namespaces/{ns}/tables/{name}` and `POST .../tables[/{name}]`
+/// endpoints of the [Iceberg REST Catalog spec](https://iceberg.apache.org/spec/#rest-catalog)
+struct RestCatalog {
+    http: reqwest::Client,
+    base_uri: String,
+    token: Option<String>,
+}
+
+impl RestCatalog {
+    fn new(base_uri: String, token: Option<String>) -> Self {
+        Self { http: reqwest::Client::new(), base_uri, token }
+    }
+
+    /// Splits a dotted table name like `analytics.events` into its REST
+    /// namespace (`analytics`) and table name (`events`); tables with no
+    /// namespace prefix live in a `default` namespace
+    fn namespace_and_name(table: &str) -> (String, String) {
+        match table.rsplit_once('.') {
+            Some((namespace, name)) => (namespace.to_string(), name.to_string()),
+            None => ("default".to_string(), table.to_string()),
+        }
+    }
+
+    fn table_url(&self, table: &str) -> String {
+        let (namespace, name) = Self::namespace_and_name(table);
+        format!("{}/v1/namespaces/{namespace}/tables/{name}", self.base_uri)
+    }
+
+    fn tables_url(&self, table: &str) -> String {
+        let (namespace, _) = Self::namespace_and_name(table);
+        format!("{}/v1/namespaces/{namespace}/tables", self.base_uri)
+    }
+
+    fn request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+}
+
+#[async_trait]
+impl Catalog for RestCatalog {
+    async fn load_table(&self, table: &str) -> Result<Option<LoadedTable>> {
+        let resp = self
+            .request(self.http.get(self.table_url(table)))
+            .send()
+            .await
+            .with_context(|| format!("load table {table} from REST catalog"))?;
+
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body: LoadTableResponse = resp
+            .error_for_status()
+            .with_context(|| format!("REST catalog rejected load of table {table}"))?
+            .json()
+            .await
+            .with_context(|| format!("parse REST catalog response for table {table}"))?;
+        Ok(Some(LoadedTable { metadata: body.metadata, version: TableVersion::Rest }))
+    }
+
+    async fn create_table(&self, table: &str, metadata: &IcebergTableMetadata) -> Result<()> {
+        let (_, name) = Self::namespace_and_name(table);
+        let request = CreateTableRequest { name, location: metadata.location.clone(), metadata: metadata.clone() };
+        self.request(self.http.post(self.tables_url(table)))
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format!("create table {table} on REST catalog"))?
+            .error_for_status()
+            .with_context(|| format!("REST catalog rejected creation of table {table}"))?;
+        Ok(())
+    }
+
+    async fn commit_table(&self, table: &str, base: &LoadedTable, new_metadata: &IcebergTableMetadata) -> Result<()> {
+        let mut updates = Vec::new();
+        if new_metadata.current_schema_id != base.metadata.current_schema_id {
+            if let Some(schema) = new_metadata.schemas.get(&new_metadata.current_schema_id) {
+                updates.push(MetadataUpdate::AddSchema { schema: schema.clone() });
+                updates.push(MetadataUpdate::SetCurrentSchema { schema_id: new_metadata.current_schema_id });
+            }
+        }
+        if new_metadata.current_snapshot_id != base.metadata.current_snapshot_id {
+            if let Some(snapshot) = new_metadata.current_snapshot_id.and_then(|id| new_metadata.snapshots.get(&id)) {
+                updates.push(MetadataUpdate::AddSnapshot { snapshot: snapshot.clone() });
+                updates.push(MetadataUpdate::SetSnapshotRef {
+                    ref_name: "main".to_string(),
+                    ref_type: "branch".to_string(),
+                    snapshot_id: snapshot.snapshot_id,
+                });
+            }
+        }
+
+        let request = CommitTableRequest {
+            requirements: vec![CommitRequirement::AssertCurrentSnapshotId {
+                current_snapshot_id: base.metadata.current_snapshot_id,
+            }],
+            updates,
+        };
+        let resp = self
+            .request(self.http.post(self.table_url(table)))
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format!("commit metadata for table {table} to REST catalog"))?;
+
+        if resp.status() == reqwest::StatusCode::CONFLICT {
+            anyhow::bail!(CatalogConflict);
+        }
+        resp.error_for_status().with_context(|| format!("REST catalog rejected metadata commit for table {table}"))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LoadTableResponse {
+    metadata: IcebergTableMetadata,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateTableRequest {
+    name: String,
+    location: String,
+    #[serde(rename = "metadata")]
+    metadata: IcebergTableMetadata,
+}
+
+/// Minimal `CommitTableRequest` body: a `requirements` list the server must
+/// still satisfy against its own copy of the metadata before applying
+/// `updates`, matching the Iceberg REST Catalog commit protocol
+#[derive(Debug, Serialize)]
+struct CommitTableRequest {
+    requirements: Vec<CommitRequirement>,
+    updates: Vec<MetadataUpdate>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+enum CommitRequirement {
+    #[serde(rename = "assert-current-snapshot-id")]
+    AssertCurrentSnapshotId {
+        #[serde(rename = "current-snapshot-id")]
+        current_snapshot_id: Option<i64>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "action")]
+enum MetadataUpdate {
+    #[serde(rename = "add-snapshot")]
+    AddSnapshot { snapshot: IcebergSnapshot },
+    #[serde(rename = "set-snapshot-ref")]
+    SetSnapshotRef {
+        #[serde(rename = "ref-name")]
+        ref_name: String,
+        #[serde(rename = "type")]
+        ref_type: String,
+        #[serde(rename = "snapshot-id")]
+        snapshot_id: i64,
+    },
+    #[serde(rename = "add-schema")]
+    AddSchema { schema: IcebergSchema },
+    #[serde(rename = "set-current-schema")]
+    SetCurrentSchema {
+        #[serde(rename = "schema-id")]
+        schema_id: i32,
+    },
+}
+
 pub struct IcebergSink {
     id: String,
     schema: SchemaRef,
@@ -221,9 +808,14 @@ pub struct IcebergSink {
     secret_key: String,
     region: String,
     object_store: Option<Arc<dyn ObjectStore>>,
+    catalog_config: rde_core::CatalogConfig,
+    catalog: Option<Box<dyn Catalog>>,
     table_metadata: Option<IcebergTableMetadata>,
     current_snapshot_id: i64,
     data_files: Vec<IcebergDataFile>,
+    delete_files: Vec<IcebergDataFile>,
+    partition_by: Vec<rde_core::PartitionFieldConfig>,
+    upsert_keys: Vec<String>,
 }
 
 impl IcebergSink {
@@ -247,12 +839,214 @@ impl IcebergSink {
             secret_key,
             region,
             object_store: None,
+            catalog_config: rde_core::CatalogConfig::File,
+            catalog: None,
             table_metadata: None,
             current_snapshot_id: 1,
             data_files: Vec::new(),
+            delete_files: Vec::new(),
+            partition_by: Vec::new(),
+            upsert_keys: Vec::new(),
+        }
+    }
+
+    /// Partitions this table by `partition_by`, a list of `(column,
+    /// transform)` pairs evaluated in order to build each row's Hive-style
+    /// partition path (e.g. `region=us/event_time_day=19723`). Each incoming
+    /// batch is split into one sub-batch per distinct partition value at
+    /// write time. No partitioning is applied if this is never called.
+    pub fn with_partition_spec(mut self, partition_by: Vec<rde_core::PartitionFieldConfig>) -> Self {
+        self.partition_by = partition_by;
+        self
+    }
+
+    /// Selects where this table's Iceberg metadata is stored and how
+    /// commits to it are coordinated; see [`rde_core::CatalogConfig`].
+    /// Defaults to the object store itself (`CatalogConfig::File`, backed
+    /// by [`StorageCatalog`]) if never called.
+    pub fn with_catalog(mut self, catalog: rde_core::CatalogConfig) -> Self {
+        self.catalog_config = catalog;
+        self
+    }
+
+    /// Switches this sink from append-only to upsert mode for a
+    /// change-data-capture stream, keyed by `upsert_keys` (identity
+    /// columns that make a row unique). Each batch must then carry a
+    /// [`CDC_OP_COLUMN`] string column: `"I"`/`"U"` rows are written as
+    /// data files (minus that column) and `"U"`/`"D"` rows additionally
+    /// produce an equality-delete file keyed on `upsert_keys`, so readers
+    /// applying the deletes see the update's new value replace the old
+    /// one. No partitioning is applied in this mode. Leave empty (the
+    /// default) for plain append-only writes.
+    pub fn with_upsert_keys(mut self, upsert_keys: Vec<String>) -> Self {
+        self.upsert_keys = upsert_keys;
+        self
+    }
+
+    /// Looks up `name`'s stable Iceberg field ID from the loaded table
+    /// metadata's `column_ids` (the same ids [`Self::assign_field_ids`]
+    /// hands out), falling back to its 1-based position in `self.schema`
+    /// only if no metadata has been loaded yet — which normal operation
+    /// never hits, since [`Self::create_or_load_table_metadata`] always
+    /// runs first
+    fn field_id_for(&self, name: &str) -> i32 {
+        self.table_metadata
+            .as_ref()
+            .and_then(|metadata| metadata.column_ids.get(name).copied())
+            .unwrap_or_else(|| {
+                self.schema
+                    .fields()
+                    .iter()
+                    .position(|field| field.name() == name)
+                    .map(|position| (position + 1) as i32)
+                    .unwrap_or(0)
+            })
+    }
+
+    /// Maps `self.upsert_keys` to their field ids in the current schema,
+    /// in the same order, for use as an equality-delete file's `equality_ids`
+    fn key_field_ids(&self) -> Vec<i32> {
+        self.upsert_keys
+            .iter()
+            .filter(|key| self.schema.fields().iter().any(|field| field.name() == *key))
+            .map(|key| self.field_id_for(key))
+            .collect()
+    }
+
+    /// Builds this table's partition spec fields from `self.partition_by`,
+    /// assigning partition field ids starting at 1000 (the first id the
+    /// Iceberg spec reserves for partition fields) and looking up each
+    /// column's stable source field id
+    ///
+    /// This is the surviving implementation of the partition-spec support
+    /// the now-deleted `TopicMappingManager` attempted in `topic_mapping.rs`
+    /// before it was dropped wholesale as dead code (no call sites).
+    fn partition_spec_fields(&self) -> Vec<IcebergPartitionField> {
+        self.partition_by
+            .iter()
+            .enumerate()
+            .map(|(index, config)| IcebergPartitionField {
+                source_id: self.field_id_for(&config.column),
+                field_id: 1000 + index as i32,
+                name: iceberg_partition::partition_field_name(&config.column, &config.transform),
+                transform: config.transform.clone(),
+            })
+            .collect()
+    }
+
+    /// Builds the `partition` sub-record's Avro field list for this table's
+    /// current partition spec: one nullable-string field per
+    /// [`Self::partition_spec_fields`] entry, named after that spec field
+    /// and `field-id`-annotated with it (starting at 1000, the first id the
+    /// Iceberg spec reserves for partition fields). Nullable so a file with
+    /// no value for a given spec field round-trips as null instead of the
+    /// static, always-empty `r102` record this used to be, which silently
+    /// dropped every partition value for every partitioned table.
+    fn partition_record_schema(&self) -> String {
+        let fields: Vec<String> = self
+            .partition_spec_fields()
+            .iter()
+            .map(|field| {
+                format!(
+                    r#"{{"name": "{name}", "field-id": {field_id}, "type": ["null", "string"], "default": null}}"#,
+                    name = field.name,
+                    field_id = field.field_id,
+                )
+            })
+            .collect();
+        format!(r#"{{"type": "record", "name": "r102", "fields": [{}]}}"#, fields.join(", "))
+    }
+
+    /// `manifest_entry` Avro schema (Iceberg v2) for this table, with the
+    /// `data_file.partition` sub-record generated from the table's current
+    /// partition spec by [`Self::partition_record_schema`] instead of a
+    /// static, always-empty record.
+    fn manifest_entry_schema(&self) -> String {
+        MANIFEST_ENTRY_SCHEMA_TEMPLATE.replace("__PARTITION_SCHEMA__", &self.partition_record_schema())
+    }
+
+    /// Converts `df` to its Avro wire representation, resolving
+    /// `df.partition` against [`Self::partition_spec_fields`] so the result
+    /// has exactly one entry per field [`Self::partition_record_schema`]
+    /// declares (`None` rather than a missing map entry when `df` has no
+    /// value for a spec field), instead of passing `df.partition`'s raw
+    /// keys through unchecked.
+    fn to_avro_data_file(&self, df: &IcebergDataFile) -> AvroDataFile {
+        let entries = |m: &HashMap<i32, i64>| m.iter().map(|(&key, &value)| AvroI64Entry { key, value }).collect();
+        let binary_entries =
+            |m: &HashMap<i32, Vec<u8>>| m.iter().map(|(&key, value)| AvroBinaryEntry { key, value: value.clone() }).collect();
+        let partition = self
+            .partition_spec_fields()
+            .iter()
+            .map(|field| (field.name.clone(), df.partition.get(&field.name).cloned()))
+            .collect();
+        AvroDataFile {
+            content: df.content,
+            file_path: df.file_path.clone(),
+            file_format: df.file_format.clone(),
+            partition,
+            record_count: df.record_count,
+            file_size_in_bytes: df.file_size_in_bytes,
+            column_sizes: entries(&df.column_sizes),
+            value_counts: entries(&df.value_counts),
+            null_value_counts: entries(&df.null_value_counts),
+            nan_value_counts: entries(&df.nan_value_counts),
+            distinct_counts: entries(&df.distinct_counts),
+            lower_bounds: binary_entries(&df.lower_bounds),
+            upper_bounds: binary_entries(&df.upper_bounds),
+            split_offsets: df.split_offsets.clone(),
+            equality_ids: df.equality_ids.clone(),
+            sort_order_id: df.sort_order_id,
+        }
+    }
+
+    /// Converts `entry` to its Avro wire representation; see
+    /// [`Self::to_avro_data_file`] for why this needs `self` rather than
+    /// being a plain `From` impl.
+    fn to_avro_manifest_entry(&self, entry: &IcebergManifestEntry) -> AvroManifestEntry {
+        AvroManifestEntry {
+            status: entry.status,
+            snapshot_id: entry.snapshot_id,
+            sequence_number: entry.sequence_number,
+            data_file: self.to_avro_data_file(&entry.data_file),
         }
     }
 
+    /// Builds each partition spec field's manifest-list-level summary from
+    /// `data_files`' already-resolved partition values: `contains_null` if
+    /// any file has no value for that field, and the lower/upper bound as
+    /// the min/max of the UTF-8 bytes of the values that do have one (this
+    /// sink only keeps partition values as the rendered strings from the
+    /// partition path, not the transform-typed value, so that's what gets
+    /// encoded here too).
+    fn partition_summaries(&self, data_files: &[IcebergDataFile]) -> Vec<IcebergPartitionSummary> {
+        self.partition_spec_fields()
+            .iter()
+            .map(|field| {
+                let mut contains_null = false;
+                let mut lower_bound: Option<Vec<u8>> = None;
+                let mut upper_bound: Option<Vec<u8>> = None;
+                for df in data_files {
+                    match df.partition.get(&field.name) {
+                        None => contains_null = true,
+                        Some(value) => {
+                            let bytes = value.as_bytes().to_vec();
+                            lower_bound = Some(match lower_bound {
+                                Some(current) if current <= bytes => current,
+                                _ => bytes.clone(),
+                            });
+                            upper_bound = Some(match upper_bound {
+                                Some(current) if current >= bytes => current,
+                                _ => bytes,
+                            });
+                        }
+                    }
+                }
+                IcebergPartitionSummary { contains_null, contains_nan: false, lower_bound, upper_bound }
+            })
+            .collect()
+    }
+
     async fn initialize_object_store(&mut self) -> Result<()> {
         if self.object_store.is_none() {
             info!("Initializing object store for MinIO...");
@@ -274,10 +1068,24 @@ impl IcebergSink {
         } else {
             info!("Object store already initialized");
         }
+
+        if self.catalog.is_none() {
+            let object_store = self.object_store.as_ref().expect("just initialized above").clone();
+            self.catalog = Some(match &self.catalog_config {
+                rde_core::CatalogConfig::File => Box::new(StorageCatalog::new(object_store)) as Box<dyn Catalog>,
+                rde_core::CatalogConfig::Rest { uri, token } => {
+                    Box::new(RestCatalog::new(uri.clone(), token.clone())) as Box<dyn Catalog>
+                }
+            });
+        }
         Ok(())
     }
 
-    async fn write_parquet_file(&self, batch: &RecordBatch, file_path: &str) -> Result<u64> {
+    fn catalog(&self) -> Result<&dyn Catalog> {
+        self.catalog.as_deref().ok_or_else(|| anyhow::anyhow!("Catalog not initialized"))
+    }
+
+    async fn write_parquet_file(&self, batch: &RecordBatch, file_path: &str) -> Result<(u64, ParquetFileStats)> {
         let object_store = self.object_store.as_ref().ok_or_else(|| {
             anyhow::anyhow!("Object store not initialized")
         })?;
@@ -286,40 +1094,125 @@ impl IcebergSink {
         let mut buffer = Vec::new();
         let props = WriterProperties::builder().build();
         let mut writer = ArrowWriter::try_new(&mut buffer, self.schema.clone(), Some(props))?;
-        
+
         writer.write(batch)?;
-        writer.close()?;
+        let parquet_metadata = writer.close()?;
+
+        let mut value_counts = HashMap::new();
+        let mut null_value_counts = HashMap::new();
+        let mut lower_bounds = HashMap::new();
+        let mut upper_bounds = HashMap::new();
+        for (index, field) in self.schema.fields().iter().enumerate() {
+            // Same stable id convert_arrow_schema_to_iceberg assigns this column
+            let field_id = self.field_id_for(field.name());
+            let array = batch.column(index);
+            value_counts.insert(field_id, batch.num_rows() as i64);
+            null_value_counts.insert(field_id, array.null_count() as i64);
+            if let Some((lower, upper)) = column_bounds(array, field.data_type()) {
+                lower_bounds.insert(field_id, lower);
+                upper_bounds.insert(field_id, upper);
+            }
+        }
+
+        let mut column_sizes: HashMap<i32, i64> = HashMap::new();
+        for row_group in &parquet_metadata.row_groups {
+            for (index, column) in row_group.columns.iter().enumerate() {
+                let field_id = self
+                    .schema
+                    .fields()
+                    .get(index)
+                    .map(|field| self.field_id_for(field.name()))
+                    .unwrap_or((index + 1) as i32);
+                let size = column.meta_data.as_ref().map(|meta| meta.total_compressed_size).unwrap_or(0);
+                *column_sizes.entry(field_id).or_insert(0) += size;
+            }
+        }
 
         // Write to object store
         let path = ObjectPath::from(file_path);
         let put_options = PutOptions::default();
         object_store.put_opts(&path, buffer.clone().into(), put_options).await?;
 
-        Ok(buffer.len() as u64)
+        Ok((
+            buffer.len() as u64,
+            ParquetFileStats { column_sizes, value_counts, null_value_counts, lower_bounds, upper_bounds },
+        ))
     }
 
+    /// Returns the table's current Iceberg schema, with each field's stable
+    /// id (see [`Self::assign_field_ids`]) rather than a position-derived
+    /// one. Reads straight from `self.table_metadata`, which
+    /// [`Self::create_or_load_table_metadata`] always populates before any
+    /// write path runs; the positional fallback below only matters if this
+    /// is somehow called before that.
     fn convert_arrow_schema_to_iceberg(&self) -> IcebergSchema {
-        let mut fields = Vec::new();
-        let mut field_id = 1;
-        
-        for field in self.schema.fields() {
-            fields.push(IcebergField {
-                id: field_id,
+        if let Some(metadata) = &self.table_metadata {
+            if let Some(schema) = metadata.schemas.get(&metadata.current_schema_id) {
+                return schema.clone();
+            }
+        }
+
+        let fields = self
+            .schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| IcebergField {
+                id: (index + 1) as i32,
                 name: field.name().clone(),
-                field_type: self.convert_arrow_type_to_iceberg(field.data_type()),
+                field_type: Self::convert_arrow_type_to_iceberg(field.data_type()),
                 required: !field.is_nullable(),
                 doc: None,
-            });
-            field_id += 1;
-        }
+            })
+            .collect();
 
-        IcebergSchema {
-            schema_id: 0,
-            fields,
-        }
+        IcebergSchema { schema_id: 0, fields }
+    }
+
+    /// Builds `IcebergField`s for `schema`, assigning each column the id it
+    /// already has in `metadata.column_ids` (preserved across renumbers and
+    /// reorders), or the next unused id counted from `metadata.last_column_id`
+    /// if it's genuinely new. `metadata.column_ids` and `last_column_id` are
+    /// updated in place; neither is ever decreased or reused.
+    fn assign_field_ids(metadata: &mut IcebergTableMetadata, schema: &SchemaRef) -> Vec<IcebergField> {
+        schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let id = if let Some(&id) = metadata.column_ids.get(field.name()) {
+                    id
+                } else {
+                    metadata.last_column_id += 1;
+                    metadata.column_ids.insert(field.name().to_string(), metadata.last_column_id);
+                    metadata.last_column_id
+                };
+
+                IcebergField {
+                    id,
+                    name: field.name().to_string(),
+                    field_type: Self::convert_arrow_type_to_iceberg(field.data_type()),
+                    required: !field.is_nullable(),
+                    doc: None,
+                }
+            })
+            .collect()
     }
 
-    fn convert_arrow_type_to_iceberg(&self, arrow_type: &datafusion::arrow::datatypes::DataType) -> String {
+    /// Checks whether `metadata`'s current schema version already matches
+    /// `self.schema` field-for-field, to avoid minting a pointless new
+    /// schema version on every commit
+    fn schema_matches(metadata: &IcebergTableMetadata, schema: &SchemaRef) -> bool {
+        metadata.schemas.get(&metadata.current_schema_id).is_some_and(|stored| {
+            stored.fields.len() == schema.fields().len()
+                && stored.fields.iter().zip(schema.fields()).all(|(stored_field, arrow_field)| {
+                    stored_field.name == *arrow_field.name()
+                        && stored_field.field_type == Self::convert_arrow_type_to_iceberg(arrow_field.data_type())
+                        && stored_field.required == !arrow_field.is_nullable()
+                })
+        })
+    }
+
+    fn convert_arrow_type_to_iceberg(arrow_type: &datafusion::arrow::datatypes::DataType) -> String {
         match arrow_type {
             datafusion::arrow::datatypes::DataType::Int64 => "long".to_string(),
             datafusion::arrow::datatypes::DataType::Int32 => "int".to_string(),
@@ -334,59 +1227,112 @@ impl IcebergSink {
     }
 
     async fn create_or_load_table_metadata(&mut self) -> Result<()> {
-        let metadata_path = format!("{}/metadata/metadata.json", self.table_name);
-        
-        if let Some(object_store) = &self.object_store {
-            // Try to load existing metadata
-            let path = ObjectPath::from(metadata_path.as_str());
-            match object_store.get(&path).await {
-                Ok(data) => {
-                    let metadata_str = String::from_utf8(data.bytes().await?.to_vec())?;
-                    self.table_metadata = Some(serde_json::from_str(&metadata_str)?);
-                    info!("Loaded existing table metadata");
+        if self.object_store.is_none() {
+            return Ok(());
+        }
+
+        match self.catalog()?.load_table(&self.table_name).await? {
+            Some(loaded) => {
+                self.current_snapshot_id = loaded.metadata.snapshots.keys().copied().max().unwrap_or(0) + 1;
+                info!("Loaded existing table metadata for table {}", self.table_name);
+                self.table_metadata = Some(loaded.metadata);
+                self.sync_schema_with_catalog().await?;
+            }
+            None => {
+                info!("Creating new table metadata");
+                self.create_new_table_metadata().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds a new schema version to the table's catalog metadata if the
+    /// pipeline's current `self.schema` no longer matches the table's
+    /// stored current schema (e.g. this pipeline's Arrow schema evolved
+    /// since the table was created, or since this sink last ran). No-op if
+    /// they already match. Existing columns keep the stable ids
+    /// [`Self::assign_field_ids`] gave them; only genuinely new columns get
+    /// new ones. Uses the same load/mutate/conditionally-commit/retry loop
+    /// as [`Self::create_snapshot`] so a concurrent writer's schema or
+    /// snapshot commit can't be silently clobbered.
+    async fn sync_schema_with_catalog(&mut self) -> Result<()> {
+        const MAX_ATTEMPTS: u32 = 5;
+        let mut backoff = std::time::Duration::from_millis(50);
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            let base = self
+                .catalog()?
+                .load_table(&self.table_name)
+                .await?
+                .with_context(|| format!("table {} disappeared while syncing its schema", self.table_name))?;
+
+            if Self::schema_matches(&base.metadata, &self.schema) {
+                self.table_metadata = Some(base.metadata);
+                return Ok(());
+            }
+
+            let mut metadata = base.metadata.clone();
+            record_metadata_log_entry(&mut metadata, &base);
+            let new_schema_id = metadata.schemas.keys().copied().max().unwrap_or(0) + 1;
+            let fields = Self::assign_field_ids(&mut metadata, &self.schema);
+            let schema = IcebergSchema { schema_id: new_schema_id, fields };
+            metadata.schemas.insert(new_schema_id, schema.clone());
+            metadata.schema = schema;
+            metadata.current_schema_id = new_schema_id;
+            metadata.last_updated_ms = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as i64;
+
+            match self.catalog()?.commit_table(&self.table_name, &base, &metadata).await {
+                Ok(()) => {
+                    info!("Iceberg table {} schema evolved to version {}", self.table_name, new_schema_id);
+                    self.table_metadata = Some(metadata);
+                    return Ok(());
                 }
-                Err(_) => {
-                    // Create new table metadata
-                    info!("Creating new table metadata");
-                    self.create_new_table_metadata().await?;
+                Err(err) if err.is::<CatalogConflict>() && attempt < MAX_ATTEMPTS => {
+                    warn!(
+                        "table {} metadata was concurrently modified while evolving schema, retrying (attempt {attempt}/{MAX_ATTEMPTS})",
+                        self.table_name
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
                 }
+                Err(err) => return Err(err),
             }
         }
-        Ok(())
+
+        anyhow::bail!(
+            "giving up evolving schema for table {} after {MAX_ATTEMPTS} concurrent-write conflicts",
+            self.table_name
+        )
     }
 
     async fn create_new_table_metadata(&mut self) -> Result<()> {
         let table_uuid = Uuid::new_v4().to_string();
         let location = format!("{}/{}", self.bucket, self.table_name);
         let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as i64;
-        
-        let schema = self.convert_arrow_schema_to_iceberg();
-        let mut schemas = HashMap::new();
-        schemas.insert(0, schema.clone());
-        
+
         let partition_spec = IcebergPartitionSpec {
             spec_id: 0,
-            fields: Vec::new(), // No partitioning for now
+            fields: self.partition_spec_fields(),
         };
         let mut specs = HashMap::new();
         specs.insert(0, partition_spec);
-        
+
         let mut properties = HashMap::new();
         properties.insert("write.format.default".to_string(), "parquet".to_string());
         properties.insert("write.metadata.delete-after-commit.enabled".to_string(), "true".to_string());
         properties.insert("write.metadata.previous-versions-max".to_string(), "1".to_string());
-        
-        self.table_metadata = Some(IcebergTableMetadata {
+
+        let mut metadata = IcebergTableMetadata {
             format_version: 2,
             table_uuid,
             location,
             last_updated_ms: now,
-            last_column_id: self.schema.fields().len() as i32,
-            schema: schema.clone(),
+            last_column_id: 0,
+            schema: IcebergSchema { schema_id: 0, fields: Vec::new() },
             partition_specs: specs.clone(),
             properties,
             current_schema_id: 0,
-            schemas,
+            schemas: HashMap::new(),
             current_spec_id: 0,
             specs,
             last_partition_id: 0,
@@ -398,99 +1344,366 @@ impl IcebergSink {
             metadata_log: Vec::new(),
             current_snapshot_id: None,
             refs: HashMap::new(),
-        });
+            column_ids: HashMap::new(),
+        };
+
+        let fields = Self::assign_field_ids(&mut metadata, &self.schema);
+        let schema = IcebergSchema { schema_id: 0, fields };
+        metadata.schema = schema.clone();
+        metadata.schemas.insert(0, schema);
 
-        // Write initial metadata
-        self.write_table_metadata().await?;
+        match self.catalog()?.create_table(&self.table_name, &metadata).await {
+            Ok(()) => {
+                self.table_metadata = Some(metadata);
+                Ok(())
+            }
+            Err(err) if err.is::<CatalogConflict>() => {
+                info!("table {} was concurrently created by another writer, loading it instead", self.table_name);
+                let loaded = self
+                    .catalog()?
+                    .load_table(&self.table_name)
+                    .await?
+                    .with_context(|| format!("table {} disappeared after a concurrent-create conflict", self.table_name))?;
+                self.current_snapshot_id = loaded.metadata.snapshots.keys().copied().max().unwrap_or(0) + 1;
+                self.table_metadata = Some(loaded.metadata);
+                Ok(())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes `sub_batch` as a Parquet data file under `partition_path`
+    /// (the empty string for an unpartitioned table) and records it in
+    /// `self.data_files` for the next commit
+    async fn write_data_file(&mut self, partition_path: &str, sub_batch: &RecordBatch) -> Result<()> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+        let file_name = format!("data-{}-{}.parquet", timestamp, Uuid::new_v4());
+        let file_path = if partition_path.is_empty() {
+            format!("{}/data/{}", self.table_name, file_name)
+        } else {
+            format!("{}/data/{}/{}", self.table_name, partition_path, file_name)
+        };
+
+        info!("Iceberg sink: Writing parquet file: {}", file_path);
+        let (file_size, stats) = self.write_parquet_file(sub_batch, &file_path).await?;
+
+        let data_file = IcebergDataFile {
+            content: 0, // Data content
+            file_path: file_path.clone(),
+            file_format: "PARQUET".to_string(),
+            partition: partition_map_from_path(partition_path),
+            record_count: sub_batch.num_rows() as i64,
+            file_size_in_bytes: file_size as i64,
+            column_sizes: stats.column_sizes,
+            value_counts: stats.value_counts,
+            null_value_counts: stats.null_value_counts,
+            nan_value_counts: HashMap::new(),
+            // Not derivable from the Arrow batch/Parquet footer alone
+            distinct_counts: HashMap::new(),
+            lower_bounds: stats.lower_bounds,
+            upper_bounds: stats.upper_bounds,
+            key_metadata: None,
+            split_offsets: Vec::new(),
+            equality_ids: Vec::new(),
+            sort_order_id: None,
+        };
+        self.data_files.push(data_file);
+
+        info!("Iceberg sink: Successfully written parquet file: {} ({} bytes)", file_path, file_size);
         Ok(())
     }
 
-    async fn write_table_metadata(&self) -> Result<()> {
-        if let (Some(metadata), Some(object_store)) = (&self.table_metadata, &self.object_store) {
-            let metadata_json = serde_json::to_string_pretty(metadata)?;
-            let metadata_path = format!("{}/metadata/metadata.json", self.table_name);
-            let path = ObjectPath::from(metadata_path.as_str());
-            let put_options = PutOptions::default();
-            
-            object_store.put_opts(&path, metadata_json.into(), put_options).await?;
-            info!("Written table metadata to: {}", metadata_path);
+    /// Splits a CDC `batch` by its [`CDC_OP_COLUMN`]: `"I"`/`"U"` rows
+    /// (minus that column) become a data file via [`Self::write_data_file`];
+    /// `"U"`/`"D"` rows, projected down to `self.upsert_keys`, become an
+    /// equality-delete file. An update's old key is assumed unchanged from
+    /// its new key, since this sink only sees one row per update event.
+    async fn write_cdc_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        let op_index = batch
+            .schema()
+            .index_of(CDC_OP_COLUMN)
+            .with_context(|| format!("upsert mode requires a '{CDC_OP_COLUMN}' column on every batch"))?;
+        let op_array = batch
+            .column(op_index)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .with_context(|| format!("'{CDC_OP_COLUMN}' column must be a string"))?;
+
+        let is_upsert =
+            BooleanArray::from((0..batch.num_rows()).map(|i| op_array.value(i) != "D").collect::<Vec<bool>>());
+        let is_keyed_change =
+            BooleanArray::from((0..batch.num_rows()).map(|i| op_array.value(i) != "I").collect::<Vec<bool>>());
+
+        let data_columns: Vec<usize> = (0..batch.num_columns()).filter(|&index| index != op_index).collect();
+        let key_columns: Vec<usize> =
+            self.upsert_keys.iter().filter_map(|key| batch.schema().index_of(key).ok()).collect();
+
+        let upsert_rows = filter_record_batch(batch, &is_upsert)?;
+        if upsert_rows.num_rows() > 0 {
+            let data_batch = upsert_rows.project(&data_columns)?;
+            self.write_data_file("", &data_batch).await?;
+        }
+
+        let changed_keys = filter_record_batch(batch, &is_keyed_change)?;
+        if changed_keys.num_rows() > 0 {
+            let key_batch = changed_keys.project(&key_columns)?;
+            self.write_equality_delete_file(&key_batch).await?;
         }
+
+        Ok(())
+    }
+
+    /// Writes `key_batch` (already projected down to `self.upsert_keys`) as
+    /// an equality-delete Parquet file and records it in `self.delete_files`
+    async fn write_equality_delete_file(&mut self, key_batch: &RecordBatch) -> Result<()> {
+        let object_store = self.object_store.as_ref().ok_or_else(|| anyhow::anyhow!("Object store not initialized"))?;
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+        let file_path = format!("{}/data/delete-{}-{}.parquet", self.table_name, timestamp, Uuid::new_v4());
+
+        let mut buffer = Vec::new();
+        let props = WriterProperties::builder().build();
+        let mut writer = ArrowWriter::try_new(&mut buffer, key_batch.schema(), Some(props))?;
+        writer.write(key_batch)?;
+        writer.close()?;
+
+        let path = ObjectPath::from(file_path.as_str());
+        object_store.put_opts(&path, buffer.clone().into(), PutOptions::default()).await?;
+        info!("Iceberg sink: Writing equality-delete file: {}", file_path);
+
+        let delete_file = IcebergDataFile {
+            content: 1, // Equality deletes
+            file_path: file_path.clone(),
+            file_format: "PARQUET".to_string(),
+            partition: HashMap::new(),
+            record_count: key_batch.num_rows() as i64,
+            file_size_in_bytes: buffer.len() as i64,
+            column_sizes: HashMap::new(),
+            value_counts: HashMap::new(),
+            null_value_counts: HashMap::new(),
+            nan_value_counts: HashMap::new(),
+            distinct_counts: HashMap::new(),
+            lower_bounds: HashMap::new(),
+            upper_bounds: HashMap::new(),
+            key_metadata: None,
+            split_offsets: Vec::new(),
+            equality_ids: self.key_field_ids(),
+            sort_order_id: None,
+        };
+        self.delete_files.push(delete_file);
+
         Ok(())
     }
 
-    async fn create_manifest_file(&self, data_files: &[IcebergDataFile]) -> Result<String> {
+    /// Commits whatever's accumulated in `self.data_files`/`self.delete_files`
+    /// as a new snapshot referencing a data manifest and, in upsert mode, a
+    /// delete manifest too. Returns `false` with no commit if both are empty.
+    async fn commit_pending_files(&mut self) -> Result<bool> {
+        if self.data_files.is_empty() && self.delete_files.is_empty() {
+            return Ok(false);
+        }
+
+        let mut manifests = Vec::new();
+        if !self.data_files.is_empty() {
+            let (path, length) = self.create_manifest_file(&self.data_files, 0).await?;
+            manifests.push(ManifestRef {
+                path,
+                length,
+                content: 0,
+                file_count: self.data_files.len() as i32,
+                row_count: self.data_files.iter().map(|df| df.record_count).sum(),
+                partitions: self.partition_summaries(&self.data_files),
+            });
+        }
+        if !self.delete_files.is_empty() {
+            let (path, length) = self.create_manifest_file(&self.delete_files, 1).await?;
+            manifests.push(ManifestRef {
+                path,
+                length,
+                content: 1,
+                file_count: self.delete_files.len() as i32,
+                row_count: self.delete_files.iter().map(|df| df.record_count).sum(),
+                partitions: self.partition_summaries(&self.delete_files),
+            });
+        }
+
+        let manifest_list_path = self.create_manifest_list(&manifests).await?;
+        self.create_snapshot(manifest_list_path).await?;
+        self.data_files.clear();
+        self.delete_files.clear();
+        Ok(true)
+    }
+
+    /// Writes `data_files` as a spec-compliant Avro manifest file and
+    /// returns `(path, length_in_bytes)`, both needed by the manifest list
+    /// entry that will point at it. `content` is `0` for a data manifest
+    /// (appended rows) or `1` for a delete manifest (equality-delete files
+    /// from [`IcebergSink::with_upsert_keys`]); every file in `data_files`
+    /// must carry the matching [`IcebergDataFile::content`].
+    async fn create_manifest_file(&self, data_files: &[IcebergDataFile], content: i32) -> Result<(String, i64)> {
         if let Some(object_store) = &self.object_store {
             let manifest_id = Uuid::new_v4();
             let manifest_path = format!("{}/metadata/{}.avro", self.table_name, manifest_id);
-            
-            let manifest = IcebergManifest {
-                schema_id: 0,
-                schema: self.convert_arrow_schema_to_iceberg(),
-                partition_spec_id: 0,
-                content: 0, // Data content
-                sequence_number: self.current_snapshot_id,
-                min_sequence_number: self.current_snapshot_id,
-                entries: data_files.iter().map(|df| IcebergManifestEntry {
-                    status: 2, // Added
-                    snapshot_id: self.current_snapshot_id,
-                    data_file: df.clone(),
-                }).collect(),
-            };
 
-            // For now, write as JSON (in production, this should be Avro)
-            let manifest_json = serde_json::to_string_pretty(&manifest)?;
+            let schema = self.convert_arrow_schema_to_iceberg();
+            let entries: Vec<AvroManifestEntry> = data_files
+                .iter()
+                .map(|df| {
+                    self.to_avro_manifest_entry(&IcebergManifestEntry {
+                        status: 2, // Added
+                        snapshot_id: self.current_snapshot_id,
+                        sequence_number: Some(self.current_snapshot_id),
+                        data_file: df.clone(),
+                    })
+                })
+                .collect();
+
+            let metadata = [
+                ("schema", serde_json::to_string(&schema)?),
+                ("partition-spec", serde_json::to_string(&self.partition_spec_fields())?),
+                ("partition-spec-id", "0".to_string()),
+                ("format-version", "2".to_string()),
+                ("content", if content == 1 { "deletes".to_string() } else { "data".to_string() }),
+            ];
+            let metadata: Vec<(&str, &str)> = metadata.iter().map(|(k, v)| (*k, v.as_str())).collect();
+            let schema_json = self.manifest_entry_schema();
+            let bytes = iceberg_avro::write_object_container(&schema_json, &metadata, &entries)?;
+            let length = bytes.len() as i64;
+
             let path = ObjectPath::from(manifest_path.as_str());
             let put_options = PutOptions::default();
-            
-            object_store.put_opts(&path, manifest_json.into(), put_options).await?;
+            object_store.put_opts(&path, bytes.into(), put_options).await?;
             info!("Created manifest file: {}", manifest_path);
-            
-            Ok(manifest_path)
+
+            Ok((manifest_path, length))
+        } else {
+            Err(anyhow::anyhow!("Object store not initialized"))
+        }
+    }
+
+    /// Writes a manifest list referencing every manifest in `manifests`
+    /// (one data manifest and, in upsert mode, one delete manifest) and
+    /// returns its path; this is the file a snapshot's `manifest_list` must
+    /// reference, not the manifests themselves.
+    async fn create_manifest_list(&self, manifests: &[ManifestRef]) -> Result<String> {
+        if let Some(object_store) = &self.object_store {
+            let manifest_list_path =
+                format!("{}/metadata/snap-{}-{}.avro", self.table_name, self.current_snapshot_id, Uuid::new_v4());
+
+            let entries: Vec<AvroManifestFileEntry> = manifests
+                .iter()
+                .map(|manifest| AvroManifestFileEntry {
+                    manifest_path: manifest.path.clone(),
+                    manifest_length: manifest.length,
+                    partition_spec_id: 0,
+                    content: manifest.content,
+                    sequence_number: self.current_snapshot_id,
+                    min_sequence_number: self.current_snapshot_id,
+                    added_snapshot_id: self.current_snapshot_id,
+                    added_data_files_count: manifest.file_count,
+                    existing_data_files_count: 0,
+                    deleted_data_files_count: 0,
+                    added_rows_count: manifest.row_count,
+                    existing_rows_count: 0,
+                    deleted_rows_count: 0,
+                    partitions: manifest.partitions.iter().map(AvroPartitionSummary::from).collect(),
+                })
+                .collect();
+
+            let bytes = iceberg_avro::write_object_container(MANIFEST_FILE_SCHEMA, &[], &entries)?;
+            let path = ObjectPath::from(manifest_list_path.as_str());
+            let put_options = PutOptions::default();
+            object_store.put_opts(&path, bytes.into(), put_options).await?;
+            info!("Created manifest list: {}", manifest_list_path);
+
+            Ok(manifest_list_path)
         } else {
             Err(anyhow::anyhow!("Object store not initialized"))
         }
     }
 
-    async fn create_snapshot(&mut self, manifest_path: String) -> Result<()> {
-        if let Some(metadata) = &mut self.table_metadata {
+    /// Commits `manifest_list_path` as a new child snapshot. The manifest
+    /// list itself is written once by the caller and reused across
+    /// attempts; on a [`CatalogConflict`] this reloads the latest
+    /// metadata, re-derives the next `snapshot_id`/`sequence_number` from
+    /// whatever is now current, and retries re-attaching the same manifest
+    /// list, with bounded exponential backoff — so two sinks appending to
+    /// the same table concurrently can't silently clobber each other.
+    async fn create_snapshot(&mut self, manifest_list_path: String) -> Result<()> {
+        const MAX_ATTEMPTS: u32 = 5;
+        let mut backoff = std::time::Duration::from_millis(50);
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            let base = self
+                .catalog()?
+                .load_table(&self.table_name)
+                .await?
+                .with_context(|| format!("cannot commit a snapshot to table {}: no metadata exists yet", self.table_name))?;
+            let mut metadata = base.metadata.clone();
+            record_metadata_log_entry(&mut metadata, &base);
+
+            let snapshot_id = metadata.snapshots.keys().copied().max().unwrap_or(0) + 1;
             let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as i64;
-            
+
             let snapshot = IcebergSnapshot {
-                snapshot_id: self.current_snapshot_id,
+                snapshot_id,
                 parent_snapshot_id: metadata.current_snapshot_id,
-                sequence_number: self.current_snapshot_id,
+                sequence_number: snapshot_id,
                 timestamp_ms: now,
-                manifest_list: manifest_path,
+                manifest_list: manifest_list_path.clone(),
                 summary: IcebergSnapshotSummary {
-                    operation: "append".to_string(),
+                    operation: if self.delete_files.is_empty() { "append".to_string() } else { "overwrite".to_string() },
                     added_data_files: self.data_files.len() as i32,
-                    deleted_data_files: 0,
+                    // Equality-delete files, not removed data files, but this is the closest
+                    // summary field the v2 spec offers for "rows logically deleted this commit"
+                    deleted_data_files: self.delete_files.len() as i32,
                     total_records: self.data_files.iter().map(|df| df.record_count).sum(),
                     added_records: self.data_files.iter().map(|df| df.record_count).sum(),
-                    deleted_records: 0,
+                    deleted_records: self.delete_files.iter().map(|df| df.record_count).sum(),
                     added_files_size: self.data_files.iter().map(|df| df.file_size_in_bytes).sum(),
-                    deleted_files_size: 0,
+                    deleted_files_size: self.delete_files.iter().map(|df| df.file_size_in_bytes).sum(),
                 },
-                schema_id: 0,
+                schema_id: metadata.current_schema_id,
             };
 
-            metadata.snapshots.insert(self.current_snapshot_id, snapshot);
-            metadata.current_snapshot_id = Some(self.current_snapshot_id);
+            metadata.snapshots.insert(snapshot_id, snapshot);
+            metadata.current_snapshot_id = Some(snapshot_id);
             metadata.last_updated_ms = now;
+            metadata.snapshot_log.push(IcebergSnapshotLogEntry { timestamp_ms: now, snapshot_id });
+            metadata.refs.insert(
+                "main".to_string(),
+                IcebergSnapshotRef {
+                    snapshot_id,
+                    ref_type: "branch".to_string(),
+                    min_snapshots_to_keep: None,
+                    max_snapshot_age_ms: None,
+                    max_ref_age_ms: None,
+                },
+            );
 
-            // Add to snapshot log
-            metadata.snapshot_log.push(IcebergSnapshotLogEntry {
-                timestamp_ms: now,
-                snapshot_id: self.current_snapshot_id,
-            });
-
-            // Write updated metadata
-            self.write_table_metadata().await?;
-            
-            info!("Created snapshot {} with {} data files", self.current_snapshot_id, self.data_files.len());
-            self.current_snapshot_id += 1;
+            match self.catalog()?.commit_table(&self.table_name, &base, &metadata).await {
+                Ok(()) => {
+                    info!("Created snapshot {} with {} data files", snapshot_id, self.data_files.len());
+                    self.table_metadata = Some(metadata);
+                    self.current_snapshot_id = snapshot_id + 1;
+                    return Ok(());
+                }
+                Err(err) if err.is::<CatalogConflict>() && attempt < MAX_ATTEMPTS => {
+                    warn!(
+                        "table {} metadata was concurrently modified, retrying snapshot commit (attempt {attempt}/{MAX_ATTEMPTS})",
+                        self.table_name
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(err) => return Err(err),
+            }
         }
-        Ok(())
+
+        anyhow::bail!(
+            "giving up committing snapshot to table {} after {MAX_ATTEMPTS} concurrent-write conflicts",
+            self.table_name
+        )
     }
 }
 
@@ -505,6 +1718,113 @@ impl Operator for IcebergSink {
     }
 }
 
+/// Encodes `array`'s min/max as Iceberg's single-value binary representation
+/// (little-endian two's-complement for int/long, little-endian IEEE-754 for
+/// float/double, UTF-8 bytes truncated to 16 for strings, days-since-epoch
+/// for dates, microseconds since epoch for timestamps), or `None` for types
+/// this sink doesn't track bounds for, or an all-null column
+fn column_bounds(array: &ArrayRef, data_type: &DataType) -> Option<(Vec<u8>, Vec<u8>)> {
+    match data_type {
+        DataType::Int32 => {
+            let array = array.as_any().downcast_ref::<Int32Array>()?;
+            Some((min(array)?.to_le_bytes().to_vec(), max(array)?.to_le_bytes().to_vec()))
+        }
+        DataType::Int64 => {
+            let array = array.as_any().downcast_ref::<Int64Array>()?;
+            Some((min(array)?.to_le_bytes().to_vec(), max(array)?.to_le_bytes().to_vec()))
+        }
+        DataType::Float32 => {
+            let array = array.as_any().downcast_ref::<Float32Array>()?;
+            Some((min(array)?.to_le_bytes().to_vec(), max(array)?.to_le_bytes().to_vec()))
+        }
+        DataType::Float64 => {
+            let array = array.as_any().downcast_ref::<Float64Array>()?;
+            Some((min(array)?.to_le_bytes().to_vec(), max(array)?.to_le_bytes().to_vec()))
+        }
+        DataType::Date32 => {
+            let array = array.as_any().downcast_ref::<Date32Array>()?;
+            Some((min(array)?.to_le_bytes().to_vec(), max(array)?.to_le_bytes().to_vec()))
+        }
+        DataType::Utf8 => {
+            let array = array.as_any().downcast_ref::<StringArray>()?;
+            Some((truncate_utf8(min_string(array)?), truncate_utf8_upper_bound(max_string(array)?)))
+        }
+        DataType::Timestamp(unit, _) => {
+            let to_micros = |value: i64| match unit {
+                TimeUnit::Second => value * 1_000_000,
+                TimeUnit::Millisecond => value * 1_000,
+                TimeUnit::Microsecond => value,
+                TimeUnit::Nanosecond => value / 1_000,
+            };
+            let (lower, upper) = match unit {
+                TimeUnit::Second => {
+                    let array = array.as_any().downcast_ref::<TimestampSecondArray>()?;
+                    (min(array)?, max(array)?)
+                }
+                TimeUnit::Millisecond => {
+                    let array = array.as_any().downcast_ref::<TimestampMillisecondArray>()?;
+                    (min(array)?, max(array)?)
+                }
+                TimeUnit::Microsecond => {
+                    let array = array.as_any().downcast_ref::<TimestampMicrosecondArray>()?;
+                    (min(array)?, max(array)?)
+                }
+                TimeUnit::Nanosecond => {
+                    let array = array.as_any().downcast_ref::<TimestampNanosecondArray>()?;
+                    (min(array)?, max(array)?)
+                }
+            };
+            Some((to_micros(lower).to_le_bytes().to_vec(), to_micros(upper).to_le_bytes().to_vec()))
+        }
+        _ => None,
+    }
+}
+
+/// Truncates to at most 16 UTF-8 bytes, rounded down to a char boundary
+fn truncate_utf8(value: &str) -> Vec<u8> {
+    let mut end = value.len().min(16);
+    while end > 0 && !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    value.as_bytes()[..end].to_vec()
+}
+
+/// Truncates `value` the same way [`truncate_utf8`] does, but for use as an
+/// upper bound: plain truncation only ever makes a string lexicographically
+/// *smaller*, which would turn a truncated max value into a false upper
+/// bound and let readers wrongly skip data files during min/max pruning. If
+/// truncation happened, the last byte is incremented (carrying into
+/// preceding bytes, dropping them on `0xFF` overflow) to produce a true
+/// upper bound, the same way Iceberg's Java/Python implementations do it.
+fn truncate_utf8_upper_bound(value: &str) -> Vec<u8> {
+    let truncated = truncate_utf8(value);
+    if truncated.len() == value.len() {
+        return truncated;
+    }
+    let mut bytes = truncated;
+    while let Some(last) = bytes.last_mut() {
+        if *last < 0xFF {
+            *last += 1;
+            return bytes;
+        }
+        bytes.pop();
+    }
+    bytes
+}
+
+/// Parses a Hive-style partition path (e.g. `"region=us/event_time_day=19723"`,
+/// as produced by [`iceberg_partition::partition_batch`]) back into a
+/// field-name-to-value map for [`IcebergDataFile::partition`]
+fn partition_map_from_path(partition_path: &str) -> HashMap<String, String> {
+    if partition_path.is_empty() {
+        return HashMap::new();
+    }
+    partition_path
+        .split('/')
+        .filter_map(|segment| segment.split_once('=').map(|(key, value)| (key.to_string(), value.to_string())))
+        .collect()
+}
+
 #[async_trait]
 impl Sink for IcebergSink {
     async fn run(&mut self, mut rx: BatchRx, _cancel: CancellationToken) -> Result<()> {
@@ -526,61 +1846,27 @@ impl Sink for IcebergSink {
                     batch_count += 1;
                     info!("Iceberg sink: Processing batch {} with {} rows", batch_count, batch.num_rows());
 
-                    // Generate unique file path
-                    let timestamp = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_millis();
-                    let file_name = format!("data-{}-{}.parquet", timestamp, Uuid::new_v4());
-                    let file_path = format!("{}/data/{}", self.table_name, file_name);
-
-                    info!("Iceberg sink: Writing parquet file: {}", file_name);
-                    
-                    // Write parquet file to MinIO
-                    let file_size = self.write_parquet_file(&batch, &file_path).await?;
-                    
-                    // Create Iceberg data file entry
-                    let data_file = IcebergDataFile {
-                        content: 0, // Data content
-                        file_path: file_path.clone(),
-                        file_format: "PARQUET".to_string(),
-                        partition: HashMap::new(), // No partitioning for now
-                        record_count: batch.num_rows() as i64,
-                        file_size_in_bytes: file_size as i64,
-                        column_sizes: HashMap::new(),
-                        value_counts: HashMap::new(),
-                        null_value_counts: HashMap::new(),
-                        nan_value_counts: HashMap::new(),
-                        distinct_counts: HashMap::new(),
-                        lower_bounds: HashMap::new(),
-                        upper_bounds: HashMap::new(),
-                        key_metadata: None,
-                        split_offsets: Vec::new(),
-                        equality_ids: Vec::new(),
-                        sort_order_id: None,
-                    };
-                    
-                    self.data_files.push(data_file);
-                    
-                    info!("Iceberg sink: Successfully written parquet file: {} ({} bytes)", file_name, file_size);
+                    if self.upsert_keys.is_empty() {
+                        // Split into one sub-batch per distinct partition value
+                        // (a single unpartitioned sub-batch when partition_by is empty)
+                        let partitions = iceberg_partition::partition_batch(&self.partition_by, &batch)?;
+                        for (partition_path, sub_batch) in partitions {
+                            self.write_data_file(&partition_path, &sub_batch).await?;
+                        }
+                    } else {
+                        self.write_cdc_batch(&batch).await?;
+                    }
                 }
                 Message::Watermark(_) => {
                     info!("Iceberg sink: Received watermark");
-                    // Commit current batch of files
-                    if !self.data_files.is_empty() {
-                        let manifest_path = self.create_manifest_file(&self.data_files).await?;
-                        self.create_snapshot(manifest_path).await?;
-                        self.data_files.clear();
+                    if self.commit_pending_files().await? {
                         info!("Iceberg sink: Committed batch of files");
                     }
                 }
                 Message::Eos => {
                     info!("Iceberg sink: End of stream, processing final batch");
-                    // Commit any remaining files
-                    if !self.data_files.is_empty() {
-                        let manifest_path = self.create_manifest_file(&self.data_files).await?;
-                        self.create_snapshot(manifest_path).await?;
-                        info!("Iceberg sink: Committed final batch of {} files", self.data_files.len());
+                    if self.commit_pending_files().await? {
+                        info!("Iceberg sink: Committed final batch of files");
                     }
                     break;
                 }
@@ -591,3 +1877,203 @@ impl Sink for IcebergSink {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod schema_sync_tests {
+    use super::{IcebergSchema, IcebergSink, IcebergTableMetadata};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn empty_metadata() -> IcebergTableMetadata {
+        IcebergTableMetadata {
+            format_version: 2,
+            table_uuid: "table-uuid".to_string(),
+            location: "s3://bucket/table".to_string(),
+            last_updated_ms: 0,
+            last_column_id: 0,
+            schema: IcebergSchema { schema_id: 0, fields: vec![] },
+            partition_specs: HashMap::new(),
+            properties: HashMap::new(),
+            current_schema_id: 0,
+            schemas: HashMap::from([(0, IcebergSchema { schema_id: 0, fields: vec![] })]),
+            current_spec_id: 0,
+            specs: HashMap::new(),
+            last_partition_id: 999,
+            default_spec_id: 0,
+            default_sort_order_id: 0,
+            sort_orders: HashMap::new(),
+            snapshots: HashMap::new(),
+            snapshot_log: vec![],
+            metadata_log: vec![],
+            current_snapshot_id: None,
+            refs: HashMap::new(),
+            column_ids: HashMap::new(),
+        }
+    }
+
+    fn arrow_schema(fields: &[(&str, DataType, bool)]) -> Arc<Schema> {
+        Arc::new(Schema::new(
+            fields.iter().map(|(name, ty, nullable)| Field::new(*name, ty.clone(), *nullable)).collect::<Vec<_>>(),
+        ))
+    }
+
+    #[test]
+    fn assign_field_ids_hands_out_new_ids_and_remembers_them() {
+        let mut metadata = empty_metadata();
+        let schema = arrow_schema(&[("id", DataType::Int64, false), ("name", DataType::Utf8, true)]);
+
+        let fields = IcebergSink::assign_field_ids(&mut metadata, &schema);
+        assert_eq!(fields.iter().map(|f| f.id).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(metadata.last_column_id, 2);
+        assert_eq!(metadata.column_ids.get("id"), Some(&1));
+        assert_eq!(metadata.column_ids.get("name"), Some(&2));
+    }
+
+    #[test]
+    fn assign_field_ids_keeps_existing_columns_stable_when_adding_a_new_one() {
+        let mut metadata = empty_metadata();
+        metadata.last_column_id = 5;
+        metadata.column_ids.insert("id".to_string(), 1);
+        let schema = arrow_schema(&[("id", DataType::Int64, false), ("amount", DataType::Float64, true)]);
+
+        let fields = IcebergSink::assign_field_ids(&mut metadata, &schema);
+        assert_eq!(fields[0].id, 1, "existing column keeps its original id");
+        assert_eq!(fields[1].id, 6, "new column gets the next id after last_column_id");
+        assert_eq!(metadata.last_column_id, 6);
+    }
+
+    #[test]
+    fn schema_matches_is_true_only_when_fields_and_types_line_up() {
+        let mut metadata = empty_metadata();
+        let schema = arrow_schema(&[("id", DataType::Int64, false)]);
+        let fields = IcebergSink::assign_field_ids(&mut metadata, &schema);
+        metadata.schemas.insert(1, IcebergSchema { schema_id: 1, fields });
+        metadata.current_schema_id = 1;
+
+        assert!(IcebergSink::schema_matches(&metadata, &schema));
+
+        let evolved_schema = arrow_schema(&[("id", DataType::Int64, false), ("amount", DataType::Float64, true)]);
+        assert!(!IcebergSink::schema_matches(&metadata, &evolved_schema));
+    }
+}
+
+#[cfg(test)]
+mod catalog_tests {
+    use super::RestCatalog;
+
+    #[test]
+    fn namespace_and_name_splits_on_the_last_dot() {
+        assert_eq!(
+            RestCatalog::namespace_and_name("analytics.events"),
+            ("analytics".to_string(), "events".to_string())
+        );
+    }
+
+    #[test]
+    fn namespace_and_name_defaults_to_default_namespace() {
+        assert_eq!(RestCatalog::namespace_and_name("events"), ("default".to_string(), "events".to_string()));
+    }
+
+    #[test]
+    fn namespace_and_name_uses_only_the_final_segment_as_the_name() {
+        assert_eq!(
+            RestCatalog::namespace_and_name("warehouse.analytics.events"),
+            ("warehouse.analytics".to_string(), "events".to_string())
+        );
+    }
+}
+
+#[cfg(test)]
+mod upsert_key_tests {
+    use super::IcebergSink;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    fn sink(upsert_keys: Vec<&str>) -> IcebergSink {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("amount", DataType::Float64, true),
+        ]));
+        IcebergSink::new(
+            "sink".to_string(),
+            schema,
+            "events".to_string(),
+            "bucket".to_string(),
+            "http://localhost".to_string(),
+            "key".to_string(),
+            "secret".to_string(),
+            "us-east-1".to_string(),
+        )
+        .with_upsert_keys(upsert_keys.into_iter().map(String::from).collect())
+    }
+
+    #[test]
+    fn key_field_ids_falls_back_to_one_based_schema_position_with_no_loaded_metadata() {
+        assert_eq!(sink(vec!["id", "amount"]).key_field_ids(), vec![1, 3]);
+    }
+
+    #[test]
+    fn key_field_ids_ignores_keys_that_are_not_in_the_schema() {
+        assert_eq!(sink(vec!["id", "not_a_column"]).key_field_ids(), vec![1]);
+    }
+}
+
+#[cfg(test)]
+mod column_stats_tests {
+    use super::{column_bounds, truncate_utf8};
+    use datafusion::arrow::array::{ArrayRef, Int32Array, Int64Array, StringArray};
+    use datafusion::arrow::datatypes::DataType;
+    use std::sync::Arc;
+
+    #[test]
+    fn column_bounds_int32_uses_little_endian_two_s_complement() {
+        let array: ArrayRef = Arc::new(Int32Array::from(vec![Some(5), None, Some(-3)]));
+        let (lower, upper) = column_bounds(&array, &DataType::Int32).unwrap();
+        assert_eq!(lower, (-3i32).to_le_bytes().to_vec());
+        assert_eq!(upper, 5i32.to_le_bytes().to_vec());
+    }
+
+    #[test]
+    fn column_bounds_int64_ignores_nulls() {
+        let array: ArrayRef = Arc::new(Int64Array::from(vec![None, Some(10), Some(2)]));
+        let (lower, upper) = column_bounds(&array, &DataType::Int64).unwrap();
+        assert_eq!(lower, 2i64.to_le_bytes().to_vec());
+        assert_eq!(upper, 10i64.to_le_bytes().to_vec());
+    }
+
+    #[test]
+    fn column_bounds_is_none_for_an_all_null_array() {
+        let array: ArrayRef = Arc::new(Int32Array::from(vec![None, None]));
+        assert!(column_bounds(&array, &DataType::Int32).is_none());
+    }
+
+    #[test]
+    fn column_bounds_utf8_truncates_to_sixteen_bytes() {
+        let array: ArrayRef = Arc::new(StringArray::from(vec!["banana", "a string longer than sixteen bytes"]));
+        let (lower, upper) = column_bounds(&array, &DataType::Utf8).unwrap();
+        assert_eq!(lower, b"a string longer ".to_vec());
+        assert_eq!(upper, b"banana".to_vec());
+    }
+
+    #[test]
+    fn truncate_utf8_rounds_down_to_a_char_boundary() {
+        let value = "a".repeat(15) + "é";
+        let truncated = truncate_utf8(&value);
+        assert_eq!(truncated, "a".repeat(15).into_bytes());
+    }
+
+    #[test]
+    fn column_bounds_utf8_upper_bound_stays_above_the_true_max_when_truncated() {
+        let min_value = "a".repeat(16);
+        let max_value = "a".repeat(16) + "b";
+        let array: ArrayRef = Arc::new(StringArray::from(vec![min_value.clone(), max_value.clone()]));
+        let (lower, upper) = column_bounds(&array, &DataType::Utf8).unwrap();
+        assert_eq!(lower, min_value.into_bytes());
+        assert!(
+            upper >= max_value.into_bytes(),
+            "truncated upper bound must remain a true upper bound, got {upper:?}"
+        );
+    }
+}
@@ -0,0 +1,387 @@
+#![allow(unused)]
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use datafusion::arrow::array::RecordBatch;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::json::writer::record_batches_to_json_rows;
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::producer::{BaseRecord, Producer, ThreadedProducer};
+use rdkafka::util::Timeout;
+use rdkafka::Message as KafkaMessage;
+use rde_core::{BatchRx, KafkaSinkSpec, MessageEncoding, Message, Operator, Sink};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+type TxnProducer = ThreadedProducer<rdkafka::producer::DefaultProducerContext>;
+
+/// Upper bound on how long a transactional call (`init_transactions`,
+/// `commit_transaction`) may block the blocking thread
+/// it runs on before giving up, so a broker outage or slow transaction
+/// coordinator can't wedge this sink forever.
+const TXN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Exactly-once transactional Kafka sink
+///
+/// Rows are produced to `spec.topic` inside a Kafka transaction opened with
+/// rdkafka's transactional producer API. A transaction boundary is
+/// committed whenever a `Message::Watermark` or `Message::Eos` arrives; the
+/// watermark being committed is written to `spec.progress_topic` as part of
+/// that same transaction, so a restart can resume from the last committed
+/// watermark instead of reprocessing (or losing) rows.
+pub struct KafkaSink {
+    id: String,
+    schema: SchemaRef,
+    spec: KafkaSinkSpec,
+}
+
+impl KafkaSink {
+    pub fn new(spec: KafkaSinkSpec, schema: SchemaRef) -> Self {
+        Self { id: spec.id.clone(), schema, spec }
+    }
+}
+
+#[async_trait]
+impl Operator for KafkaSink {
+    fn name(&self) -> &str {
+        &self.id
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[async_trait]
+impl Sink for KafkaSink {
+    async fn run(&mut self, mut rx: BatchRx, cancel: CancellationToken) -> Result<()> {
+        // Avro is a real `MessageEncoding` variant, decoded on the source
+        // side by `KafkaSource` against a schema fetched from the registry
+        // by id, but producing it needs a write-schema plus a
+        // register-schema-with-the-registry call, and no such registration
+        // path exists anywhere in this crate. Rejecting it here rather than
+        // silently producing something no registry-aware consumer could
+        // read; see `KafkaSinkSpec::encoding`.
+        anyhow::ensure!(
+            self.spec.encoding == MessageEncoding::Json,
+            "KafkaSink only supports json encoding so far, got {:?}",
+            self.spec.encoding
+        );
+
+        let producer: Arc<TxnProducer> = Arc::new(
+            ClientConfig::new()
+                .set("bootstrap.servers", &self.spec.brokers)
+                .set("transactional.id", &self.spec.transactional_id)
+                .set("enable.idempotence", "true")
+                .create()
+                .context("build transactional Kafka producer")?,
+        );
+
+        // `init_transactions` fences any producer from a prior run with the
+        // same `transactional.id` and aborts whatever transaction it left
+        // open, per the Kafka transactional-recovery contract — there is no
+        // separate, explicit `abort_transaction` call for us to make here.
+        //
+        // This is rdkafka's synchronous API, so it runs on a blocking thread
+        // with a bounded timeout instead of directly on this async fn's
+        // Tokio worker thread, and races against `cancel` so a shutdown
+        // during a stuck broker round trip doesn't hang the whole pipeline.
+        match run_cancellable(&cancel, init_transactions(producer.clone())).await {
+            Some(result) => result?,
+            None => {
+                info!("KafkaSink: cancelled during init_transactions");
+                return Ok(());
+            }
+        }
+
+        let mut resume_watermark = read_last_progress(&self.spec.brokers, &self.spec.progress_topic).await?;
+        if let Some(watermark) = resume_watermark {
+            if self.spec.timestamp_column.is_none() {
+                warn!(
+                    "KafkaSink: resuming after last committed watermark {watermark}, but no \
+                     timestamp_column is configured so already-committed rows cannot be \
+                     distinguished from new ones and will be reproduced"
+                );
+            } else {
+                info!("KafkaSink: resuming after last committed watermark {watermark}, dropping rows at or before it");
+            }
+        }
+
+        let mut txn_open = false;
+        let mut rows_in_txn: u64 = 0;
+        let mut last_watermark: Option<i64> = None;
+        // Max `timestamp_column` value seen among rows produced in the
+        // currently-open transaction, for EOS-only runs that never receive
+        // an intervening `Message::Watermark` (plausible for bounded/
+        // batch-style upstream sources) — without this, such a run would
+        // commit rows but never write progress, reproducing everything on
+        // restart despite having already committed it once.
+        let mut pending_max_ts: Option<i64> = None;
+
+        loop {
+            let msg = tokio::select! {
+                _ = cancel.cancelled() => {
+                    info!("KafkaSink: cancellation requested, stopping after the in-flight transaction");
+                    break;
+                }
+                msg = rx.recv() => msg,
+            };
+            let Some(msg) = msg else { break };
+
+            match msg {
+                Message::Batch(batch) => {
+                    if !txn_open {
+                        let Some(result) = run_cancellable(&cancel, begin_transaction(producer.clone())).await else { break };
+                        result?;
+                        txn_open = true;
+                    }
+                    let (produced, batch_max_ts) = self.produce_batch(&producer, &batch, resume_watermark)?;
+                    rows_in_txn += produced;
+                    if let Some(ts) = batch_max_ts {
+                        pending_max_ts = Some(pending_max_ts.map_or(ts, |cur| cur.max(ts)));
+                    }
+                }
+                Message::Watermark(ts) => {
+                    if !txn_open {
+                        let Some(result) = run_cancellable(&cancel, begin_transaction(producer.clone())).await else { break };
+                        result?;
+                        txn_open = true;
+                    }
+                    self.produce_progress(&producer, ts)?;
+                    let Some(result) = run_cancellable(&cancel, commit_transaction(producer.clone())).await else { break };
+                    result?;
+                    info!("KafkaSink: committed transaction with {rows_in_txn} rows up to watermark {ts}");
+                    txn_open = false;
+                    rows_in_txn = 0;
+                    pending_max_ts = None;
+                    last_watermark = Some(ts);
+                    if resume_watermark.is_some_and(|resume| ts > resume) {
+                        resume_watermark = None;
+                    }
+                }
+                Message::Eos => break,
+            }
+        }
+
+        if txn_open {
+            // Record progress for this final transaction the same way the
+            // watermark-driven path does, otherwise a restart right after
+            // EOS has no record of it and reproduces these rows. Fall back
+            // to the batches' own max timestamp_column value when no
+            // watermark ever arrived.
+            match last_watermark.or(pending_max_ts) {
+                Some(ts) => self.produce_progress(&producer, ts)?,
+                None => warn!(
+                    "KafkaSink: committing final transaction with no watermark and no timestamp_column \
+                     configured, so no progress record can be written; a restart will reproduce these rows"
+                ),
+            }
+            if let Some(result) = run_cancellable(&cancel, commit_transaction(producer.clone())).await {
+                result?;
+                info!("KafkaSink: committed final transaction with {rows_in_txn} rows on EOS");
+            } else {
+                warn!("KafkaSink: cancelled before the final transaction could be committed");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Races `fut` against `cancel`, returning `None` if `cancel` fires first.
+/// `fut` itself is left running to completion in that case (there's no way
+/// to abort a blocking thread), so callers should treat a `None` as "stop
+/// driving the sink", not "the operation didn't happen".
+async fn run_cancellable<T>(cancel: &CancellationToken, fut: impl std::future::Future<Output = T>) -> Option<T> {
+    tokio::select! {
+        _ = cancel.cancelled() => None,
+        result = fut => Some(result),
+    }
+}
+
+/// Runs `init_transactions` on a blocking thread with a bounded timeout
+/// instead of `Timeout::Never`, since this is rdkafka's synchronous API and
+/// would otherwise wedge the Tokio worker thread indefinitely on a broker
+/// outage or slow transaction coordinator.
+async fn init_transactions(producer: Arc<TxnProducer>) -> Result<()> {
+    tokio::task::spawn_blocking(move || producer.init_transactions(Timeout::After(TXN_TIMEOUT)))
+        .await
+        .context("init_transactions task panicked")?
+        .context("init_transactions failed")
+}
+
+/// Same rationale as [`init_transactions`]: `begin_transaction` is
+/// synchronous, so it runs on a blocking thread. rdkafka's
+/// `begin_transaction` takes no timeout, so no [`TXN_TIMEOUT`] applies here.
+async fn begin_transaction(producer: Arc<TxnProducer>) -> Result<()> {
+    tokio::task::spawn_blocking(move || producer.begin_transaction())
+        .await
+        .context("begin_transaction task panicked")?
+        .context("begin_transaction failed")
+}
+
+/// Same rationale as [`init_transactions`]: `commit_transaction` is
+/// synchronous and can block on the transaction coordinator, so it runs on
+/// a blocking thread with a bounded timeout instead of `Timeout::Never`.
+async fn commit_transaction(producer: Arc<TxnProducer>) -> Result<()> {
+    tokio::task::spawn_blocking(move || producer.commit_transaction(Timeout::After(TXN_TIMEOUT)))
+        .await
+        .context("commit_transaction task panicked")?
+        .context("commit_transaction failed")
+}
+
+impl KafkaSink {
+    /// Serializes each row of `batch` as a JSON object and produces it to
+    /// `spec.topic` within the currently open transaction, returning the
+    /// number of rows produced and the highest `timestamp_column` value seen
+    /// among them (for the caller to fall back on as a progress value if no
+    /// `Message::Watermark` arrives before EOS)
+    ///
+    /// Rows whose `timestamp_column` value is at or before `resume_watermark`
+    /// are skipped instead of produced, since they were already committed
+    /// before the crash/restart that `resume_watermark` was read back from.
+    fn produce_batch(
+        &self,
+        producer: &TxnProducer,
+        batch: &RecordBatch,
+        resume_watermark: Option<i64>,
+    ) -> Result<(u64, Option<i64>)> {
+        let rows = record_batches_to_json_rows(&[batch]).context("converting batch to JSON rows")?;
+        let mut produced = 0u64;
+        let mut max_ts: Option<i64> = None;
+
+        for row in &rows {
+            let row_ts = self.spec.timestamp_column.as_ref().and_then(|ts_column| row.get(ts_column)).and_then(Value::as_i64);
+
+            if should_skip_for_resume(row_ts, resume_watermark) {
+                continue;
+            }
+
+            let body = serde_json::to_vec(row)?;
+            let key = self
+                .spec
+                .key_column
+                .as_ref()
+                .and_then(|col| row.get(col))
+                .filter(|v| !v.is_null())
+                .map(json_key_to_string);
+
+            let mut record = BaseRecord::to(&self.spec.topic).payload(&body);
+            if let Some(key) = &key {
+                record = record.key(key.as_bytes());
+            }
+            if let Err((err, _)) = producer.send(record) {
+                anyhow::bail!("failed to produce row to '{}': {err}", self.spec.topic);
+            }
+            produced += 1;
+            if let Some(row_ts) = row_ts {
+                max_ts = Some(max_ts.map_or(row_ts, |cur: i64| cur.max(row_ts)));
+            }
+        }
+
+        Ok((produced, max_ts))
+    }
+
+    /// Produces a progress record recording the watermark just committed,
+    /// keyed so the compacted `progress_topic` only ever retains the latest one
+    fn produce_progress(
+        &self,
+        producer: &TxnProducer,
+        watermark: i64,
+    ) -> Result<()> {
+        let envelope = serde_json::json!({ "watermark": watermark });
+        let body = serde_json::to_vec(&envelope)?;
+        let record = BaseRecord::to(&self.spec.progress_topic).payload(&body).key(self.spec.id.as_bytes());
+        if let Err((err, _)) = producer.send(record) {
+            anyhow::bail!("failed to produce progress record to '{}': {err}", self.spec.progress_topic);
+        }
+        Ok(())
+    }
+}
+
+fn json_key_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Whether a row with timestamp `row_ts` was already committed before a
+/// restart that resumed from `resume_watermark`, and so should be skipped
+/// instead of re-produced. A row with no parseable timestamp is never
+/// skipped, since there's no way to tell it apart from a new one.
+fn should_skip_for_resume(row_ts: Option<i64>, resume_watermark: Option<i64>) -> bool {
+    matches!((resume_watermark, row_ts), (Some(resume), Some(ts)) if ts <= resume)
+}
+
+/// Reads the highest committed watermark from `progress_topic`, if any, by
+/// draining it from the beginning with a throwaway consumer group until no
+/// more messages arrive within a short quiet period
+async fn read_last_progress(brokers: &str, progress_topic: &str) -> Result<Option<i64>> {
+    let consumer: StreamConsumer = ClientConfig::new()
+        .set("bootstrap.servers", brokers)
+        .set("group.id", format!("rde-kafka-sink-progress-{}", uuid::Uuid::new_v4()))
+        .set("auto.offset.reset", "earliest")
+        .set("enable.auto.commit", "false")
+        .create()
+        .context("build progress-reading Kafka consumer")?;
+
+    consumer.subscribe(&[progress_topic]).context("subscribe to progress topic")?;
+
+    let mut last_watermark: Option<i64> = None;
+    loop {
+        match tokio::time::timeout(std::time::Duration::from_secs(3), consumer.recv()).await {
+            Ok(Ok(message)) => {
+                if let Some(payload) = message.payload() {
+                    if let Ok(envelope) = serde_json::from_slice::<Value>(payload) {
+                        if let Some(watermark) = envelope.get("watermark").and_then(Value::as_i64) {
+                            last_watermark = Some(watermark);
+                        }
+                    }
+                }
+            }
+            Ok(Err(err)) => {
+                warn!(error=?err, "KafkaSink: error reading progress topic '{progress_topic}'");
+                break;
+            }
+            Err(_) => break, // quiet period elapsed, caught up
+        }
+    }
+
+    Ok(last_watermark)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_key_to_string, should_skip_for_resume};
+    use serde_json::json;
+
+    #[test]
+    fn json_key_to_string_passes_strings_through_unquoted() {
+        assert_eq!(json_key_to_string(&json!("abc")), "abc");
+    }
+
+    #[test]
+    fn json_key_to_string_stringifies_non_string_values() {
+        assert_eq!(json_key_to_string(&json!(42)), "42");
+        assert_eq!(json_key_to_string(&json!(true)), "true");
+    }
+
+    #[test]
+    fn should_skip_for_resume_skips_rows_at_or_before_the_resume_watermark() {
+        assert!(should_skip_for_resume(Some(5), Some(10)));
+        assert!(should_skip_for_resume(Some(10), Some(10)));
+        assert!(!should_skip_for_resume(Some(11), Some(10)));
+    }
+
+    #[test]
+    fn should_skip_for_resume_never_skips_without_a_resume_watermark_or_row_timestamp() {
+        assert!(!should_skip_for_resume(Some(5), None));
+        assert!(!should_skip_for_resume(None, Some(10)));
+        assert!(!should_skip_for_resume(None, None));
+    }
+}
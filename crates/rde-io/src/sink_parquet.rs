@@ -1,12 +1,13 @@
 #![allow(unused)]
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::arrow::array::RecordBatch;
 use datafusion::parquet::arrow::arrow_writer::ArrowWriter;
-use datafusion::parquet::file::properties::WriterProperties;
+use datafusion::parquet::basic::{BrotliLevel, Compression, GzipLevel, ZstdLevel};
+use datafusion::parquet::file::properties::{WriterProperties, WriterVersion};
 use async_trait::async_trait;
-use rde_core::{BatchRx, Message, Operator, Sink};
+use rde_core::{BatchRx, Message, Operator, Sink, ParquetWriterSpec};
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 use tracing::info;
@@ -15,10 +16,105 @@ pub struct ParquetDirSink {
     id: String,
     dir: std::path::PathBuf,
     schema: SchemaRef,
+    writer_spec: ParquetWriterSpec,
 }
 impl ParquetDirSink {
     pub fn new(id: String, dir: std::path::PathBuf, schema: SchemaRef) -> Self {
-        Self { id, dir, schema }
+        Self::with_writer_spec(id, dir, schema, ParquetWriterSpec::default())
+    }
+
+    pub fn with_writer_spec(
+        id: String,
+        dir: std::path::PathBuf,
+        schema: SchemaRef,
+        writer_spec: ParquetWriterSpec,
+    ) -> Self {
+        Self {
+            id,
+            dir,
+            schema,
+            writer_spec,
+        }
+    }
+
+    fn writer_properties(&self) -> Result<WriterProperties> {
+        let compression = parse_compression(&self.writer_spec.compression)?;
+        let writer_version = match self.writer_spec.writer_version.as_str() {
+            "1.0" => WriterVersion::PARQUET_1_0,
+            "2.0" => WriterVersion::PARQUET_2_0,
+            other => anyhow::bail!("unknown parquet writer_version: {other} (expected \"1.0\" or \"2.0\")"),
+        };
+
+        let mut builder = WriterProperties::builder()
+            .set_compression(compression)
+            .set_dictionary_enabled(self.writer_spec.dictionary_enabled)
+            .set_writer_version(writer_version);
+
+        if let Some(limit) = self.writer_spec.data_pagesize_limit {
+            builder = builder.set_data_page_size_limit(limit);
+        }
+        if let Some(size) = self.writer_spec.write_batch_size {
+            builder = builder.set_write_batch_size(size);
+        }
+        if let Some(size) = self.writer_spec.max_row_group_size {
+            builder = builder.set_max_row_group_size(size);
+        }
+
+        Ok(builder.build())
+    }
+}
+
+/// Parse a compression codec string like `zstd(3)` or `snappy` into a Parquet `Compression`
+fn parse_compression(raw: &str) -> Result<Compression> {
+    let trimmed = raw.trim();
+    let lower = trimmed.to_lowercase();
+    let (name, level) = match lower.split_once('(') {
+        Some((name, rest)) => {
+            let level_str = rest.trim_end_matches(')').trim();
+            let level: u32 = level_str
+                .parse()
+                .with_context(|| format!("invalid compression level in {raw:?}"))?;
+            (name, Some(level))
+        }
+        None => (lower.as_str(), None),
+    };
+
+    Ok(match name {
+        "uncompressed" => Compression::UNCOMPRESSED,
+        "snappy" => Compression::SNAPPY,
+        "lz4" => Compression::LZ4,
+        "gzip" => Compression::GZIP(
+            GzipLevel::try_new(level.unwrap_or(6)).context("invalid gzip level")?,
+        ),
+        "zstd" => Compression::ZSTD(
+            ZstdLevel::try_new(level.unwrap_or(1) as i32).context("invalid zstd level")?,
+        ),
+        "brotli" => Compression::BROTLI(
+            BrotliLevel::try_new(level.unwrap_or(1)).context("invalid brotli level")?,
+        ),
+        other => anyhow::bail!("unknown parquet compression codec: {other}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_compression;
+    use datafusion::parquet::basic::Compression;
+
+    #[test]
+    fn parse_compression_accepts_known_codecs_case_insensitively() {
+        assert_eq!(parse_compression("UNCOMPRESSED").unwrap(), Compression::UNCOMPRESSED);
+        assert_eq!(parse_compression("snappy").unwrap(), Compression::SNAPPY);
+        assert_eq!(parse_compression("lz4").unwrap(), Compression::LZ4);
+        assert!(matches!(parse_compression("Gzip(9)").unwrap(), Compression::GZIP(_)));
+        assert!(matches!(parse_compression("zstd(19)").unwrap(), Compression::ZSTD(_)));
+        assert!(matches!(parse_compression("brotli(11)").unwrap(), Compression::BROTLI(_)));
+        assert!(matches!(parse_compression("zstd").unwrap(), Compression::ZSTD(_))); // default level
+    }
+
+    #[test]
+    fn parse_compression_rejects_unknown_codec() {
+        assert!(parse_compression("snappy2").is_err());
     }
 }
 #[async_trait]
@@ -36,7 +132,7 @@ impl Sink for ParquetDirSink {
         std::fs::create_dir_all(&self.dir)?;
         let file_path = self.dir.join(format!("{}.parquet", self.id));
         let file = std::fs::File::create(&file_path)?;
-        let props = WriterProperties::builder().build();
+        let props = self.writer_properties()?;
         let mut writer = ArrowWriter::try_new(file, self.schema.clone(), Some(props))?;
         while let Some(msg) = rx.recv().await {
             match msg {
@@ -2,18 +2,25 @@
 
 use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::arrow::array::RecordBatch;
-use datafusion::arrow::array::StringArray;
+use datafusion::arrow::csv::WriterBuilder as CsvWriterBuilder;
+use datafusion::arrow::json::LineDelimitedWriter;
+use datafusion::arrow::util::pretty::pretty_format_batches;
 use async_trait::async_trait;
-use rde_core::{BatchRx, Message, Operator, Sink};
+use rde_core::{BatchRx, Message, Operator, Sink, StdoutFormat};
 use tokio_util::sync::CancellationToken;
 use tracing::info;
 pub struct StdoutSink {
     id: String,
     schema: SchemaRef,
+    format: StdoutFormat,
 }
 impl StdoutSink {
     pub fn new(id: String, schema: SchemaRef) -> Self {
-        Self { id, schema }
+        Self::with_format(id, schema, StdoutFormat::Table)
+    }
+
+    pub fn with_format(id: String, schema: SchemaRef, format: StdoutFormat) -> Self {
+        Self { id, schema, format }
     }
 }
 #[async_trait]
@@ -28,21 +35,13 @@ impl Operator for StdoutSink {
 #[async_trait]
 impl Sink for StdoutSink {
     async fn run(&mut self, mut rx: BatchRx, _cancel: CancellationToken) -> anyhow::Result<()> {
+        // Emit a CSV header only once, before the first batch
+        let mut csv_header_written = false;
+
         while let Some(msg) = rx.recv().await {
             match msg {
                 Message::Batch(b) => {
-                    // Pretty printing is in arrow’s util; keep minimal here
-                    // TODO: Implement pretty printing for batch messages
-                    // Display the actual data content
-                    for row in 0..b.num_rows() {
-                        for col in 0..b.num_columns() {
-                            let array = b.column(col);
-                            if let Some(value) = array.as_any().downcast_ref::<StringArray>() {
-                                let str_val = value.value(row);
-                                println!("{}", str_val);
-                            }
-                        }
-                    }
+                    self.render_batch(&b, &mut csv_header_written)?;
                 }
                 Message::Watermark(ts) => {
                     println!("watermark={}", ts);
@@ -53,3 +52,86 @@ impl Sink for StdoutSink {
         Ok(())
     }
 }
+
+impl StdoutSink {
+    fn render_batch(&self, batch: &RecordBatch, csv_header_written: &mut bool) -> anyhow::Result<()> {
+        let rendered = Self::format_batch(self.format, batch, csv_header_written)?;
+        print!("{rendered}");
+        info!("StdoutSink: rendered batch with {} rows", batch.num_rows());
+        Ok(())
+    }
+
+    /// Renders `batch` in `format` to a string, including a trailing
+    /// newline (for `Table`, appended after the `pretty_format_batches` grid).
+    /// `csv_header_written` tracks whether the CSV header has already been
+    /// emitted for this sink, since it should only appear once.
+    fn format_batch(format: StdoutFormat, batch: &RecordBatch, csv_header_written: &mut bool) -> anyhow::Result<String> {
+        Ok(match format {
+            StdoutFormat::Table => {
+                let formatted = pretty_format_batches(std::slice::from_ref(batch))?;
+                format!("{formatted}\n")
+            }
+            StdoutFormat::Ndjson => {
+                let mut writer = LineDelimitedWriter::new(Vec::new());
+                writer.write_batches(&[batch])?;
+                writer.finish()?;
+                String::from_utf8_lossy(&writer.into_inner()).into_owned()
+            }
+            StdoutFormat::Csv => {
+                let mut builder = CsvWriterBuilder::new().with_header(!*csv_header_written);
+                let mut writer = builder.build(Vec::new());
+                writer.write(batch)?;
+                *csv_header_written = true;
+                String::from_utf8_lossy(&writer.into_inner()).into_owned()
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StdoutSink;
+    use datafusion::arrow::array::{Int64Array, RecordBatch, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use rde_core::StdoutFormat;
+    use std::sync::Arc;
+
+    fn batch() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![Arc::new(Int64Array::from(vec![1, 2])), Arc::new(StringArray::from(vec!["a", "b"]))],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn ndjson_renders_one_object_per_row() {
+        let rendered = StdoutSink::format_batch(StdoutFormat::Ndjson, &batch(), &mut false).unwrap();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], r#"{"id":1,"name":"a"}"#);
+    }
+
+    #[test]
+    fn csv_emits_header_once_across_batches() {
+        let mut header_written = false;
+        let first = StdoutSink::format_batch(StdoutFormat::Csv, &batch(), &mut header_written).unwrap();
+        assert!(first.starts_with("id,name"));
+        assert!(header_written);
+
+        let second = StdoutSink::format_batch(StdoutFormat::Csv, &batch(), &mut header_written).unwrap();
+        assert!(!second.starts_with("id,name"));
+    }
+
+    #[test]
+    fn table_renders_pretty_grid() {
+        let rendered = StdoutSink::format_batch(StdoutFormat::Table, &batch(), &mut false).unwrap();
+        assert!(rendered.contains("id"));
+        assert!(rendered.contains("name"));
+        assert!(rendered.contains('+'));
+    }
+}
@@ -15,6 +15,11 @@ pub struct CsvSource {
     pub id: String,
     pub schema: SchemaRef,
     pub spec: CsvSourceSpec,
+    /// Unprojected schema the CSV reader is built against; equal to `schema` when
+    /// no projection is configured
+    full_schema: SchemaRef,
+    /// Column indices into `full_schema`, resolved from `spec.projection`
+    projection: Option<Vec<usize>>,
 }
 
 impl CsvSource {
@@ -23,12 +28,36 @@ impl CsvSource {
             id: spec.id.clone(),
             schema: Arc::new(Schema::empty()),
             spec,
+            full_schema: Arc::new(Schema::empty()),
+            projection: None,
         })
     }
 
-    pub fn with_schema(mut self, schema: SchemaRef) -> Self {
-        self.schema = schema;
-        self
+    /// Sets the full (unprojected) schema inferred for this source, resolving
+    /// `spec.projection` (if any) into column indices and narrowing `schema`
+    /// to just the projected columns. Fails if a projected column name isn't
+    /// present in `schema`.
+    pub fn with_schema(mut self, schema: SchemaRef) -> Result<Self> {
+        self.full_schema = schema.clone();
+        match &self.spec.projection {
+            Some(names) => {
+                let indices: Vec<usize> = names
+                    .iter()
+                    .map(|name| {
+                        schema
+                            .index_of(name)
+                            .with_context(|| format!("projected column not found in schema: {name}"))
+                    })
+                    .collect::<Result<_>>()?;
+                self.schema = Arc::new(schema.project(&indices)?);
+                self.projection = Some(indices);
+            }
+            None => {
+                self.schema = schema;
+                self.projection = None;
+            }
+        }
+        Ok(self)
     }
 }
 
@@ -46,6 +75,10 @@ impl Operator for CsvSource {
 #[async_trait]
 impl Source for CsvSource {
     async fn run(&mut self, tx: BatchTx, cancel: CancellationToken) -> Result<()> {
+        if self.spec.streaming {
+            return self.run_streaming(tx, cancel).await;
+        }
+
         let mut paths: Vec<String> = vec![];
 
         for entry in glob(&self.spec.path).context("glob")? {
@@ -68,10 +101,7 @@ impl Source for CsvSource {
                 info!("Using schema: {:?}", self.schema);
             }
 
-            let mut reader = ReaderBuilder::new(self.schema.clone())
-                .with_batch_size(self.spec.batch_rows)
-                .with_header(self.spec.has_header)
-                .build(file)?; // requires schema
+            let mut reader = self.build_reader(file)?;
 
             loop {
                 if cancel.is_cancelled() {
@@ -99,3 +129,189 @@ impl Source for CsvSource {
         Ok(())
     }
 }
+
+impl CsvSource {
+    fn build_reader(&self, file: std::fs::File) -> Result<datafusion::arrow::csv::Reader<std::fs::File>> {
+        let mut builder = ReaderBuilder::new(self.full_schema.clone())
+            .with_batch_size(self.spec.batch_rows)
+            .with_header(self.spec.has_header);
+        if let Some(indices) = &self.projection {
+            builder = builder.with_projection(indices.clone());
+        }
+        Ok(builder.build(file)?)
+    }
+
+    /// Tail `spec.path` as a FIFO/named pipe, never emitting `Message::Eos`.
+    ///
+    /// Each time the reader is drained (`reader.next()` returns `None`), the
+    /// file handle is re-opened and polling resumes after a short delay, so
+    /// writers that keep appending to the same path (or re-create the pipe
+    /// per connection) are picked up without restarting the pipeline.
+    async fn run_streaming(&mut self, tx: BatchTx, cancel: CancellationToken) -> Result<()> {
+        const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+        let event_time_idx = self
+            .spec
+            .event_time_column
+            .as_ref()
+            .map(|name| {
+                self.full_schema
+                    .index_of(name)
+                    .with_context(|| format!("event_time_column not found in schema: {name}"))
+            })
+            .transpose()?;
+
+        let watermark_interval = std::time::Duration::from_millis(self.spec.watermark_interval_ms);
+        let mut last_watermark_at = tokio::time::Instant::now();
+        let mut max_event_time_ms: Option<i64> = None;
+        let mut last_emitted_watermark: Option<i64> = None;
+
+        info!("Starting streaming CSV source on: {}", self.spec.path);
+
+        'reopen: loop {
+            if cancel.is_cancelled() {
+                break;
+            }
+
+            let file = std::fs::File::open(&self.spec.path)
+                .with_context(|| format!("open {}", self.spec.path))?;
+            let mut reader = self.build_reader(file)?;
+
+            loop {
+                if cancel.is_cancelled() {
+                    break 'reopen;
+                }
+
+                if event_time_idx.is_some() && last_watermark_at.elapsed() >= watermark_interval {
+                    if let Some(max_ts) = max_event_time_ms {
+                        let candidate = max_ts - self.spec.allowed_lateness_ms;
+                        let ts = last_emitted_watermark.map_or(candidate, |prev| prev.max(candidate));
+                        if last_emitted_watermark != Some(ts) {
+                            let _ = tx.send(Message::Watermark(ts)).await;
+                            last_emitted_watermark = Some(ts);
+                        }
+                    }
+                    last_watermark_at = tokio::time::Instant::now();
+                }
+
+                match reader.next() {
+                    Some(Ok(batch)) => {
+                        info!("Read streaming batch with {} rows", batch.num_rows());
+                        if let Some(idx) = event_time_idx {
+                            if let Some(ts) = max_event_time_in_column(&batch, idx) {
+                                max_event_time_ms = Some(max_event_time_ms.map_or(ts, |cur| cur.max(ts)));
+                            }
+                        }
+                        if tx.send(Message::Batch(batch)).await.is_err() {
+                            break 'reopen;
+                        }
+                    }
+                    Some(Err(e)) => {
+                        warn!(error=?e, "csv read error");
+                    }
+                    None => {
+                        // Drained; re-open the path and keep polling rather than emitting Eos
+                        tokio::time::sleep(POLL_INTERVAL).await;
+                        continue 'reopen;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Finds the maximum value in an event-time column, interpreted as epoch milliseconds
+fn max_event_time_in_column(batch: &datafusion::arrow::array::RecordBatch, col_idx: usize) -> Option<i64> {
+    use datafusion::arrow::array::{Array, Int64Array, TimestampMillisecondArray};
+
+    let array = batch.column(col_idx);
+    if let Some(arr) = array.as_any().downcast_ref::<Int64Array>() {
+        return arr.iter().flatten().max();
+    }
+    if let Some(arr) = array.as_any().downcast_ref::<TimestampMillisecondArray>() {
+        return arr.iter().flatten().max();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CsvSource;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use rde_core::{CsvSourceSpec, Operator};
+    use std::sync::Arc;
+
+    fn full_schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("amount", DataType::Float64, true),
+        ]))
+    }
+
+    fn spec(projection: Option<Vec<String>>) -> CsvSourceSpec {
+        CsvSourceSpec {
+            id: "csv-1".to_string(),
+            path: "unused.csv".to_string(),
+            has_header: true,
+            batch_rows: 1024,
+            projection,
+            streaming: false,
+            event_time_column: None,
+            watermark_interval_ms: 5_000,
+            allowed_lateness_ms: 0,
+        }
+    }
+
+    #[test]
+    fn with_schema_narrows_schema_to_the_projection_in_declared_order() {
+        let source = CsvSource::try_new(spec(Some(vec!["amount".to_string(), "id".to_string()])))
+            .unwrap()
+            .with_schema(full_schema())
+            .unwrap();
+
+        let projected_names: Vec<&str> = source.schema().fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(projected_names, vec!["amount", "id"]);
+    }
+
+    #[test]
+    fn with_schema_passes_through_unprojected() {
+        let source = CsvSource::try_new(spec(None)).unwrap().with_schema(full_schema()).unwrap();
+        assert_eq!(source.schema(), full_schema());
+    }
+
+    #[test]
+    fn with_schema_fails_fast_on_unknown_projected_column() {
+        let err = CsvSource::try_new(spec(Some(vec!["nope".to_string()])))
+            .unwrap()
+            .with_schema(full_schema())
+            .unwrap_err();
+        assert!(err.to_string().contains("nope"));
+    }
+
+    #[test]
+    fn max_event_time_in_column_reads_int64_and_timestamp_columns() {
+        use super::max_event_time_in_column;
+        use datafusion::arrow::array::{Int64Array, RecordBatch, TimestampMillisecondArray};
+
+        let int_schema = Arc::new(Schema::new(vec![Field::new("ts", DataType::Int64, true)]));
+        let int_batch =
+            RecordBatch::try_new(int_schema, vec![Arc::new(Int64Array::from(vec![Some(5), None, Some(9), Some(2)]))])
+                .unwrap();
+        assert_eq!(max_event_time_in_column(&int_batch, 0), Some(9));
+
+        let ts_schema = Arc::new(Schema::new(vec![Field::new(
+            "ts",
+            DataType::Timestamp(datafusion::arrow::datatypes::TimeUnit::Millisecond, None),
+            true,
+        )]));
+        let ts_batch = RecordBatch::try_new(
+            ts_schema,
+            vec![Arc::new(TimestampMillisecondArray::from(vec![Some(100), Some(300)]))],
+        )
+        .unwrap();
+        assert_eq!(max_event_time_in_column(&ts_batch, 0), Some(300));
+    }
+}
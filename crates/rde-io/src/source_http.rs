@@ -0,0 +1,226 @@
+#![allow(unused)]
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::post,
+    Router,
+};
+use datafusion::arrow::array::RecordBatch;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::json::reader::infer_json_schema_from_iterator;
+use datafusion::arrow::json::ReaderBuilder;
+use rde_core::{BatchTx, HttpSourceSpec, Message, Operator, Source};
+use serde_json::Value;
+use std::io::Cursor;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Push-based HTTP ingestion source
+///
+/// Accepts POSTed NDJSON or a JSON array of events on `spec.path` and turns
+/// them into `RecordBatch`es, forwarding them as `Message::Batch`. Events
+/// arrive schema-less, so the first non-empty flush infers a schema from the
+/// buffered events and every later batch is decoded against that same
+/// schema. Partial batches are flushed on a timer so events don't sit
+/// unbounded between requests.
+pub struct HttpSource {
+    id: String,
+    schema: SchemaRef,
+    spec: HttpSourceSpec,
+}
+
+impl HttpSource {
+    pub fn new(spec: HttpSourceSpec) -> Self {
+        Self {
+            id: spec.id.clone(),
+            schema: Arc::new(datafusion::arrow::datatypes::Schema::empty()),
+            spec,
+        }
+    }
+
+    pub fn with_schema(mut self, schema: SchemaRef) -> Self {
+        self.schema = schema;
+        self
+    }
+}
+
+#[async_trait]
+impl Operator for HttpSource {
+    fn name(&self) -> &str {
+        &self.id
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Shared state for the Axum handlers
+///
+/// `schema` starts out empty (HTTP events arrive schema-less) and is
+/// inferred from the first non-empty flush, so it needs to be mutable even
+/// though handlers only ever see a shared `Arc<AppState>`.
+struct AppState {
+    schema: Mutex<SchemaRef>,
+    tx: BatchTx,
+    batch_rows: usize,
+    buffer: Mutex<Vec<Value>>,
+}
+
+#[async_trait]
+impl Source for HttpSource {
+    async fn run(&mut self, tx: BatchTx, cancel: CancellationToken) -> Result<()> {
+        let state = Arc::new(AppState {
+            schema: Mutex::new(self.schema.clone()),
+            tx: tx.clone(),
+            batch_rows: self.spec.batch_rows,
+            buffer: Mutex::new(Vec::new()),
+        });
+
+        let app = Router::new()
+            .route(&self.spec.path, post(ingest))
+            .with_state(state.clone());
+
+        let listener = TcpListener::bind(&self.spec.bind_addr)
+            .await
+            .with_context(|| format!("bind HTTP source to {}", self.spec.bind_addr))?;
+        info!(
+            "HttpSource: listening on {} (path {})",
+            self.spec.bind_addr, self.spec.path
+        );
+
+        let flush_interval = std::time::Duration::from_millis(self.spec.flush_interval_ms);
+        let flush_state = state.clone();
+        let flush_cancel = cancel.clone();
+        let flush_task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(flush_interval);
+            loop {
+                tokio::select! {
+                    _ = flush_cancel.cancelled() => break,
+                    _ = ticker.tick() => {
+                        if let Err(e) = flush(&flush_state).await {
+                            warn!(error=?e, "HttpSource: periodic flush failed");
+                        }
+                    }
+                }
+            }
+        });
+
+        let server_cancel = cancel.clone();
+        axum::serve(listener, app)
+            .with_graceful_shutdown(async move { server_cancel.cancelled().await })
+            .await
+            .context("HTTP source server error")?;
+
+        flush_task.abort();
+        flush(&state).await?;
+
+        info!("HttpSource finished, sending EOS");
+        let _ = tx.send(Message::Eos).await;
+        Ok(())
+    }
+}
+
+async fn ingest(
+    State(state): State<Arc<AppState>>,
+    body: axum::body::Bytes,
+) -> StatusCode {
+    let values = match parse_body(&body) {
+        Ok(values) => values,
+        Err(e) => {
+            warn!(error=?e, "HttpSource: failed to parse request body");
+            return StatusCode::BAD_REQUEST;
+        }
+    };
+
+    let should_flush = {
+        let mut buffer = state.buffer.lock().await;
+        buffer.extend(values);
+        buffer.len() >= state.batch_rows
+    };
+
+    if should_flush {
+        if let Err(e) = flush(&state).await {
+            warn!(error=?e, "HttpSource: flush-on-threshold failed");
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    }
+
+    StatusCode::ACCEPTED
+}
+
+/// Parses a request body as either NDJSON (one object per line) or a JSON array
+fn parse_body(body: &[u8]) -> Result<Vec<Value>> {
+    let text = std::str::from_utf8(body).context("request body is not valid UTF-8")?;
+    let trimmed = text.trim();
+
+    if trimmed.starts_with('[') {
+        let values: Vec<Value> = serde_json::from_str(trimmed).context("invalid JSON array body")?;
+        return Ok(values);
+    }
+
+    trimmed
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| serde_json::from_str(l).context("invalid NDJSON line"))
+        .collect()
+}
+
+/// Drains the buffer and forwards a single `RecordBatch`, if non-empty
+async fn flush(state: &AppState) -> Result<()> {
+    let values = {
+        let mut buffer = state.buffer.lock().await;
+        if buffer.is_empty() {
+            return Ok(());
+        }
+        std::mem::take(&mut *buffer)
+    };
+
+    let batch = {
+        let mut schema = state.schema.lock().await;
+        infer_schema_if_empty(&mut schema, &values)?;
+        json_values_to_batch(&schema, &values)?
+    };
+    info!("HttpSource: flushing batch with {} rows", batch.num_rows());
+    if state.tx.send(Message::Batch(batch)).await.is_err() {
+        warn!("HttpSource: failed to send batch to channel");
+    }
+    Ok(())
+}
+
+/// Infers `schema` from `values` the first time a non-empty batch is seen,
+/// since HTTP events arrive schema-less and `schema` starts out empty
+fn infer_schema_if_empty(schema: &mut SchemaRef, values: &[Value]) -> Result<()> {
+    if !schema.fields().is_empty() || values.is_empty() {
+        return Ok(());
+    }
+    let inferred = infer_json_schema_from_iterator(values.iter().map(|v| Ok::<_, ArrowError>(v.clone())))?;
+    info!("HttpSource: inferred schema from first batch: {:?}", inferred.fields());
+    *schema = Arc::new(inferred);
+    Ok(())
+}
+
+/// Parses buffered JSON values into a single `RecordBatch` matching `schema`
+fn json_values_to_batch(schema: &SchemaRef, values: &[Value]) -> Result<RecordBatch> {
+    let mut ndjson = String::new();
+    for value in values {
+        ndjson.push_str(&serde_json::to_string(value)?);
+        ndjson.push('\n');
+    }
+
+    let mut reader = ReaderBuilder::new(schema.clone())
+        .with_batch_size(values.len().max(1))
+        .build(Cursor::new(ndjson.into_bytes()))?;
+
+    reader
+        .next()
+        .context("no batch produced from buffered events")?
+        .context("failed to decode buffered events")
+}
@@ -0,0 +1,242 @@
+#![allow(unused)]
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::prelude::SessionContext;
+use futures::StreamExt;
+use iceberg::io::FileIOBuilder;
+use iceberg::table::StaticTable;
+use iceberg::TableIdent;
+use iceberg_datafusion::IcebergTableProvider;
+use rde_core::{BatchTx, IcebergSourceSpec, Message, Operator, Source};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+const SCAN_TABLE_ALIAS: &str = "iceberg_source";
+
+/// Iceberg table scan source
+///
+/// Reads an existing Iceberg table back into a pipeline via the
+/// `iceberg-datafusion` `TableProvider`, applying an optional column
+/// projection and SQL predicate so only the requested columns/rows are
+/// scanned out of the underlying Parquet files.
+pub struct IcebergSource {
+    id: String,
+    schema: SchemaRef,
+    spec: IcebergSourceSpec,
+}
+
+impl IcebergSource {
+    pub fn new(spec: IcebergSourceSpec) -> Self {
+        Self {
+            id: spec.id.clone(),
+            schema: Arc::new(Schema::empty()),
+            spec,
+        }
+    }
+
+    /// Sets the projected/filtered Arrow schema resolved upfront (see
+    /// [`resolve_schema`]) so the CLI's transform/sink schema chaining
+    /// works without needing to start the scan first.
+    pub fn with_schema(mut self, schema: SchemaRef) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    fn scan_query(&self) -> String {
+        build_scan_query(&self.spec)
+    }
+}
+
+/// Builds the `SELECT <projection> FROM iceberg_source [WHERE <filter>]`
+/// query pushed into DataFusion for both the real scan ([`IcebergSource::run`])
+/// and upfront schema resolution ([`resolve_schema`]), so projection/filter
+/// pushdown stays in one place
+fn build_scan_query(spec: &IcebergSourceSpec) -> String {
+    let select_list = match &spec.projection {
+        Some(cols) if !cols.is_empty() => cols.join(", "),
+        _ => "*".to_string(),
+    };
+    let mut query = format!("SELECT {select_list} FROM {SCAN_TABLE_ALIAS}");
+    if let Some(filter) = &spec.filter {
+        query.push_str(&format!(" WHERE {filter}"));
+    }
+    query
+}
+
+#[async_trait]
+impl Operator for IcebergSource {
+    fn name(&self) -> &str {
+        &self.id
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[async_trait]
+impl Source for IcebergSource {
+    async fn run(&mut self, tx: BatchTx, cancel: CancellationToken) -> Result<()> {
+        info!("Starting Iceberg source scan for table: {}", self.spec.table_name);
+
+        let provider = load_table_provider(&self.spec).await?;
+
+        let ctx = SessionContext::new();
+        ctx.register_table(SCAN_TABLE_ALIAS, provider)
+            .context("register Iceberg table with DataFusion context")?;
+
+        let query = self.scan_query();
+        info!("Iceberg source: running scan query: {}", query);
+        let df = ctx.sql(&query).await.context("plan Iceberg scan query")?;
+        self.schema = Arc::new(df.schema().as_arrow().clone());
+
+        let mut stream = df.execute_stream().await.context("execute Iceberg scan")?;
+
+        while let Some(batch) = stream.next().await {
+            if cancel.is_cancelled() {
+                info!("Iceberg source cancelled");
+                break;
+            }
+            let batch = batch.context("Iceberg scan batch error")?;
+            info!("Iceberg source: read batch with {} rows", batch.num_rows());
+            if tx.send(Message::Batch(batch)).await.is_err() {
+                break;
+            }
+        }
+
+        info!("Iceberg source finished, sending EOS");
+        let _ = tx.send(Message::Eos).await;
+        Ok(())
+    }
+}
+
+/// Reads the `version-hint.text` pointer `IcebergSink`'s `StorageCatalog`
+/// writes on every commit and resolves it to the metadata file it names
+async fn resolve_metadata_path(file_io: &iceberg::io::FileIO, location: &str) -> Result<String> {
+    let hint_path = format!("{location}/metadata/version-hint.text");
+    let input = file_io.new_input(&hint_path).context("open version-hint.text")?;
+    let bytes = input.read().await.context("read version-hint.text")?;
+    let file_name = String::from_utf8(bytes.to_vec())?.trim().to_string();
+    Ok(format!("{location}/metadata/{file_name}"))
+}
+
+/// Picks the snapshot id to scan from `spec`'s time-travel options:
+/// `snapshot_id` directly if set, else the latest entry in `snapshot_log`
+/// at or before `as_of_timestamp`, else `None` for the table's current
+/// snapshot
+fn resolve_snapshot_id(table: &iceberg::table::Table, spec: &IcebergSourceSpec) -> Option<i64> {
+    if let Some(snapshot_id) = spec.snapshot_id {
+        return Some(snapshot_id);
+    }
+    let as_of = spec.as_of_timestamp?;
+    table
+        .metadata()
+        .history()
+        .iter()
+        .filter(|entry| entry.timestamp_ms <= as_of)
+        .max_by_key(|entry| entry.timestamp_ms)
+        .map(|entry| entry.snapshot_id)
+}
+
+/// Loads the Iceberg table's `TableProvider` from its current
+/// `version-hint.text`-pointed metadata file, as written by
+/// [`crate::sink_iceberg::IcebergSink`]
+async fn load_table_provider(spec: &IcebergSourceSpec) -> Result<Arc<IcebergTableProvider>> {
+    let location = format!("s3://{}/{}", spec.bucket, spec.table_name);
+
+    let file_io = FileIOBuilder::new("s3")
+        .with_prop("s3.endpoint", &spec.endpoint)
+        .with_prop("s3.access-key-id", &spec.access_key)
+        .with_prop("s3.secret-access-key", &spec.secret_key)
+        .with_prop("s3.region", &spec.region)
+        .build()
+        .context("build Iceberg FileIO")?;
+
+    let metadata_path = resolve_metadata_path(&file_io, &location).await?;
+    let ident = TableIdent::from_strs([spec.table_name.clone()]).context("invalid table name")?;
+    let table = StaticTable::from_metadata_file(&metadata_path, ident, file_io)
+        .await
+        .context("load Iceberg table metadata")?
+        .into_table();
+
+    let provider = match resolve_snapshot_id(&table, spec) {
+        Some(snapshot_id) => {
+            info!("Iceberg source: time-travel read of table {} at snapshot {}", spec.table_name, snapshot_id);
+            IcebergTableProvider::try_new_from_table_snapshot(table, snapshot_id)
+                .await
+                .context("build Iceberg table provider at snapshot")?
+        }
+        None => IcebergTableProvider::try_new(table).await.context("build Iceberg table provider")?,
+    };
+
+    Ok(Arc::new(provider))
+}
+
+/// Resolves the projected/filtered Arrow schema for `spec` without starting a
+/// scan, for use during the CLI's upfront schema-inference phase
+pub async fn resolve_schema(spec: &IcebergSourceSpec) -> Result<SchemaRef> {
+    let provider = load_table_provider(spec).await?;
+    let ctx = SessionContext::new();
+    ctx.register_table(SCAN_TABLE_ALIAS, provider)
+        .context("register Iceberg table with DataFusion context")?;
+
+    let query = build_scan_query(spec);
+    let df = ctx.sql(&query).await.context("plan Iceberg scan query")?;
+    Ok(Arc::new(df.schema().as_arrow().clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_scan_query;
+    use rde_core::IcebergSourceSpec;
+
+    fn spec(projection: Option<Vec<&str>>, filter: Option<&str>) -> IcebergSourceSpec {
+        IcebergSourceSpec {
+            id: "iceberg-1".to_string(),
+            table_name: "events".to_string(),
+            bucket: "bucket".to_string(),
+            endpoint: "http://localhost:9000".to_string(),
+            access_key: "key".to_string(),
+            secret_key: "secret".to_string(),
+            region: "us-east-1".to_string(),
+            projection: projection.map(|cols| cols.into_iter().map(String::from).collect()),
+            filter: filter.map(String::from),
+            snapshot_id: None,
+            as_of_timestamp: None,
+        }
+    }
+
+    #[test]
+    fn no_projection_or_filter_selects_star() {
+        assert_eq!(build_scan_query(&spec(None, None)), "SELECT * FROM iceberg_source");
+    }
+
+    #[test]
+    fn projection_and_filter_are_pushed_into_the_query() {
+        assert_eq!(
+            build_scan_query(&spec(Some(vec!["id", "amount"]), Some("amount > 10"))),
+            "SELECT id, amount FROM iceberg_source WHERE amount > 10"
+        );
+    }
+
+    #[test]
+    fn empty_projection_falls_back_to_star() {
+        assert_eq!(build_scan_query(&spec(Some(vec![]), None)), "SELECT * FROM iceberg_source");
+    }
+
+    #[test]
+    fn projection_without_a_filter_omits_the_where_clause() {
+        assert_eq!(build_scan_query(&spec(Some(vec!["id"]), None)), "SELECT id FROM iceberg_source");
+    }
+
+    #[test]
+    fn filter_without_a_projection_still_selects_star() {
+        assert_eq!(
+            build_scan_query(&spec(None, Some("amount > 10"))),
+            "SELECT * FROM iceberg_source WHERE amount > 10"
+        );
+    }
+}
@@ -1,30 +1,35 @@
 use rdkafka::ClientConfig;
-use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
 use rdkafka::error::KafkaError;
 use rdkafka::message::BorrowedMessage;
+use rdkafka::topic_partition_list::TopicPartitionList;
 use rdkafka::Message as KafkaMessage;
 
-use anyhow::Result;
-use arrow_array::{ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray, RecordBatch};
-use arrow_schema::{Field, Schema, SchemaRef, DataType};
+use anyhow::{Context, Result};
+use arrow_array::{Int64Array, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
 use async_trait::async_trait;
 use futures::StreamExt;
-use rde_core::{BatchTx, Message, Operator, Source};
+use rde_core::{BatchTx, Message, MessageEncoding, Operator, Source};
 use serde_json::Value;
 use std::sync::Arc;
 use std::collections::HashMap;
-use tokio::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
 /// Represents a stream of incoming Kafka messages.
-/// For now, we assume JSON payloads (common in data engineering),
-/// but this can be generic over Avro/Protobuf/etc.
+///
+/// Payloads may be plain JSON, or Confluent wire-format Avro — see
+/// [`MessageEncoding`] and [`SchemaRegistryClient`].
 pub struct KafkaSource {
     pub brokers: String,
     pub group_id: String,
     pub topic: String,
+    encoding: MessageEncoding,
+    registry: Option<Arc<SchemaRegistryClient>>,
 }
 
 impl KafkaSource {
@@ -33,25 +38,48 @@ impl KafkaSource {
             brokers: brokers.to_string(),
             group_id: group_id.to_string(),
             topic: topic.to_string(),
+            encoding: MessageEncoding::Json,
+            registry: None,
         }
     }
 
-    // start consuming messages and yield them as serde_json::Value
-    pub async fn stream(&self) -> Result<impl futures::Stream<Item = Value>, KafkaError> {
+    /// Configures the wire encoding and, for `Avro`, the Schema Registry
+    /// used to resolve payload schema IDs
+    pub fn with_encoding(mut self, encoding: MessageEncoding, registry_url: Option<String>) -> Self {
+        self.encoding = encoding;
+        self.registry = registry_url.map(|url| Arc::new(SchemaRegistryClient::new(url)));
+        self
+    }
+
+    /// Starts consuming messages, yielding each decode outcome alongside the
+    /// `(partition, offset)` it was read from. A message that fails to
+    /// decode (bad Avro body, malformed Confluent envelope, registry fetch
+    /// error) is still yielded as [`DecodedMessage::Err`] rather than
+    /// dropped, so its offset isn't silently skipped past — see
+    /// [`KafkaPipelineSource::run`], which dead-letters or commits it.
+    /// Offsets are never auto-committed — the returned `StreamConsumer` is
+    /// handed back so the caller can commit only once a message has been
+    /// durably accepted downstream.
+    pub async fn stream(
+        &self,
+    ) -> Result<(impl futures::Stream<Item = (i32, i64, DecodedMessage)>, Arc<StreamConsumer>), KafkaError> {
         let consumer: StreamConsumer = ClientConfig::new()
             .set("bootstrap.servers", &self.brokers)
             .set("group.id", &self.group_id)
             .set("enable.partition.eof", "false")
             .set("session.timeout.ms", "6000")
-            .set("enable.auto.commit", "true")
+            .set("enable.auto.commit", "false")
             .create()?;
 
         consumer.subscribe(&[&self.topic])?;
 
+        let consumer = Arc::new(consumer);
         let (tx, rx) = mpsc::channel(100);
 
         // Spawn consumer task
-        let consumer_task = consumer;
+        let consumer_task = consumer.clone();
+        let encoding = self.encoding;
+        let registry = self.registry.clone();
         tokio::spawn(async move {
             let mut stream = consumer_task.stream();
             info!("Kafka consumer started, waiting for messages...");
@@ -59,7 +87,20 @@ impl KafkaSource {
                 let value = match result {
                     Ok(m) => {
                         info!("Received Kafka message");
-                        parse_message(&m).ok()
+                        let partition = m.partition();
+                        let offset = m.offset();
+                        let raw = m.payload().unwrap_or_default().to_vec();
+                        let decoded = match decode_message(&m, encoding, registry.as_deref()).await {
+                            Ok(value) => DecodedMessage::Ok(value),
+                            Err(e) => {
+                                warn!(
+                                    "failed to decode Kafka message at partition {} offset {}: {:#}",
+                                    partition, offset, e
+                                );
+                                DecodedMessage::Err { raw, error: e.to_string() }
+                            }
+                        };
+                        Some((partition, offset, decoded))
                     },
                     Err(e) => {
                         warn!("kafka error on consuming message from broker: {:?}", e);
@@ -76,16 +117,182 @@ impl KafkaSource {
             }
         });
 
-        Ok(ReceiverStream::new(rx))
+        Ok((ReceiverStream::new(rx), consumer))
     }
 }
 
+/// Commits the highest observed offset (exclusive) per partition in
+/// `offsets`, for batches that have already been accepted downstream
+fn commit_offsets(consumer: &StreamConsumer, topic: &str, offsets: &HashMap<i32, i64>) -> Result<()> {
+    let mut tpl = TopicPartitionList::new();
+    for (&partition, &offset) in offsets {
+        tpl.add_partition_offset(topic, partition, rdkafka::Offset::Offset(offset))
+            .context("build topic partition list for offset commit")?;
+    }
+    consumer
+        .commit(&tpl, CommitMode::Async)
+        .context("commit Kafka consumer offsets")
+}
+
+/// Caches schemas fetched from a Confluent-compatible Schema Registry by ID
+pub struct SchemaRegistryClient {
+    base_url: String,
+    http: reqwest::Client,
+    cache: Mutex<HashMap<u32, Arc<apache_avro::Schema>>>,
+}
+
+impl SchemaRegistryClient {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            base_url,
+            http: reqwest::Client::new(),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Fetches (and caches) the Avro schema registered under `schema_id`
+    pub async fn get_avro_schema(&self, schema_id: u32) -> Result<Arc<apache_avro::Schema>> {
+        if let Some(schema) = self.cache.lock().await.get(&schema_id) {
+            return Ok(schema.clone());
+        }
+
+        let url = format!("{}/schemas/ids/{}", self.base_url, schema_id);
+        let resp: RegistrySchemaResponse = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("fetch schema {schema_id} from registry"))?
+            .json()
+            .await
+            .with_context(|| format!("parse registry response for schema {schema_id}"))?;
+
+        let schema = Arc::new(
+            apache_avro::Schema::parse_str(&resp.schema)
+                .with_context(|| format!("invalid Avro schema for id {schema_id}"))?,
+        );
+
+        self.cache.lock().await.insert(schema_id, schema.clone());
+        Ok(schema)
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct RegistrySchemaResponse {
+    schema: String,
+}
+
+/// Splits a Confluent wire-format payload into its schema ID and encoded body.
+///
+/// The format is: a `0x00` magic byte, a 4-byte big-endian schema ID, then the
+/// Avro-encoded body.
+fn decode_confluent_envelope(payload: &[u8]) -> Result<(u32, &[u8])> {
+    if payload.len() < 5 || payload[0] != 0x00 {
+        anyhow::bail!("payload is not Confluent wire-format (missing 0x00 magic byte)");
+    }
+    let schema_id = u32::from_be_bytes([payload[1], payload[2], payload[3], payload[4]]);
+    Ok((schema_id, &payload[5..]))
+}
+
+/// Outcome of decoding one Kafka message's payload
+pub enum DecodedMessage {
+    /// Payload decoded successfully
+    Ok(Value),
+    /// Decoding failed; `raw` is the original payload (for dead-lettering)
+    /// and `error` describes what went wrong (for logging)
+    Err { raw: Vec<u8>, error: String },
+}
+
+/// Decodes a single Kafka message's payload into a `serde_json::Value`,
+/// dispatching on the configured [`MessageEncoding`]
+async fn decode_message(
+    m: &BorrowedMessage<'_>,
+    encoding: MessageEncoding,
+    registry: Option<&SchemaRegistryClient>,
+) -> Result<Value> {
+    let payload = match m.payload() {
+        Some(payload) => payload,
+        None => return Ok(Value::Null),
+    };
+
+    match encoding {
+        MessageEncoding::Json => Ok(serde_json::from_slice(payload)?),
+        MessageEncoding::Avro => {
+            let registry = registry.context("Avro encoding requires schema.registry_url")?;
+            let (schema_id, body) = decode_confluent_envelope(payload)?;
+            let schema = registry.get_avro_schema(schema_id).await?;
+            let avro_value = apache_avro::from_avro_datum(&schema, &mut std::io::Cursor::new(body), None)
+                .context("decode Avro body")?;
+            avro_value_to_json(&avro_value)
+        }
+    }
+}
+
+/// Converts a decoded Avro value into the equivalent `serde_json::Value`
+fn avro_value_to_json(value: &apache_avro::types::Value) -> Result<Value> {
+    use apache_avro::types::Value as Avro;
+    Ok(match value {
+        Avro::Null => Value::Null,
+        Avro::Boolean(b) => Value::Bool(*b),
+        Avro::Int(i) => Value::from(*i),
+        Avro::Long(i) => Value::from(*i),
+        Avro::Float(f) => Value::from(*f),
+        Avro::Double(f) => Value::from(*f),
+        Avro::String(s) | Avro::Enum(_, s) => Value::String(s.clone()),
+        Avro::Bytes(b) | Avro::Fixed(_, b) => Value::String(String::from_utf8_lossy(b).to_string()),
+        Avro::Union(_, inner) => avro_value_to_json(inner)?,
+        Avro::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(avro_value_to_json)
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        Avro::Map(entries) => {
+            let mut obj = serde_json::Map::new();
+            for (k, v) in entries {
+                obj.insert(k.clone(), avro_value_to_json(v)?);
+            }
+            Value::Object(obj)
+        }
+        Avro::Record(fields) => {
+            let mut obj = serde_json::Map::new();
+            for (name, v) in fields {
+                obj.insert(name.clone(), avro_value_to_json(v)?);
+            }
+            Value::Object(obj)
+        }
+        other => Value::String(format!("{other:?}")),
+    })
+}
+
+/// Outcome of feeding one message into [`DynamicSchemaManager::observe`]
+pub enum SchemaObservation {
+    /// Still buffering the initial sample; the message was retained
+    /// internally and has not been assigned a schema yet
+    Sampling,
+    /// A schema is available. `changed` is true when the schema grew or was
+    /// widened by this message; `completed_sampling` is true exactly once,
+    /// on the message that fills the sample buffer — callers should then
+    /// call [`DynamicSchemaManager::drain_sampled_rows`] to recover the rows
+    /// buffered during sampling, this message's own row included (it is the
+    /// last of them).
+    Ready { changed: bool, completed_sampling: bool },
+}
+
 /// Dynamic schema manager for handling evolving JSON schemas
 pub struct DynamicSchemaManager {
     current_schema: Option<SchemaRef>,
     field_types: HashMap<String, DataType>,
     configured_schema: Option<SchemaRef>,
     auto_infer: bool,
+    /// Number of leading messages to sample before committing to a unified
+    /// schema; `0` disables sampling (the first message sets the schema and
+    /// later ones extend it additively)
+    sample_size: usize,
+    /// Raw messages buffered while a sample is being collected
+    sampling_buffer: Vec<Value>,
+    /// Set once sampling has produced a schema, so it only runs once
+    sampling_resolved: bool,
 }
 
 impl DynamicSchemaManager {
@@ -95,13 +302,17 @@ impl DynamicSchemaManager {
             field_types: HashMap::new(),
             configured_schema: None,
             auto_infer: true,
+            sample_size: 0,
+            sampling_buffer: Vec::new(),
+            sampling_resolved: false,
         }
     }
 
     pub fn with_config(mut self, config: &Option<rde_core::SchemaConfig>) -> Self {
         if let Some(schema_config) = config {
             self.auto_infer = schema_config.auto_infer;
-            
+            self.sample_size = schema_config.sample_size;
+
             if !schema_config.fields.is_empty() {
                 let fields: Vec<Field> = schema_config.fields.iter()
                     .map(|f| {
@@ -110,6 +321,8 @@ impl DynamicSchemaManager {
                             "float64" => DataType::Float64,
                             "string" => DataType::Utf8,
                             "boolean" => DataType::Boolean,
+                            "timestamp" => DataType::Timestamp(TimeUnit::Millisecond, None),
+                            "decimal" => DataType::Decimal128(38, 10),
                             _ => DataType::Utf8, // Default to string for unknown types
                         };
                         Field::new(&f.name, data_type, f.nullable)
@@ -165,7 +378,15 @@ impl DynamicSchemaManager {
                     DataType::List(Arc::new(Field::new("item", first_type, true)))
                 }
             }
-            Value::Object(_) => DataType::Utf8, // Treat objects as JSON strings for now
+            Value::Object(obj) => {
+                // Nested objects become real Struct fields, decoded recursively
+                // by `json_rows_to_batch` into `StructArray`s
+                let fields: Vec<Field> = obj
+                    .iter()
+                    .map(|(key, val)| Field::new(key, self.infer_field_type(val), true))
+                    .collect();
+                DataType::Struct(fields.into())
+            }
         }
     }
 
@@ -203,6 +424,137 @@ impl DynamicSchemaManager {
             .or_else(|| self.configured_schema.clone())
     }
 
+    /// Feeds one decoded message through schema inference.
+    ///
+    /// When `sample_size` is configured, the first `sample_size` messages
+    /// are buffered and merged into a single schema via [`promote_type`]'s
+    /// type-promotion lattice instead of replacing the schema on every
+    /// field change; after that, evolution is additive — new fields extend
+    /// the schema and existing fields widen in place, but never disappear.
+    pub fn observe(&mut self, value: &Value) -> SchemaObservation {
+        if !self.auto_infer {
+            if self.current_schema.is_none() && self.configured_schema.is_some() {
+                self.current_schema = self.configured_schema.clone();
+                return SchemaObservation::Ready { changed: true, completed_sampling: false };
+            }
+            return SchemaObservation::Ready { changed: false, completed_sampling: false };
+        }
+
+        if self.sample_size > 0 && !self.sampling_resolved {
+            self.sampling_buffer.push(value.clone());
+            if self.sampling_buffer.len() < self.sample_size {
+                return SchemaObservation::Sampling;
+            }
+            self.sampling_resolved = true;
+            let unified = self.unify_sampled_schema();
+            info!("Sampled {} messages, unified schema: {:?}", self.sampling_buffer.len(), unified.fields());
+            let changed = self.apply_schema(unified);
+            return SchemaObservation::Ready { changed, completed_sampling: true };
+        }
+
+        let changed = self.extend_schema_for(value);
+        SchemaObservation::Ready { changed, completed_sampling: false }
+    }
+
+    /// Drains the messages buffered while a sample was being collected, in
+    /// the order they arrived, so the caller can replay them against the
+    /// schema that sampling just produced
+    pub fn drain_sampled_rows(&mut self) -> Vec<Value> {
+        std::mem::take(&mut self.sampling_buffer)
+    }
+
+    /// Replaces the current schema, returning whether its fields actually changed
+    fn apply_schema(&mut self, schema: SchemaRef) -> bool {
+        let changed = self.current_schema.as_ref().map(|s| s.fields()) != Some(schema.fields());
+        self.current_schema = Some(schema);
+        changed
+    }
+
+    /// Merges per-field inferred types across every message buffered during
+    /// sampling, widening conflicting types via [`promote_type`]. Fields seen
+    /// as `null` in some messages don't narrow a type already observed
+    /// elsewhere in the sample.
+    fn unify_sampled_schema(&self) -> SchemaRef {
+        let mut fields: Vec<Field> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+
+        for row in &self.sampling_buffer {
+            let Some(obj) = row.as_object() else { continue };
+            for (key, val) in obj {
+                if val.is_null() {
+                    index.entry(key.clone()).or_insert_with(|| {
+                        fields.push(Field::new(key, DataType::Utf8, true));
+                        fields.len() - 1
+                    });
+                    continue;
+                }
+
+                let observed = self.infer_field_type(val);
+                match index.get(key) {
+                    Some(&i) => {
+                        let widened = promote_type(fields[i].data_type(), &observed);
+                        if &widened != fields[i].data_type() {
+                            fields[i] = Field::new(fields[i].name(), widened, true);
+                        }
+                    }
+                    None => {
+                        index.insert(key.clone(), fields.len());
+                        fields.push(Field::new(key, observed, true));
+                    }
+                }
+            }
+        }
+
+        Arc::new(Schema::new(fields))
+    }
+
+    /// Extends the current schema additively for one message: new fields are
+    /// appended and existing fields are widened in place via [`promote_type`],
+    /// but no field is ever removed once observed
+    fn extend_schema_for(&mut self, value: &Value) -> bool {
+        let Some(obj) = value.as_object() else { return false };
+
+        let Some(current) = self.current_schema.clone() else {
+            self.current_schema = Some(self.infer_schema(value));
+            return true;
+        };
+
+        let mut fields: Vec<Field> = current.fields().iter().map(|f| f.as_ref().clone()).collect();
+        let mut index: HashMap<String, usize> = fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| (f.name().clone(), i))
+            .collect();
+        let mut changed = false;
+
+        for (key, val) in obj {
+            if val.is_null() {
+                continue;
+            }
+
+            let observed = self.infer_field_type(val);
+            match index.get(key) {
+                Some(&i) => {
+                    let widened = promote_type(fields[i].data_type(), &observed);
+                    if &widened != fields[i].data_type() {
+                        fields[i] = Field::new(fields[i].name(), widened, true);
+                        changed = true;
+                    }
+                }
+                None => {
+                    index.insert(key.clone(), fields.len());
+                    fields.push(Field::new(key, observed, true));
+                    changed = true;
+                }
+            }
+        }
+
+        if changed {
+            self.current_schema = Some(Arc::new(Schema::new(fields)));
+        }
+        changed
+    }
+
     pub fn merge_schemas(&mut self, value: &Value) -> SchemaRef {
         let inferred_schema = self.infer_schema(value);
         
@@ -231,12 +583,27 @@ impl DynamicSchemaManager {
     }
 }
 
+/// Type-promotion lattice used to merge conflicting per-field types observed
+/// across multiple messages: `Int64`/`Float64` widen to `Float64`, and any
+/// other mismatch (including struct/list shape conflicts) widens to `Utf8`
+fn promote_type(a: &DataType, b: &DataType) -> DataType {
+    if a == b {
+        return a.clone();
+    }
+    match (a, b) {
+        (DataType::Int64, DataType::Float64) | (DataType::Float64, DataType::Int64) => DataType::Float64,
+        _ => DataType::Utf8,
+    }
+}
+
 /// Kafka source that implements the Source trait for the pipeline
 pub struct KafkaPipelineSource {
     pub id: String,
     pub schema: SchemaRef,
     pub spec: rde_core::KafkaSourceSpec,
     pub schema_manager: DynamicSchemaManager,
+    rejected_messages: u64,
+    flight_tap: Option<crate::flight_server::FlightTap>,
 }
 
 impl KafkaPipelineSource {
@@ -247,13 +614,169 @@ impl KafkaPipelineSource {
             schema: Arc::new(Schema::empty()), // Will be dynamically inferred
             spec,
             schema_manager,
+            rejected_messages: 0,
+            flight_tap: None,
         }
     }
-    
+
     pub fn with_schema(mut self, schema: SchemaRef) -> Self {
         self.schema = schema;
         self
     }
+
+    /// Attaches an Arrow Flight tap so every batch produced by this source is
+    /// also broadcast to connected Flight clients (see [`crate::flight_server`])
+    pub fn with_flight_tap(mut self, tap: crate::flight_server::FlightTap) -> Self {
+        self.flight_tap = Some(tap);
+        self
+    }
+
+    /// Number of messages rejected by JSON Schema validation and routed to the
+    /// dead-letter topic so far
+    pub fn rejected_count(&self) -> u64 {
+        self.rejected_messages
+    }
+}
+
+/// Compiled JSON Schemas (Draft 7/2020-12), keyed by name, used to validate
+/// decoded Kafka payloads before they are converted to Arrow
+struct NamedSchemas {
+    compiled: HashMap<String, jsonschema::JSONSchema>,
+}
+
+impl NamedSchemas {
+    /// Loads and compiles every schema referenced by `config`, merging
+    /// `config.schema_dir` (one schema per `*.json` file, named after the
+    /// file stem) with `config.schemas`
+    fn compile(config: &rde_core::ValidationConfig) -> Result<Self> {
+        let mut raw = config.schemas.clone();
+
+        if let Some(dir) = &config.schema_dir {
+            for entry in std::fs::read_dir(dir).with_context(|| format!("read schema_dir {dir}"))? {
+                let path = entry?.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                    continue;
+                }
+                let name = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let content = std::fs::read_to_string(&path)
+                    .with_context(|| format!("read schema file {}", path.display()))?;
+                let value: Value = serde_json::from_str(&content)
+                    .with_context(|| format!("parse schema file {}", path.display()))?;
+                raw.insert(name, value);
+            }
+        }
+
+        let mut compiled = HashMap::new();
+        for (name, schema) in &raw {
+            let validator = jsonschema::JSONSchema::compile(schema)
+                .map_err(|e| anyhow::anyhow!("invalid JSON Schema '{name}': {e}"))?;
+            compiled.insert(name.clone(), validator);
+        }
+        Ok(Self { compiled })
+    }
+
+    /// Validates `value` against the schema selected by `config`, returning
+    /// the list of validation error strings if it fails.
+    ///
+    /// When no schema can be selected for `value` (key field absent and no
+    /// `default_schema` configured, or the selected name isn't compiled),
+    /// the message passes through unvalidated.
+    fn validate(&self, value: &Value, config: &rde_core::ValidationConfig) -> Option<Vec<String>> {
+        let schema_name = config
+            .schema_key_field
+            .as_ref()
+            .and_then(|field| value.get(field))
+            .and_then(|v| v.as_str())
+            .or(config.default_schema.as_deref())?;
+
+        let validator = self.compiled.get(schema_name)?;
+        match validator.validate(value) {
+            Ok(()) => None,
+            Err(errors) => Some(errors.map(|e| e.to_string()).collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod named_schemas_tests {
+    use super::NamedSchemas;
+    use rde_core::ValidationConfig;
+    use std::collections::HashMap;
+
+    fn config(schema_key_field: Option<&str>, default_schema: Option<&str>) -> ValidationConfig {
+        let mut schemas = HashMap::new();
+        schemas.insert(
+            "order".to_string(),
+            serde_json::json!({
+                "type": "object",
+                "required": ["id"],
+                "properties": { "id": { "type": "integer" } }
+            }),
+        );
+        ValidationConfig {
+            schema_dir: None,
+            schemas,
+            schema_key_field: schema_key_field.map(String::from),
+            default_schema: default_schema.map(String::from),
+            dead_letter_topic: "dead-letters".to_string(),
+        }
+    }
+
+    #[test]
+    fn valid_payload_against_default_schema_passes() {
+        let named = NamedSchemas::compile(&config(None, Some("order"))).unwrap();
+        let cfg = config(None, Some("order"));
+        assert!(named.validate(&serde_json::json!({"id": 1}), &cfg).is_none());
+    }
+
+    #[test]
+    fn invalid_payload_returns_error_strings() {
+        let named = NamedSchemas::compile(&config(None, Some("order"))).unwrap();
+        let cfg = config(None, Some("order"));
+        let errors = named.validate(&serde_json::json!({"id": "not-a-number"}), &cfg).unwrap();
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn schema_key_field_selects_schema_per_message() {
+        let named = NamedSchemas::compile(&config(Some("schema"), None)).unwrap();
+        let cfg = config(Some("schema"), None);
+        assert!(named.validate(&serde_json::json!({"schema": "order", "id": 1}), &cfg).is_none());
+    }
+
+    #[test]
+    fn no_selectable_schema_passes_through_unvalidated() {
+        let named = NamedSchemas::compile(&config(None, None)).unwrap();
+        let cfg = config(None, None);
+        assert!(named.validate(&serde_json::json!({"id": "not-a-number"}), &cfg).is_none());
+    }
+}
+
+/// Produces a rejected payload, alongside its validation errors, to the
+/// configured dead-letter topic
+async fn send_to_dead_letter(
+    producer: &rdkafka::producer::FutureProducer,
+    topic: &str,
+    payload: &Value,
+    errors: &[String],
+) {
+    let envelope = serde_json::json!({ "payload": payload, "errors": errors });
+    let body = match serde_json::to_vec(&envelope) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("failed to serialize dead-letter envelope: {}", e);
+            return;
+        }
+    };
+
+    let record: rdkafka::producer::FutureRecord<(), [u8]> =
+        rdkafka::producer::FutureRecord::to(topic).payload(&body);
+    if let Err((e, _)) = producer.send(record, std::time::Duration::from_secs(0)).await {
+        warn!("failed to produce dead-letter message to '{}': {}", topic, e);
+    }
 }
 
 #[async_trait]
@@ -273,102 +796,507 @@ impl Operator for KafkaPipelineSource {
 impl Source for KafkaPipelineSource {
     async fn run(&mut self, tx: BatchTx, cancel: CancellationToken) -> Result<()> {
         info!("Starting Kafka source for topic: {}", self.spec.topic);
-        let kafka_source = KafkaSource::new(&self.spec.brokers, &self.spec.group_id, &self.spec.topic);
-        let mut stream = kafka_source.stream().await?;
-        
-        while let Some(value) = stream.next().await {
-            if cancel.is_cancelled() {
-                info!("Kafka source cancelled");
-                break;
-            }
-            
-            info!("Processing Kafka message in source");
-            
-            // Update schema if needed
-            self.schema_manager.update_schema_if_needed(&value);
-            
-            // Parse JSON message into structured RecordBatch
-            if let Some(batch) = parse_json_to_batch_dynamic(&value, &self.schema_manager)? {
-                info!("Created RecordBatch with {} rows", batch.num_rows());
-                if tx.send(Message::Batch(batch)).await.is_err() {
-                    warn!("Failed to send batch to channel");
-                    break;
+        let (encoding, registry_url) = self
+            .spec
+            .schema
+            .as_ref()
+            .map(|s| (s.encoding, s.registry_url.clone()))
+            .unwrap_or_default();
+        let kafka_source = KafkaSource::new(&self.spec.brokers, &self.spec.group_id, &self.spec.topic)
+            .with_encoding(encoding, registry_url);
+        let (mut stream, consumer) = kafka_source.stream().await?;
+
+        let validator = match &self.spec.validation {
+            Some(cfg) => Some(NamedSchemas::compile(cfg)?),
+            None => None,
+        };
+        let dead_letter_producer = match &self.spec.validation {
+            Some(_) => Some(
+                ClientConfig::new()
+                    .set("bootstrap.servers", &self.spec.brokers)
+                    .create::<rdkafka::producer::FutureProducer>()
+                    .context("build dead-letter producer")?,
+            ),
+            None => None,
+        };
+
+        let mut accumulator: Option<BatchAccumulator> = None;
+        let mut flush_ticker = tokio::time::interval(Duration::from_millis(self.spec.flush_interval_ms));
+        flush_ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        // Bounded-out-of-orderness watermark generation; see
+        // `CsvSource::run_streaming` for the same pattern
+        let mut max_event_time_ms: Option<i64> = None;
+        let mut last_emitted_watermark: Option<i64> = None;
+        let mut watermark_ticker = tokio::time::interval(Duration::from_millis(self.spec.watermark_interval_ms));
+        watermark_ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        // Offsets of messages consumed while a schema sample is still being
+        // collected (no accumulator exists yet to own them); folded into
+        // whichever accumulator ends up holding the drained sample rows
+        let mut sampling_offsets: HashMap<i32, i64> = HashMap::new();
+        // Offsets safe to commit right now: accumulator flushes merge their
+        // own `pending_offsets` in here once their batch is accepted
+        // downstream, and messages that never produce a row (validation
+        // rejects) are committable immediately since they leave nothing
+        // pending in any accumulator
+        let mut committable_offsets: HashMap<i32, i64> = HashMap::new();
+        let mut commit_ticker = tokio::time::interval(Duration::from_millis(self.spec.commit_interval_ms));
+        commit_ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                maybe_value = stream.next() => {
+                    let Some((partition, offset, decoded)) = maybe_value else { break; };
+                    let next_offset = offset + 1;
+
+                    if cancel.is_cancelled() {
+                        info!("Kafka source cancelled");
+                        break;
+                    }
+
+                    info!("Processing Kafka message in source");
+
+                    let value = match decoded {
+                        DecodedMessage::Ok(value) => value,
+                        DecodedMessage::Err { raw, error } => {
+                            self.rejected_messages += 1;
+                            warn!(
+                                "dropping Kafka message that failed to decode (partition {}, offset {}): {}",
+                                partition, offset, error
+                            );
+                            if let (Some(producer), Some(cfg)) = (&dead_letter_producer, &self.spec.validation) {
+                                let payload = Value::String(String::from_utf8_lossy(&raw).to_string());
+                                send_to_dead_letter(producer, &cfg.dead_letter_topic, &payload, &[error]).await;
+                            }
+                            committable_offsets
+                                .entry(partition)
+                                .and_modify(|o| *o = (*o).max(next_offset))
+                                .or_insert(next_offset);
+                            continue;
+                        }
+                    };
+
+                    if let (Some(schemas), Some(cfg)) = (&validator, &self.spec.validation) {
+                        if let Some(errors) = schemas.validate(&value, cfg) {
+                            self.rejected_messages += 1;
+                            warn!("Kafka message failed validation: {:?}", errors);
+                            if let Some(producer) = &dead_letter_producer {
+                                send_to_dead_letter(producer, &cfg.dead_letter_topic, &value, &errors).await;
+                            }
+                            committable_offsets
+                                .entry(partition)
+                                .and_modify(|o| *o = (*o).max(next_offset))
+                                .or_insert(next_offset);
+                            continue;
+                        }
+                    }
+
+                    let (schema_changed, completed_sampling) = match self.schema_manager.observe(&value) {
+                        SchemaObservation::Sampling => {
+                            sampling_offsets
+                                .entry(partition)
+                                .and_modify(|o| *o = (*o).max(next_offset))
+                                .or_insert(next_offset);
+                            continue;
+                        }
+                        SchemaObservation::Ready { changed, completed_sampling } => (changed, completed_sampling),
+                    };
+
+                    if schema_changed || accumulator.is_none() {
+                        if let Some(mut acc) = accumulator.take() {
+                            flush_accumulator(&mut acc, &tx, self.flight_tap.as_ref(), &mut committable_offsets).await?;
+                        }
+                        let schema = self.schema_manager.get_current_schema()
+                            .ok_or_else(|| anyhow::anyhow!("No schema available"))?;
+                        let mut new_acc = BatchAccumulator::new(schema, self.spec.max_batch_rows, self.spec.max_batch_bytes);
+                        merge_offsets(&mut new_acc.pending_offsets, &mut sampling_offsets);
+                        accumulator = Some(new_acc);
+                        flush_ticker.reset();
+                    }
+
+                    let acc = accumulator.as_mut().expect("accumulator initialized above");
+                    acc.record_offset(partition, next_offset);
+                    if completed_sampling {
+                        for row in self.schema_manager.drain_sampled_rows() {
+                            if let Some(column) = &self.spec.event_time_column {
+                                if let Some(ts) = event_time_ms(&row, column) {
+                                    max_event_time_ms = Some(max_event_time_ms.map_or(ts, |cur| cur.max(ts)));
+                                }
+                            }
+                            acc.append_row(&row);
+                        }
+                    } else {
+                        if let Some(column) = &self.spec.event_time_column {
+                            if let Some(ts) = event_time_ms(&value, column) {
+                                max_event_time_ms = Some(max_event_time_ms.map_or(ts, |cur| cur.max(ts)));
+                            }
+                        }
+                        acc.append_row(&value);
+                    }
+                    if acc.should_flush() {
+                        flush_accumulator(acc, &tx, self.flight_tap.as_ref(), &mut committable_offsets).await?;
+                        flush_ticker.reset();
+                    }
+                }
+                _ = flush_ticker.tick() => {
+                    if let Some(acc) = accumulator.as_mut() {
+                        if !acc.is_empty() {
+                            info!("Flushing Kafka batch on flush_interval_ms");
+                            flush_accumulator(acc, &tx, self.flight_tap.as_ref(), &mut committable_offsets).await?;
+                        }
+                    }
+                }
+                _ = commit_ticker.tick() => {
+                    if !committable_offsets.is_empty() {
+                        commit_offsets(&consumer, &self.spec.topic, &committable_offsets)?;
+                        committable_offsets.clear();
+                    }
+                }
+                _ = watermark_ticker.tick(), if self.spec.event_time_column.is_some() => {
+                    if let Some(max_ts) = max_event_time_ms {
+                        let candidate = max_ts - self.spec.allowed_lateness_ms;
+                        let ts = last_emitted_watermark.map_or(candidate, |prev| prev.max(candidate));
+                        if last_emitted_watermark != Some(ts) {
+                            let _ = tx.send(Message::Watermark(ts)).await;
+                            last_emitted_watermark = Some(ts);
+                        }
+                    }
                 }
-                info!("Successfully sent batch to channel");
             }
         }
-        
+
+        if let Some(acc) = accumulator.as_mut() {
+            flush_accumulator(acc, &tx, self.flight_tap.as_ref(), &mut committable_offsets).await?;
+        }
+
+        if !committable_offsets.is_empty() {
+            info!("Committing Kafka offsets on EOS/cancellation");
+            commit_offsets(&consumer, &self.spec.topic, &committable_offsets)?;
+            committable_offsets.clear();
+        }
+
         info!("Kafka source finished, sending EOS");
         let _ = tx.send(Message::Eos).await;
         Ok(())
     }
 }
 
-// helper function to parse payload as JSON
-// TODO: add transformation logic for the messages
-fn parse_message(m: &BorrowedMessage) -> Result<Value, serde_json::Error> {
-    if let Some(payload) = m.payload() {
-        serde_json::from_slice(payload)
-    } else {
-        Ok(Value::Null)
+/// Reads `column` out of a decoded JSON row as an epoch-millisecond event
+/// time, for watermark generation; see `source_csv::max_event_time_in_column`
+/// for the equivalent extraction once a row is already in a `RecordBatch`.
+fn event_time_ms(row: &Value, column: &str) -> Option<i64> {
+    row.get(column)?.as_i64()
+}
+
+/// Moves every partition's offset from `inflight` into `committable`,
+/// keyed to the highest offset seen so it is safe to call repeatedly as
+/// more batches are accepted downstream
+fn merge_offsets(committable: &mut HashMap<i32, i64>, inflight: &mut HashMap<i32, i64>) {
+    for (partition, offset) in inflight.drain() {
+        committable
+            .entry(partition)
+            .and_modify(|o| *o = (*o).max(offset))
+            .or_insert(offset);
     }
 }
 
-// Dynamic JSON to RecordBatch conversion
-fn parse_json_to_batch_dynamic(value: &Value, schema_manager: &DynamicSchemaManager) -> Result<Option<RecordBatch>> {
-    if value.is_null() {
-        return Ok(None);
+/// Flushes `acc`'s buffered rows as a single `Message::Batch`, if any are
+/// buffered, then moves the offsets `acc` recorded for those rows into
+/// `committable` — only once the batch has actually been accepted by the
+/// downstream channel are its rows' offsets safe to commit.
+async fn flush_accumulator(
+    acc: &mut BatchAccumulator,
+    tx: &BatchTx,
+    tap: Option<&crate::flight_server::FlightTap>,
+    committable: &mut HashMap<i32, i64>,
+) -> Result<()> {
+    if let Some(batch) = acc.finish()? {
+        info!("Created RecordBatch with {} rows", batch.num_rows());
+        let message = Message::Batch(batch);
+        if let Some(tap) = tap {
+            tap.observe(&message);
+        }
+        if tx.send(message).await.is_ok() {
+            merge_offsets(committable, &mut acc.pending_offsets);
+        } else {
+            warn!("Failed to send batch to channel, not committing its offsets");
+        }
     }
-    
-    let schema = schema_manager.get_current_schema()
-        .ok_or_else(|| anyhow::anyhow!("No schema available"))?;
-    
-    let mut arrays: Vec<ArrayRef> = Vec::new();
-    
-    if let Value::Object(obj) = value {
-        for field in schema.fields() {
-            let field_name = field.name();
-            let field_value = obj.get(field_name).unwrap_or(&Value::Null);
-            
-            let array: ArrayRef = match field.data_type() {
-                DataType::Boolean => {
-                    let bool_val = field_value.as_bool().unwrap_or(false);
-                    Arc::new(BooleanArray::from(vec![bool_val]))
-                }
-                DataType::Int64 => {
-                    let int_val = field_value.as_i64().unwrap_or(0);
-                    Arc::new(Int64Array::from(vec![int_val]))
-                }
-                DataType::Float64 => {
-                    let float_val = field_value.as_f64().unwrap_or(0.0);
-                    Arc::new(Float64Array::from(vec![float_val]))
-                }
-                DataType::Utf8 => {
-                    let str_val = field_value.as_str().unwrap_or("").to_string();
-                    Arc::new(StringArray::from(vec![str_val]))
-                }
-                DataType::List(_) => {
-                    // For arrays, convert to JSON string for now
-                    let json_str = serde_json::to_string(field_value).unwrap_or_default();
-                    Arc::new(StringArray::from(vec![json_str]))
-                }
-                _ => {
-                    // Fallback to string for unknown types
-                    let json_str = serde_json::to_string(field_value).unwrap_or_default();
-                    Arc::new(StringArray::from(vec![json_str]))
-                }
-            };
-            
-            arrays.push(array);
+    Ok(())
+}
+
+/// Buffers decoded Kafka rows and flushes a combined `RecordBatch` once a
+/// row-count or byte-size threshold is reached, turning one-row-per-message
+/// ingestion into vectorized batches. Rows are decoded in one pass per flush
+/// by [`json_rows_to_batch`], rather than appended scalar-by-scalar, so
+/// nested `Struct`/`List` fields come out as real nested arrays.
+///
+/// `pending_offsets` tracks, per partition, the offsets of only the rows
+/// this specific accumulator holds — not whatever offset happened to be
+/// in flight when a row was read — so a row's offset only becomes
+/// committable once the accumulator that actually contains it is flushed.
+struct BatchAccumulator {
+    schema: SchemaRef,
+    rows: Vec<Value>,
+    bytes: usize,
+    max_rows: usize,
+    max_bytes: usize,
+    pending_offsets: HashMap<i32, i64>,
+}
+
+impl BatchAccumulator {
+    fn new(schema: SchemaRef, max_rows: usize, max_bytes: usize) -> Self {
+        Self {
+            schema,
+            rows: Vec::new(),
+            bytes: 0,
+            max_rows,
+            max_bytes,
+            pending_offsets: HashMap::new(),
         }
     }
-    
-    let batch = RecordBatch::try_new(schema, arrays)?;
-    Ok(Some(batch))
+
+    fn append_row(&mut self, value: &Value) {
+        self.bytes += estimate_value_bytes(value);
+        self.rows.push(value.clone());
+    }
+
+    /// Records that the row(s) up to `offset` (exclusive) for `partition`
+    /// are now held by this accumulator, so they commit only once it flushes
+    fn record_offset(&mut self, partition: i32, offset: i64) {
+        self.pending_offsets
+            .entry(partition)
+            .and_modify(|o| *o = (*o).max(offset))
+            .or_insert(offset);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    fn should_flush(&self) -> bool {
+        !self.rows.is_empty() && (self.rows.len() >= self.max_rows || self.bytes >= self.max_bytes)
+    }
+
+    fn finish(&mut self) -> Result<Option<RecordBatch>> {
+        if self.rows.is_empty() {
+            return Ok(None);
+        }
+        let rows = std::mem::take(&mut self.rows);
+        self.bytes = 0;
+        Ok(Some(json_rows_to_batch(&self.schema, &rows)?))
+    }
+}
+
+#[cfg(test)]
+mod batch_accumulator_tests {
+    use super::BatchAccumulator;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    fn schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, true)]))
+    }
+
+    #[test]
+    fn flushes_on_row_count_threshold() {
+        let mut acc = BatchAccumulator::new(schema(), 2, usize::MAX);
+        acc.append_row(&serde_json::json!({"id": 1}));
+        assert!(!acc.should_flush());
+        acc.append_row(&serde_json::json!({"id": 2}));
+        assert!(acc.should_flush());
+    }
+
+    #[test]
+    fn flushes_on_byte_size_threshold() {
+        let mut acc = BatchAccumulator::new(schema(), usize::MAX, 1);
+        assert!(!acc.should_flush());
+        acc.append_row(&serde_json::json!({"id": 1}));
+        assert!(acc.should_flush());
+    }
+
+    #[test]
+    fn finish_clears_rows_and_bytes_but_not_pending_offsets() {
+        let mut acc = BatchAccumulator::new(schema(), 10, usize::MAX);
+        acc.append_row(&serde_json::json!({"id": 1}));
+        acc.record_offset(0, 5);
+
+        let batch = acc.finish().unwrap().unwrap();
+        assert_eq!(batch.num_rows(), 1);
+        assert!(acc.is_empty());
+        assert_eq!(acc.bytes, 0);
+        assert_eq!(acc.pending_offsets.get(&0), Some(&5));
+    }
+}
+
+/// Decodes buffered JSON rows into a single `RecordBatch` against `schema`,
+/// via arrow-json's tape-based decoder (the same mechanism [`crate::source_http`]
+/// uses for its buffered events). Struct and List fields recurse into real
+/// `StructArray`/`ListArray`s, numbers are coerced to the declared column
+/// type, absent fields are filled with null, and `Timestamp`/`Decimal`
+/// columns are supported natively.
+fn json_rows_to_batch(schema: &SchemaRef, rows: &[Value]) -> Result<RecordBatch> {
+    let mut ndjson = String::new();
+    for row in rows {
+        ndjson.push_str(&serde_json::to_string(row)?);
+        ndjson.push('\n');
+    }
+
+    let mut reader = datafusion::arrow::json::ReaderBuilder::new(schema.clone())
+        .with_batch_size(rows.len().max(1))
+        .build(std::io::Cursor::new(ndjson.into_bytes()))?;
+
+    reader
+        .next()
+        .context("no batch produced from buffered Kafka rows")?
+        .context("failed to decode buffered Kafka rows")
+}
+
+#[cfg(test)]
+mod json_rows_to_batch_tests {
+    use super::json_rows_to_batch;
+    use datafusion::arrow::array::{Int64Array, ListArray, StringArray, StructArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Fields, Schema};
+    use std::sync::Arc;
+
+    #[test]
+    fn nested_struct_and_list_fields_decode_to_real_arrays() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new(
+                "info",
+                DataType::Struct(Fields::from(vec![Field::new("score", DataType::Int64, true)])),
+                true,
+            ),
+            Field::new("tags", DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))), true),
+        ]));
+
+        let rows = vec![serde_json::json!({
+            "id": 1,
+            "info": {"score": 42},
+            "tags": ["a", "b"],
+        })];
+
+        let batch = json_rows_to_batch(&schema, &rows).unwrap();
+        assert_eq!(batch.num_rows(), 1);
+
+        let id = batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(id.value(0), 1);
+
+        let info = batch.column(1).as_any().downcast_ref::<StructArray>().unwrap();
+        let score = info.column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(score.value(0), 42);
+
+        let tags = batch.column(2).as_any().downcast_ref::<ListArray>().unwrap();
+        let first_list = tags.value(0);
+        let tags_strings = first_list.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(tags_strings.iter().flatten().collect::<Vec<_>>(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn absent_fields_are_filled_with_null() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("missing", DataType::Int64, true),
+        ]));
+        let rows = vec![serde_json::json!({"id": 7})];
+        let batch = json_rows_to_batch(&schema, &rows).unwrap();
+        assert!(batch.column(1).is_null(0));
+    }
+}
+
+#[cfg(test)]
+mod dynamic_schema_manager_tests {
+    use super::{promote_type, DynamicSchemaManager, SchemaObservation};
+    use datafusion::arrow::datatypes::DataType;
+    use rde_core::SchemaConfig;
+
+    #[test]
+    fn promote_type_widens_int_and_float_to_float() {
+        assert_eq!(promote_type(&DataType::Int64, &DataType::Float64), DataType::Float64);
+        assert_eq!(promote_type(&DataType::Float64, &DataType::Int64), DataType::Float64);
+    }
+
+    #[test]
+    fn promote_type_widens_other_conflicts_to_utf8() {
+        assert_eq!(promote_type(&DataType::Boolean, &DataType::Int64), DataType::Utf8);
+        assert_eq!(promote_type(&DataType::Utf8, &DataType::Boolean), DataType::Utf8);
+    }
+
+    #[test]
+    fn promote_type_is_a_no_op_on_matching_types() {
+        assert_eq!(promote_type(&DataType::Int64, &DataType::Int64), DataType::Int64);
+    }
+
+    fn sampling_manager(sample_size: usize) -> DynamicSchemaManager {
+        DynamicSchemaManager::new().with_config(&Some(SchemaConfig {
+            auto_infer: true,
+            sample_size,
+            fields: vec![],
+        }))
+    }
+
+    #[test]
+    fn sampling_buffers_until_sample_size_then_unifies_and_widens() {
+        let mut mgr = sampling_manager(3);
+
+        assert!(matches!(mgr.observe(&serde_json::json!({"n": 1, "x": "a"})), SchemaObservation::Sampling));
+        assert!(matches!(mgr.observe(&serde_json::json!({"n": 2.5})), SchemaObservation::Sampling));
+
+        match mgr.observe(&serde_json::json!({"n": 3, "x": null})) {
+            SchemaObservation::Ready { changed, completed_sampling } => {
+                assert!(changed);
+                assert!(completed_sampling);
+            }
+            SchemaObservation::Sampling => panic!("expected sampling to complete on the third message"),
+        }
+
+        let schema = mgr.get_current_schema().unwrap();
+        // n was observed as both Int64 and Float64 across the sample, so it widens to Float64
+        assert_eq!(schema.field_with_name("n").unwrap().data_type(), &DataType::Float64);
+        // x was observed as a string and then null; the null shouldn't narrow it back
+        assert_eq!(schema.field_with_name("x").unwrap().data_type(), &DataType::Utf8);
+
+        let drained = mgr.drain_sampled_rows();
+        assert_eq!(drained.len(), 3);
+    }
+
+    #[test]
+    fn post_sampling_evolution_is_additive_not_replacing() {
+        let mut mgr = sampling_manager(1);
+        mgr.observe(&serde_json::json!({"a": 1}));
+
+        match mgr.observe(&serde_json::json!({"a": 2, "b": "new"})) {
+            SchemaObservation::Ready { changed, completed_sampling } => {
+                assert!(changed);
+                assert!(!completed_sampling);
+            }
+            SchemaObservation::Sampling => panic!("sampling already resolved, should not sample again"),
+        }
+
+        let schema = mgr.get_current_schema().unwrap();
+        // the original field is still present alongside the newly observed one
+        assert!(schema.field_with_name("a").is_some());
+        assert!(schema.field_with_name("b").is_some());
+    }
+}
+
+/// Estimates the serialized byte size of a buffered row, used to drive the
+/// byte-size flush threshold
+fn estimate_value_bytes(value: &Value) -> usize {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 8,
+        Value::String(s) => s.len(),
+        Value::Array(items) => items.iter().map(estimate_value_bytes).sum(),
+        Value::Object(obj) => obj.values().map(estimate_value_bytes).sum(),
+    }
 }
 
 // Legacy function - keeping for backward compatibility but marked as deprecated
-#[deprecated(note = "Use parse_json_to_batch_dynamic instead for dynamic schema support")]
+#[deprecated(note = "Use BatchAccumulator instead for vectorized batches")]
 fn parse_json_to_batch(value: &Value) -> Result<Option<RecordBatch>> {
     if value.is_null() {
         return Ok(None);
@@ -398,3 +1326,24 @@ fn parse_json_to_batch(value: &Value) -> Result<Option<RecordBatch>> {
     
     Ok(Some(batch))
 }
+
+#[cfg(test)]
+mod event_time_ms_tests {
+    use super::event_time_ms;
+    use serde_json::json;
+
+    #[test]
+    fn reads_an_integer_field_as_epoch_millis() {
+        let row = json!({"ts": 1_700_000_000_000i64, "id": 1});
+        assert_eq!(event_time_ms(&row, "ts"), Some(1_700_000_000_000));
+    }
+
+    #[test]
+    fn returns_none_for_a_missing_or_non_numeric_column() {
+        let row = json!({"id": 1});
+        assert_eq!(event_time_ms(&row, "ts"), None);
+
+        let row = json!({"ts": "not-a-number"});
+        assert_eq!(event_time_ms(&row, "ts"), None);
+    }
+}
@@ -0,0 +1,107 @@
+#![allow(unused)]
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::Message as KafkaMessage;
+use rde_core::{BatchTx, Message, Operator, ReplaySourceSpec, Source};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Deterministically replays a [`Message`] stream previously captured by
+/// [`crate::capture::spawn_tee`] from a Kafka topic
+///
+/// Reads `spec.topic` from the beginning in publish order and decodes each
+/// record back into the `Message` it was captured from, forwarding it
+/// unchanged. A captured `Message::Eos` ends the replay; so does running
+/// out of captured records to read, in case the original stream never
+/// reached one (e.g. the source run was cancelled mid-capture).
+pub struct ReplaySource {
+    id: String,
+    schema: SchemaRef,
+    spec: ReplaySourceSpec,
+}
+
+impl ReplaySource {
+    pub fn new(spec: ReplaySourceSpec) -> Self {
+        Self {
+            id: spec.id.clone(),
+            schema: Arc::new(datafusion::arrow::datatypes::Schema::empty()),
+            spec,
+        }
+    }
+
+    pub fn with_schema(mut self, schema: SchemaRef) -> Self {
+        self.schema = schema;
+        self
+    }
+}
+
+#[async_trait]
+impl Operator for ReplaySource {
+    fn name(&self) -> &str {
+        &self.id
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[async_trait]
+impl Source for ReplaySource {
+    async fn run(&mut self, tx: BatchTx, cancel: CancellationToken) -> Result<()> {
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &self.spec.brokers)
+            .set("group.id", format!("rde-replay-{}", self.spec.id))
+            .set("auto.offset.reset", "earliest")
+            .set("enable.auto.commit", "false")
+            .create()
+            .context("build replay Kafka consumer")?;
+
+        consumer.subscribe(&[self.spec.topic.as_str()]).context("subscribe to replay topic")?;
+        info!("ReplaySource: replaying captured messages from '{}'", self.spec.topic);
+
+        loop {
+            let received = tokio::select! {
+                _ = cancel.cancelled() => break,
+                received = tokio::time::timeout(std::time::Duration::from_secs(10), consumer.recv()) => received,
+            };
+
+            let message = match received {
+                Ok(Ok(message)) => message,
+                Ok(Err(err)) => {
+                    warn!(error=?err, "ReplaySource: error reading '{}'", self.spec.topic);
+                    break;
+                }
+                Err(_) => {
+                    info!("ReplaySource: no more captured messages on '{}', ending replay", self.spec.topic);
+                    break;
+                }
+            };
+
+            let Some(payload) = message.payload() else { continue };
+            let decoded = match crate::capture::decode(payload) {
+                Ok(msg) => msg,
+                Err(err) => {
+                    warn!(error=?err, "ReplaySource: dropping record that failed to decode");
+                    continue;
+                }
+            };
+
+            let is_eos = matches!(decoded, Message::Eos);
+            if tx.send(decoded).await.is_err() {
+                break;
+            }
+            if is_eos {
+                return Ok(());
+            }
+        }
+
+        let _ = tx.send(Message::Eos).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+#![allow(unused)]
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use datafusion::arrow::array::RecordBatch;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::json::reader::infer_json_schema_from_iterator;
+use datafusion::arrow::json::ReaderBuilder;
+use futures::{SinkExt, StreamExt};
+use rde_core::{BatchTx, Message, Operator, Source, WebSocketSourceSpec};
+use serde_json::Value;
+use std::io::Cursor;
+use std::sync::Arc;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Streaming WebSocket ingestion source
+///
+/// Holds an open connection to `spec.url`, re-sending `spec.subscribe` on
+/// every (re)connect, and turns each text frame's `json_pointer`-extracted
+/// payload into rows batched against the pipeline schema. Disconnects and
+/// parse errors are not fatal: the source reconnects with exponential
+/// backoff (capped at `spec.backoff_max_ms`), logging how long the feed was
+/// down, rather than ending the pipeline.
+pub struct WebSocketSource {
+    id: String,
+    schema: SchemaRef,
+    spec: WebSocketSourceSpec,
+}
+
+impl WebSocketSource {
+    pub fn new(spec: WebSocketSourceSpec) -> Self {
+        Self {
+            id: spec.id.clone(),
+            schema: Arc::new(datafusion::arrow::datatypes::Schema::empty()),
+            spec,
+        }
+    }
+
+    pub fn with_schema(mut self, schema: SchemaRef) -> Self {
+        self.schema = schema;
+        self
+    }
+}
+
+#[async_trait]
+impl Operator for WebSocketSource {
+    fn name(&self) -> &str {
+        &self.id
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[async_trait]
+impl Source for WebSocketSource {
+    async fn run(&mut self, tx: BatchTx, cancel: CancellationToken) -> Result<()> {
+        let mut backoff = std::time::Duration::from_millis(self.spec.backoff_initial_ms);
+        let backoff_max = std::time::Duration::from_millis(self.spec.backoff_max_ms);
+        let mut buffer: Vec<Value> = Vec::new();
+        let mut down_since: Option<std::time::Instant> = None;
+
+        loop {
+            if cancel.is_cancelled() {
+                break;
+            }
+
+            match self.connect_and_subscribe().await {
+                Ok(mut socket) => {
+                    if let Some(since) = down_since.take() {
+                        info!("WebSocketSource: reconnected to {} after {:?}", self.spec.url, since.elapsed());
+                    }
+                    backoff = std::time::Duration::from_millis(self.spec.backoff_initial_ms);
+
+                    let result = self.read_frames(&mut socket, &tx, &cancel, &mut buffer).await;
+                    let _ = socket.close(None).await;
+
+                    match result {
+                        Ok(Cancelled::Yes) => break,
+                        Ok(Cancelled::No) => {
+                            warn!("WebSocketSource: connection to {} closed, reconnecting", self.spec.url);
+                        }
+                        Err(err) => {
+                            warn!(error=?err, "WebSocketSource: connection to {} failed, reconnecting", self.spec.url);
+                        }
+                    }
+                }
+                Err(err) => {
+                    warn!(error=?err, "WebSocketSource: failed to connect to {}", self.spec.url);
+                }
+            }
+
+            down_since.get_or_insert_with(std::time::Instant::now);
+
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(backoff) => {}
+            }
+            backoff = (backoff * 2).min(backoff_max);
+        }
+
+        if !buffer.is_empty() {
+            if let Err(err) = infer_schema_if_empty(&mut self.schema, &buffer) {
+                warn!(error=?err, "WebSocketSource: failed to infer schema for final partial batch");
+            } else if let Err(err) = flush(&self.schema, &tx, &mut buffer).await {
+                warn!(error=?err, "WebSocketSource: failed to flush final partial batch");
+            }
+        }
+
+        info!("WebSocketSource finished, sending EOS");
+        let _ = tx.send(Message::Eos).await;
+        Ok(())
+    }
+}
+
+type WsStream = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+enum Cancelled {
+    Yes,
+    No,
+}
+
+impl WebSocketSource {
+    async fn connect_and_subscribe(&self) -> Result<WsStream> {
+        let (mut socket, _) = tokio_tungstenite::connect_async(&self.spec.url)
+            .await
+            .with_context(|| format!("connecting to {}", self.spec.url))?;
+
+        if let Some(subscribe) = &self.spec.subscribe {
+            let frame = serde_json::to_string(subscribe).context("serializing subscribe message")?;
+            socket.send(WsMessage::Text(frame)).await.context("sending subscribe message")?;
+        }
+
+        Ok(socket)
+    }
+
+    /// Reads frames off `socket` until it closes, errors, or `cancel` fires,
+    /// parsing each text frame's `json_pointer`-extracted payload into
+    /// `buffer` and flushing whenever it reaches `spec.batch_rows`
+    async fn read_frames(
+        &mut self,
+        socket: &mut WsStream,
+        tx: &BatchTx,
+        cancel: &CancellationToken,
+        buffer: &mut Vec<Value>,
+    ) -> Result<Cancelled> {
+        loop {
+            let frame = tokio::select! {
+                _ = cancel.cancelled() => return Ok(Cancelled::Yes),
+                frame = socket.next() => frame,
+            };
+
+            let Some(frame) = frame else {
+                return Ok(Cancelled::No);
+            };
+
+            let frame = frame.context("reading WebSocket frame")?;
+            let text = match frame {
+                WsMessage::Text(text) => text,
+                WsMessage::Close(_) => return Ok(Cancelled::No),
+                _ => continue,
+            };
+
+            let payload = match self.extract_payload(&text) {
+                Ok(payload) => payload,
+                Err(err) => {
+                    warn!(error=?err, "WebSocketSource: dropping frame that failed to parse");
+                    continue;
+                }
+            };
+
+            buffer.push(payload);
+            if buffer.len() >= self.spec.batch_rows {
+                infer_schema_if_empty(&mut self.schema, buffer)?;
+                flush(&self.schema, tx, buffer).await?;
+            }
+        }
+    }
+
+    /// Parses a text frame as JSON and extracts the row payload at `json_pointer`
+    fn extract_payload(&self, text: &str) -> Result<Value> {
+        let value: Value = serde_json::from_str(text).context("invalid JSON frame")?;
+        if self.spec.json_pointer.is_empty() {
+            return Ok(value);
+        }
+        value
+            .pointer(&self.spec.json_pointer)
+            .cloned()
+            .with_context(|| format!("json_pointer '{}' not found in frame", self.spec.json_pointer))
+    }
+}
+
+/// Infers `schema` from `values` the first time a non-empty batch is seen,
+/// since WebSocket frames arrive schema-less and `schema` starts out empty
+fn infer_schema_if_empty(schema: &mut SchemaRef, values: &[Value]) -> Result<()> {
+    if !schema.fields().is_empty() || values.is_empty() {
+        return Ok(());
+    }
+    let inferred = infer_json_schema_from_iterator(values.iter().map(|v| Ok::<_, ArrowError>(v.clone())))?;
+    info!("WebSocketSource: inferred schema from first batch: {:?}", inferred.fields());
+    *schema = Arc::new(inferred);
+    Ok(())
+}
+
+/// Drains `buffer` and forwards a single `RecordBatch`, if non-empty
+async fn flush(schema: &SchemaRef, tx: &BatchTx, buffer: &mut Vec<Value>) -> Result<()> {
+    if buffer.is_empty() {
+        return Ok(());
+    }
+    let values = std::mem::take(buffer);
+    let batch = json_values_to_batch(schema, &values)?;
+    info!("WebSocketSource: flushing batch with {} rows", batch.num_rows());
+    if tx.send(Message::Batch(batch)).await.is_err() {
+        warn!("WebSocketSource: failed to send batch to channel");
+    }
+    Ok(())
+}
+
+/// Parses buffered JSON values into a single `RecordBatch` matching `schema`
+fn json_values_to_batch(schema: &SchemaRef, values: &[Value]) -> Result<RecordBatch> {
+    let mut ndjson = String::new();
+    for value in values {
+        ndjson.push_str(&serde_json::to_string(value)?);
+        ndjson.push('\n');
+    }
+
+    let mut reader = ReaderBuilder::new(schema.clone())
+        .with_batch_size(values.len().max(1))
+        .build(Cursor::new(ndjson.into_bytes()))?;
+
+    reader
+        .next()
+        .context("no batch produced from buffered events")?
+        .context("failed to decode buffered events")
+}
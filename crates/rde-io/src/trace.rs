@@ -0,0 +1,252 @@
+#![allow(unused)]
+
+//! Distributed-tracing instrumentation for pipeline edges and operators.
+//!
+//! [`spawn_metrics_tee`] sits on every pipeline channel, counting the
+//! batches and rows flowing through it and stamping each batch's schema
+//! metadata with a trace id for the current pipeline run (propagating it
+//! unchanged once an upstream edge has already stamped it). The counters it
+//! accumulates, together with an operator's run duration, are combined into
+//! an [`OperatorSpanReport`] once that operator's `run` returns.
+//!
+//! [`TraceReporter`] optionally publishes those reports to Kafka in the
+//! background, batching and retrying like a lightweight Kafka-based APM
+//! reporter; reports always surface through the `tracing` subscriber
+//! regardless of whether a reporter is configured.
+
+use anyhow::Result;
+use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::record_batch::RecordBatch;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rde_core::{BatchRx, BatchTx, Message, TracingSpec};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Schema metadata key a batch's trace id is stamped under
+pub const TRACE_ID_KEY: &str = "rde.trace_id";
+
+/// Batch and row counters for one pipeline edge, updated by
+/// [`spawn_metrics_tee`] as messages flow through it
+#[derive(Default)]
+pub struct ChannelCounters {
+    pub batches: AtomicU64,
+    pub rows: AtomicU64,
+    pub max_backpressure: AtomicUsize,
+}
+
+impl ChannelCounters {
+    fn record(&self, batch: &RecordBatch, in_flight: usize) {
+        self.batches.fetch_add(1, Ordering::Relaxed);
+        self.rows.fetch_add(batch.num_rows() as u64, Ordering::Relaxed);
+        self.max_backpressure.fetch_max(in_flight, Ordering::Relaxed);
+    }
+}
+
+/// Wraps a channel so every message passing through it is counted (batches
+/// stamped with `trace_id`, unless an upstream edge already stamped them)
+/// and forwarded unchanged. Capacity is preserved from `capacity`.
+pub fn spawn_metrics_tee(
+    trace_id: Arc<str>,
+    capacity: usize,
+    cancel: CancellationToken,
+) -> (BatchTx, BatchRx, Arc<ChannelCounters>) {
+    let counters = Arc::new(ChannelCounters::default());
+    let (tap_tx, mut tap_rx): (BatchTx, BatchRx) = tokio::sync::mpsc::channel(capacity);
+    let (out_tx, out_rx): (BatchTx, BatchRx) = tokio::sync::mpsc::channel(capacity);
+
+    let task_counters = counters.clone();
+    tokio::spawn(async move {
+        loop {
+            let msg = tokio::select! {
+                _ = cancel.cancelled() => break,
+                msg = tap_rx.recv() => msg,
+            };
+            let Some(msg) = msg else { break };
+
+            let in_flight = capacity.saturating_sub(out_tx.capacity());
+            let msg = match msg {
+                Message::Batch(batch) => {
+                    task_counters.record(&batch, in_flight);
+                    Message::Batch(stamp_trace_id(batch, &trace_id))
+                }
+                other => other,
+            };
+
+            if out_tx.send(msg).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    (tap_tx, out_rx, counters)
+}
+
+/// Stamps `trace_id` into a batch's schema metadata, unless it is already
+/// present (an upstream edge already stamped it)
+fn stamp_trace_id(batch: RecordBatch, trace_id: &str) -> RecordBatch {
+    if batch.schema().metadata().contains_key(TRACE_ID_KEY) {
+        return batch;
+    }
+    let mut metadata = batch.schema().metadata().clone();
+    metadata.insert(TRACE_ID_KEY.to_string(), trace_id.to_string());
+    let schema = Arc::new(Schema::new_with_metadata(batch.schema().fields().clone(), metadata));
+    RecordBatch::try_new(schema, batch.columns().to_vec()).unwrap_or(batch)
+}
+
+/// One completed `Source`/`Transform`/`Sink::run` invocation
+#[derive(Debug, Clone, Serialize)]
+pub struct OperatorSpanReport {
+    pub trace_id: Arc<str>,
+    pub operator: String,
+    pub kind: &'static str,
+    pub duration_ms: u64,
+    pub input_batches: u64,
+    pub input_rows: u64,
+    pub output_batches: u64,
+    pub output_rows: u64,
+    pub max_backpressure: usize,
+}
+
+/// Logs `report` through the `tracing` subscriber; always called, whether or
+/// not a Kafka reporter is configured
+pub fn log_span(report: &OperatorSpanReport) {
+    info!(
+        trace_id = %report.trace_id,
+        operator = %report.operator,
+        kind = report.kind,
+        duration_ms = report.duration_ms,
+        input_batches = report.input_batches,
+        input_rows = report.input_rows,
+        output_batches = report.output_batches,
+        output_rows = report.output_rows,
+        max_backpressure = report.max_backpressure,
+        "operator span completed"
+    );
+}
+
+/// Background publisher for completed [`OperatorSpanReport`]s. Reports are
+/// batched and published to a Kafka topic, retrying a failed publish a few
+/// times before logging and dropping it; publishing never blocks the
+/// operator whose span just ended.
+pub struct TraceReporter {
+    report_tx: tokio::sync::mpsc::UnboundedSender<OperatorSpanReport>,
+}
+
+impl TraceReporter {
+    pub fn spawn(spec: &TracingSpec, cancel: CancellationToken) -> Result<Self> {
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", &spec.brokers)
+            .create()?;
+        let topic = spec.kafka_topic.clone();
+        let (report_tx, mut report_rx) = tokio::sync::mpsc::unbounded_channel::<OperatorSpanReport>();
+
+        tokio::spawn(async move {
+            const BATCH_SIZE: usize = 20;
+            const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+            let mut pending = Vec::with_capacity(BATCH_SIZE);
+
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => {
+                        report_rx.close();
+                    }
+                    _ = tokio::time::sleep(FLUSH_INTERVAL) => {}
+                    report = report_rx.recv() => {
+                        match report {
+                            Some(report) => pending.push(report),
+                            None => {}
+                        }
+                    }
+                }
+
+                if pending.len() >= BATCH_SIZE || (!pending.is_empty() && (cancel.is_cancelled() || report_rx.is_closed())) {
+                    for report in pending.drain(..) {
+                        if let Err(err) = publish_with_retries(&producer, &topic, &report).await {
+                            warn!(error=?err, operator=%report.operator, "trace: failed to publish span report after retries");
+                        }
+                    }
+                }
+
+                if report_rx.is_closed() && pending.is_empty() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self { report_tx })
+    }
+
+    /// Logs `report` and enqueues it for background publishing to Kafka
+    pub fn report(&self, report: OperatorSpanReport) {
+        log_span(&report);
+        let _ = self.report_tx.send(report);
+    }
+}
+
+async fn publish_with_retries(producer: &FutureProducer, topic: &str, report: &OperatorSpanReport) -> Result<()> {
+    let body = serde_json::to_vec(report)?;
+    let mut attempt = 0;
+    loop {
+        let record: FutureRecord<(), [u8]> = FutureRecord::to(topic).payload(&body);
+        match producer.send(record, Duration::from_secs(5)).await {
+            Ok(_) => return Ok(()),
+            Err((err, _)) if attempt < 2 => {
+                attempt += 1;
+                warn!(error=?err, attempt, "trace: retrying span report publish");
+            }
+            Err((err, _)) => anyhow::bail!("publishing span report to '{topic}': {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{spawn_metrics_tee, stamp_trace_id, TRACE_ID_KEY};
+    use datafusion::arrow::array::{Int64Array, RecordBatch};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use rde_core::Message;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use tokio_util::sync::CancellationToken;
+
+    fn batch() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from(vec![1, 2, 3]))]).unwrap()
+    }
+
+    #[test]
+    fn stamp_trace_id_adds_the_metadata_key() {
+        let stamped = stamp_trace_id(batch(), "abc-123");
+        assert_eq!(stamped.schema().metadata().get(TRACE_ID_KEY), Some(&"abc-123".to_string()));
+    }
+
+    #[test]
+    fn stamp_trace_id_leaves_an_already_stamped_batch_unchanged() {
+        let once = stamp_trace_id(batch(), "first");
+        let twice = stamp_trace_id(once, "second");
+        assert_eq!(twice.schema().metadata().get(TRACE_ID_KEY), Some(&"first".to_string()));
+    }
+
+    #[tokio::test]
+    async fn spawn_metrics_tee_counts_batches_rows_and_stamps_trace_id() {
+        let cancel = CancellationToken::new();
+        let (tap_tx, mut out_rx, counters) = spawn_metrics_tee(Arc::from("run-1"), 4, cancel.clone());
+
+        tap_tx.send(Message::Batch(batch())).await.unwrap();
+        let Message::Batch(out) = out_rx.recv().await.unwrap() else { panic!("expected Batch") };
+        assert_eq!(out.schema().metadata().get(TRACE_ID_KEY), Some(&"run-1".to_string()));
+        assert_eq!(counters.batches.load(Ordering::Relaxed), 1);
+        assert_eq!(counters.rows.load(Ordering::Relaxed), 3);
+
+        tap_tx.send(Message::Watermark(42)).await.unwrap();
+        assert!(matches!(out_rx.recv().await.unwrap(), Message::Watermark(42)));
+        assert_eq!(counters.batches.load(Ordering::Relaxed), 1);
+
+        cancel.cancel();
+    }
+}
@@ -11,13 +11,23 @@
 //! - **Schema Evolution**: Dynamic schema inference and evolution handling
 //! - **Data Cleaning**: Remove nulls, trim strings, normalize case
 //!
-//! ### Structural Transformations  
+//! ### Structural Transformations
 //! - **JSON Flattening**: Convert nested JSON structures to flat tables
 //! - **Partitioning**: Add partition columns for optimized storage
 //!
+//! `Repartition` (hash-based shuffle into N parallel output partitions) and
+//! `Union` (fan-in of multiple upstream streams with schema reconciliation)
+//! also live in this crate, but are `#[doc(hidden)]` and have no
+//! `TransformSpec` variant: `main.rs`'s pipeline construction has no
+//! multi-successor/multi-predecessor DAG concept yet for their extra
+//! channels to plug into, so no YAML pipeline can reach them today. See
+//! their own doc comments for what's missing before they can be wired up.
+//!
 //! ### Advanced Transformations
 //! - **SQL Transform**: Complex business logic using DataFusion SQL engine
 //! - **Window Operations**: Time-based aggregations and analytics
+//! - **Window Aggregate**: Watermark-triggered event-time `GROUP BY` aggregation
+//! - **Operator Catalog**: `information_schema`-style introspection over operator schemas
 //! - **Custom Transforms**: Extensible framework for domain-specific logic
 //!
 //! ## Example Usage
@@ -56,13 +66,18 @@
 //! WHERE user_id IS NOT NULL
 //! ```
 
-use datafusion::arrow::array::{RecordBatch, StringArray, ArrayRef};
-use datafusion::arrow::datatypes::{SchemaRef, Schema, Field, DataType};
-use anyhow::Result;
+use datafusion::arrow::array::{
+    BooleanArray, Float32Array, Float64Array, Int64Array, ListArray, RecordBatch, StringArray, StructArray,
+    TimestampMillisecondArray, ArrayRef,
+};
+use datafusion::arrow::datatypes::{SchemaRef, Schema, Field, DataType, TimeUnit};
+use datafusion::catalog::{CatalogProvider, SchemaProvider};
+use datafusion::datasource::{MemTable, TableProvider};
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use rde_core::{BatchRx, BatchTx, Message, Operator, Transform};
 use tokio_util::sync::CancellationToken;
-use tracing::{info, error};
+use tracing::{info, error, warn};
 use chrono::Utc;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -175,63 +190,187 @@ impl SchemaEvolution {
         if json_data.is_empty() {
             return Ok(self.current_schema.as_ref().clone());
         }
+        infer_schema_with_policy(json_data, self.strict_mode)
+    }
 
-        let mut field_map: HashMap<String, DataType> = HashMap::new();
-        
-        for value in json_data {
-            self.extract_fields_from_json(value, "", &mut field_map)?;
+    /// Checks that every field in `batch` is part of the declared schema.
+    /// Used when `auto_infer` is disabled, where the declared schema is
+    /// authoritative rather than something to widen.
+    fn check_declared_schema(&self, batch: &RecordBatch) -> anyhow::Result<()> {
+        for field in batch.schema().fields() {
+            if self.current_schema.field_with_name(field.name()).is_err() {
+                if self.strict_mode {
+                    anyhow::bail!(
+                        "SchemaEvolution: field '{}' is not part of the declared schema (strict_mode)",
+                        field.name()
+                    );
+                }
+                warn!(
+                    "SchemaEvolution: field '{}' is not part of the declared schema, ignoring (auto_infer disabled)",
+                    field.name()
+                );
+            }
         }
+        Ok(())
+    }
 
-        let mut fields = Vec::new();
-        for (name, data_type) in field_map {
-            fields.push(Field::new(name, data_type, true));
+    /// Adopts `new_schema` as `current_schema` if it actually differs,
+    /// logging which fields were added, widened, or removed
+    fn apply_schema_change(&mut self, new_schema: Schema) -> anyhow::Result<()> {
+        if new_schema == *self.current_schema {
+            return Ok(());
         }
+        log_schema_diff(&self.current_schema, &new_schema);
+        self.current_schema = Arc::new(new_schema);
+        Ok(())
+    }
+}
+
+/// Logs each field added, widened, or removed going from `old` to `new`, so
+/// schema drift is observable rather than silently applied
+fn log_schema_diff(old: &Schema, new: &Schema) {
+    let old_types: HashMap<&str, &DataType> =
+        old.fields().iter().map(|f| (f.name().as_str(), f.data_type())).collect();
+    let new_names: std::collections::HashSet<&str> = new.fields().iter().map(|f| f.name().as_str()).collect();
 
-        Ok(Schema::new(fields))
+    for field in new.fields() {
+        match old_types.get(field.name().as_str()) {
+            None => info!("SchemaEvolution: field '{}' added ({:?})", field.name(), field.data_type()),
+            Some(&old_type) if old_type != field.data_type() => info!(
+                "SchemaEvolution: field '{}' widened {:?} -> {:?}",
+                field.name(),
+                old_type,
+                field.data_type()
+            ),
+            _ => {}
+        }
+    }
+    for field in old.fields() {
+        if !new_names.contains(field.name().as_str()) {
+            info!("SchemaEvolution: field '{}' removed", field.name());
+        }
     }
+}
 
-    fn extract_fields_from_json(
-        &self,
-        value: &serde_json::Value,
-        prefix: &str,
-        field_map: &mut HashMap<String, DataType>,
-    ) -> anyhow::Result<()> {
-        match value {
-            serde_json::Value::Object(map) => {
-                for (key, val) in map {
-                    let field_name = if prefix.is_empty() {
-                        key.clone()
-                    } else {
-                        format!("{}.{}", prefix, key)
-                    };
-                    self.extract_fields_from_json(val, &field_name, field_map)?;
-                }
+/// Ordered map from field path to inferred `DataType`, preserving the order
+/// in which fields are first seen so merged schemas have deterministic
+/// field order instead of `HashMap` iteration order
+#[derive(Default)]
+struct FieldTypes {
+    order: Vec<String>,
+    types: HashMap<String, DataType>,
+}
+
+impl FieldTypes {
+    /// Merges `data_type` into whatever type (if any) `name` already has,
+    /// via [`widen_data_type`]
+    fn merge(&mut self, name: &str, data_type: DataType, strict_mode: bool) -> anyhow::Result<()> {
+        match self.types.get(name) {
+            Some(existing) => {
+                let widened = widen_data_type(existing, &data_type, strict_mode)
+                    .with_context(|| format!("SchemaEvolution: merging field '{name}'"))?;
+                self.types.insert(name.to_string(), widened);
             }
-            serde_json::Value::Array(arr) => {
-                if !arr.is_empty() {
-                    // For arrays, we'll use the type of the first element
-                    self.extract_fields_from_json(&arr[0], prefix, field_map)?;
-                }
+            None => {
+                self.order.push(name.to_string());
+                self.types.insert(name.to_string(), data_type);
             }
-            serde_json::Value::String(_) => {
-                field_map.insert(prefix.to_string(), DataType::Utf8);
+        }
+        Ok(())
+    }
+
+    fn into_schema(self) -> Schema {
+        let Self { order, types } = self;
+        let fields = order
+            .into_iter()
+            .map(|name| {
+                let data_type = types.get(&name).expect("every ordered field has a type").clone();
+                Field::new(name, data_type, true)
+            })
+            .collect();
+        Schema::new(fields)
+    }
+}
+
+/// Widens `existing` and `incoming` to their least-general common type:
+/// `Int64`+`Float64` -> `Float64`, `Boolean`+`Int64` -> `Int64`, any
+/// numeric/boolean+`Utf8` -> `Utf8`. Mismatched, non-coercible types widen to
+/// `Utf8` as a last resort, unless `strict_mode` is set, in which case they
+/// are rejected.
+fn widen_data_type(existing: &DataType, incoming: &DataType, strict_mode: bool) -> anyhow::Result<DataType> {
+    use DataType::*;
+    Ok(match (existing, incoming) {
+        (a, b) if a == b => a.clone(),
+        (Float64, Int64) | (Int64, Float64) => Float64,
+        (Boolean, Int64) | (Int64, Boolean) => Int64,
+        (Utf8, Boolean | Int64 | Float64) | (Boolean | Int64 | Float64, Utf8) => Utf8,
+        (a, b) => {
+            if strict_mode {
+                anyhow::bail!("incompatible types {a:?} and {b:?} for the same field");
             }
-            serde_json::Value::Number(n) => {
-                if n.is_i64() {
-                    field_map.insert(prefix.to_string(), DataType::Int64);
+            Utf8
+        }
+    })
+}
+
+/// Infers a flat Arrow schema from a slice of JSON objects, nesting object
+/// keys with `.` (e.g. `"a": {"b": 1}` becomes field `"a.b"`), widening a
+/// field's type across rows via [`widen_data_type`]. An empty slice produces
+/// an empty schema.
+fn infer_schema(json_data: &[serde_json::Value]) -> Schema {
+    infer_schema_with_policy(json_data, false).expect("non-strict merge never errors")
+}
+
+/// Like [`infer_schema`], but bails on incompatible field types instead of
+/// widening to `Utf8` when `strict_mode` is set
+fn infer_schema_with_policy(json_data: &[serde_json::Value], strict_mode: bool) -> anyhow::Result<Schema> {
+    let mut fields = FieldTypes::default();
+    for value in json_data {
+        extract_fields_from_json(value, "", &mut fields, strict_mode)?;
+    }
+    Ok(fields.into_schema())
+}
+
+fn extract_fields_from_json(
+    value: &serde_json::Value,
+    prefix: &str,
+    fields: &mut FieldTypes,
+    strict_mode: bool,
+) -> anyhow::Result<()> {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, val) in map {
+                let field_name = if prefix.is_empty() {
+                    key.clone()
                 } else {
-                    field_map.insert(prefix.to_string(), DataType::Float64);
-                }
-            }
-            serde_json::Value::Bool(_) => {
-                field_map.insert(prefix.to_string(), DataType::Boolean);
+                    format!("{}.{}", prefix, key)
+                };
+                extract_fields_from_json(val, &field_name, fields, strict_mode)?;
             }
-            serde_json::Value::Null => {
-                // Skip null values in schema inference
+        }
+        serde_json::Value::Array(arr) => {
+            if !arr.is_empty() {
+                // For arrays, we'll use the type of the first element
+                extract_fields_from_json(&arr[0], prefix, fields, strict_mode)?;
             }
         }
-        Ok(())
+        serde_json::Value::String(_) => {
+            fields.merge(prefix, DataType::Utf8, strict_mode)?;
+        }
+        serde_json::Value::Number(n) => {
+            let data_type = if n.is_i64() { DataType::Int64 } else { DataType::Float64 };
+            fields.merge(prefix, data_type, strict_mode)?;
+        }
+        serde_json::Value::Bool(_) => {
+            fields.merge(prefix, DataType::Boolean, strict_mode)?;
+        }
+        serde_json::Value::Null => {
+            // Skip null values in schema inference; fields only seen as null
+            // stay out of the schema, and fields seen elsewhere are already
+            // nullable (see `FieldTypes::into_schema`)
+        }
     }
+    Ok(())
 }
 
 #[async_trait]
@@ -257,16 +396,14 @@ impl Transform for SchemaEvolution {
             match &msg {
                 Message::Batch(batch) => {
                     info!("SchemaEvolution: processing batch with {} rows", batch.num_rows());
-                    
+
                     if self.auto_infer {
                         // Convert batch to JSON for schema inference
-                        let json_data = self.batch_to_json(batch)?;
+                        let json_data = batch_to_json(batch)?;
                         let new_schema = self.infer_schema_from_json(&json_data)?;
-                        
-                        if new_schema != *self.current_schema {
-                            info!("Schema evolution detected: updating schema");
-                            self.current_schema = Arc::new(new_schema);
-                        }
+                        self.apply_schema_change(new_schema)?;
+                    } else {
+                        self.check_declared_schema(batch)?;
                     }
                 }
                 Message::Watermark(_) => {
@@ -286,29 +423,105 @@ impl Transform for SchemaEvolution {
     }
 }
 
-impl SchemaEvolution {
-    fn batch_to_json(&self, batch: &RecordBatch) -> anyhow::Result<Vec<serde_json::Value>> {
-        // Convert Arrow batch to JSON for schema inference
-        // This is a simplified implementation
-        // TODO: check with schema registry for desired schema and infer which table and partition the data is for based on schema
-        let mut json_data = Vec::new();
-        for row_idx in 0..batch.num_rows() {
-            let mut row = serde_json::Map::new();
-            for (col_idx, field) in batch.schema().fields().iter().enumerate() {
-                let array = batch.column(col_idx);
-                let value = self.array_value_to_json(array, row_idx)?;
-                row.insert(field.name().clone(), value);
-            }
-            json_data.push(serde_json::Value::Object(row));
+/// Converts one `RecordBatch` into a vector of per-row JSON objects, with
+/// each column's value converted according to its Arrow `DataType` (see
+/// [`array_value_to_json`])
+fn batch_to_json(batch: &RecordBatch) -> anyhow::Result<Vec<serde_json::Value>> {
+    let mut json_data = Vec::with_capacity(batch.num_rows());
+    for row_idx in 0..batch.num_rows() {
+        let mut row = serde_json::Map::new();
+        for (col_idx, field) in batch.schema().fields().iter().enumerate() {
+            let array = batch.column(col_idx);
+            let value = array_value_to_json(array, row_idx)?;
+            row.insert(field.name().clone(), value);
         }
-        Ok(json_data)
+        json_data.push(serde_json::Value::Object(row));
     }
+    Ok(json_data)
+}
 
-    fn array_value_to_json(&self, _array: &ArrayRef, _row_idx: usize) -> anyhow::Result<serde_json::Value> {
-        // Simplified conversion from Arrow array to JSON value
-        // In a real implementation, you'd handle all Arrow types properly
-        Ok(serde_json::Value::String("placeholder".to_string()))
+/// Converts a single array element to its JSON representation, dispatching
+/// on the array's Arrow `DataType`. Null values become `Value::Null`
+/// regardless of type.
+fn array_value_to_json(array: &ArrayRef, row_idx: usize) -> anyhow::Result<serde_json::Value> {
+    if array.is_null(row_idx) {
+        return Ok(serde_json::Value::Null);
     }
+    Ok(match array.data_type() {
+        DataType::Boolean => {
+            let arr = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+            serde_json::Value::Bool(arr.value(row_idx))
+        }
+        DataType::Int64 => {
+            let arr = array.as_any().downcast_ref::<Int64Array>().unwrap();
+            serde_json::Value::Number(arr.value(row_idx).into())
+        }
+        DataType::Float64 => {
+            let arr = array.as_any().downcast_ref::<Float64Array>().unwrap();
+            serde_json::Number::from_f64(arr.value(row_idx))
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null)
+        }
+        DataType::Utf8 => {
+            let arr = array.as_any().downcast_ref::<StringArray>().unwrap();
+            serde_json::Value::String(arr.value(row_idx).to_string())
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            let arr = array.as_any().downcast_ref::<TimestampMillisecondArray>().unwrap();
+            serde_json::Value::Number(arr.value(row_idx).into())
+        }
+        DataType::List(_) => {
+            let arr = array.as_any().downcast_ref::<ListArray>().unwrap();
+            let list_values = arr.value(row_idx);
+            let mut items = Vec::with_capacity(list_values.len());
+            for item_idx in 0..list_values.len() {
+                items.push(array_value_to_json(&list_values, item_idx)?);
+            }
+            serde_json::Value::Array(items)
+        }
+        DataType::Struct(_) => {
+            let arr = array.as_any().downcast_ref::<StructArray>().unwrap();
+            let mut map = serde_json::Map::new();
+            for (col_idx, field) in arr.fields().iter().enumerate() {
+                let value = array_value_to_json(arr.column(col_idx), row_idx)?;
+                map.insert(field.name().clone(), value);
+            }
+            serde_json::Value::Object(map)
+        }
+        other => anyhow::bail!("array_value_to_json: unsupported Arrow type {other:?}"),
+    })
+}
+
+/// Builds a typed Arrow column for `field` by pulling each row's value (by
+/// field name) out of `json_data`, dispatching the builder on `field`'s
+/// `DataType`
+fn json_column_to_array(field: &Field, json_data: &[serde_json::Value]) -> anyhow::Result<ArrayRef> {
+    Ok(match field.data_type() {
+        DataType::Boolean => {
+            let values: Vec<Option<bool>> = json_data.iter().map(|row| row.get(field.name()).and_then(|v| v.as_bool())).collect();
+            Arc::new(BooleanArray::from(values))
+        }
+        DataType::Int64 => {
+            let values: Vec<Option<i64>> = json_data.iter().map(|row| row.get(field.name()).and_then(|v| v.as_i64())).collect();
+            Arc::new(Int64Array::from(values))
+        }
+        DataType::Float64 => {
+            let values: Vec<Option<f64>> = json_data.iter().map(|row| row.get(field.name()).and_then(|v| v.as_f64())).collect();
+            Arc::new(Float64Array::from(values))
+        }
+        DataType::Utf8 => {
+            let values: Vec<Option<String>> = json_data
+                .iter()
+                .map(|row| match row.get(field.name()) {
+                    Some(serde_json::Value::String(s)) => Some(s.clone()),
+                    Some(serde_json::Value::Null) | None => None,
+                    Some(other) => Some(other.to_string()),
+                })
+                .collect();
+            Arc::new(StringArray::from(values))
+        }
+        other => anyhow::bail!("json_column_to_array: unsupported inferred field type {other:?}"),
+    })
 }
 
 /// JSON flattening transform that converts nested JSON structures to flat relational format
@@ -422,9 +635,9 @@ impl Transform for JsonFlatten {
 impl JsonFlatten {
     fn flatten_batch(&self, batch: &RecordBatch) -> anyhow::Result<RecordBatch> {
         // Convert batch to JSON, flatten, then back to Arrow
-        let json_data = self.batch_to_json(batch)?;
+        let json_data = batch_to_json(batch)?;
         let mut flattened_data = Vec::new();
-        
+
         for row in json_data {
             let mut flattened_row = HashMap::new();
             self.flatten_json_value(&row, "", 0, &mut flattened_row)?;
@@ -432,45 +645,22 @@ impl JsonFlatten {
             let map = serde_json::Map::from_iter(flattened_row);
             flattened_data.push(serde_json::Value::Object(map));
         }
-        
-        // Convert back to Arrow batch
-        self.json_to_batch(&flattened_data)
-    }
-
-    fn batch_to_json(&self, batch: &RecordBatch) -> anyhow::Result<Vec<serde_json::Value>> {
-        // Simplified conversion - in real implementation, handle all Arrow types
-        let mut json_data = Vec::new();
-        for row_idx in 0..batch.num_rows() {
-            let mut row = serde_json::Map::new();
-            for (col_idx, field) in batch.schema().fields().iter().enumerate() {
-                let array = batch.column(col_idx);
-                let value = self.array_value_to_json(array, row_idx)?;
-                row.insert(field.name().clone(), value);
-            }
-            json_data.push(serde_json::Value::Object(row));
-        }
-        Ok(json_data)
-    }
 
-    fn array_value_to_json(&self, _array: &ArrayRef, _row_idx: usize) -> anyhow::Result<serde_json::Value> {
-        // Simplified conversion - in real implementation, handle all Arrow types
-        Ok(serde_json::Value::String("placeholder".to_string()))
+        // Convert back to Arrow batch
+        json_to_batch(&flattened_data)
     }
+}
 
-    fn json_to_batch(&self, json_data: &[serde_json::Value]) -> anyhow::Result<RecordBatch> {
-        // Simplified conversion - in real implementation, handle all Arrow types
-        let schema = Arc::new(Schema::new(vec![
-            Field::new("flattened_data", DataType::Utf8, true)
-        ]));
-        
-        let strings: Vec<Option<String>> = json_data
-            .iter()
-            .map(|v| Some(v.to_string()))
-            .collect();
-        
-        let array = StringArray::from(strings);
-        Ok(RecordBatch::try_new(schema, vec![Arc::new(array)])?)
+/// Converts flattened JSON rows back into a `RecordBatch`, inferring a flat
+/// schema from the rows and building one typed Arrow column per field (see
+/// [`json_column_to_array`])
+fn json_to_batch(json_data: &[serde_json::Value]) -> anyhow::Result<RecordBatch> {
+    let schema = Arc::new(infer_schema(json_data));
+    let mut columns = Vec::with_capacity(schema.fields().len());
+    for field in schema.fields() {
+        columns.push(json_column_to_array(field, json_data)?);
     }
+    Ok(RecordBatch::try_new(schema, columns)?)
 }
 
 /// Partitioning transform that adds partition columns based on data values
@@ -604,188 +794,140 @@ impl Partition {
     }
 }
 
-/// SQL transformation using DataFusion
-pub struct SqlTransform {
+/// Hash-based shuffle operator that fans a single input stream into
+/// `num_partitions` output partitions, modeled on Ballista's shuffle writer.
+///
+/// Unlike the other transforms in this module, `Repartition` drives N
+/// downstream `BatchTx` senders rather than one, so it cannot implement the
+/// single-output [`Transform`] trait; it exposes its own `run` instead and
+/// is driven directly rather than through [`create_transform`]. There is
+/// deliberately no `TransformSpec::Repartition` variant either: `main.rs`'s
+/// pipeline construction is still "v0: assume single source -> transforms ->
+/// single sink" — one channel per positional edge, built from
+/// `spec.transforms.len() + 1` — so there is nowhere for a fan-out
+/// operator's extra output channels to go. Exposing this to YAML pipelines
+/// needs `PipelineSpec`/`EdgeSpec` to model a real multi-successor DAG
+/// first, not just a new enum variant.
+///
+/// `#[doc(hidden)]` until that DAG support lands, so this doesn't show up
+/// in generated docs as a pipeline feature users can reach today.
+///
+/// Status: blocked, not done. A YAML pipeline cannot reach this operator;
+/// closing this out as delivered would overstate it. Unblocking it is
+/// scoped to adding `PipelineSpec`/`EdgeSpec` DAG wiring to `main.rs`.
+#[doc(hidden)]
+pub struct Repartition {
     id: String,
     schema: SchemaRef,
-    query: String,
-    window_size: usize,
-    ctx: datafusion::prelude::SessionContext,
+    key_columns: Vec<String>,
+    num_partitions: usize,
 }
 
-impl SqlTransform {
-    pub fn new(id: String, schema: SchemaRef, query: String, window_size: usize) -> anyhow::Result<Self> {
-        let ctx = datafusion::prelude::SessionContext::new();
-        
-        Ok(Self {
+impl Repartition {
+    pub fn new(id: String, schema: SchemaRef, key_columns: Vec<String>, num_partitions: usize) -> Self {
+        Self {
             id,
             schema,
-            query,
-            window_size,
-            ctx,
-        })
-    }
-
-    async fn execute_sql_query(&self, batch: &RecordBatch) -> anyhow::Result<RecordBatch> {
-        // Register the batch as a temporary table
-        let table_name = "input_data";
-        // Convert our RecordBatch to DataFusion's RecordBatch
-        let df_batch = datafusion::arrow::array::RecordBatch::try_new(
-            batch.schema().clone(),
-            batch.columns().to_vec(),
-        )?;
-        self.ctx.register_batch(table_name, df_batch)?;
-        
-        // Execute the SQL query
-        let df = self.ctx.sql(&self.query).await?;
-        let result = df.collect().await?;
-        
-        if result.is_empty() {
-            // Return empty batch with same schema
-            return Ok(RecordBatch::new_empty(self.schema.clone()));
+            key_columns,
+            num_partitions,
         }
-        
-        // Convert DataFusion's RecordBatch back to our RecordBatch
-        let df_batch = &result[0];
-        Ok(RecordBatch::try_new(
-            df_batch.schema().clone(),
-            df_batch.columns().to_vec(),
-        )?)
-    }
-}
-
-#[async_trait]
-impl Operator for SqlTransform {
-    fn name(&self) -> &str {
-        &self.id
-    }
-    fn schema(&self) -> SchemaRef {
-        self.schema.clone()
     }
-}
 
-#[async_trait]
-impl Transform for SqlTransform {
-    async fn run(
-        &mut self,
-        mut rx: BatchRx,
-        tx: BatchTx,
-        _cancel: CancellationToken,
-    ) -> anyhow::Result<()> {
-        info!("SqlTransform started with query: {}", self.query);
-        
-        let mut batch_buffer = Vec::new();
-        
+    /// Consumes `rx`, splitting each batch across `txs` by hashing
+    /// `key_columns`. Watermarks and EOS are forwarded to every partition.
+    pub async fn run(&mut self, mut rx: BatchRx, txs: Vec<BatchTx>, cancel: CancellationToken) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            txs.len() == self.num_partitions,
+            "Repartition: expected {} output channels, got {}",
+            self.num_partitions,
+            txs.len()
+        );
+        info!("Repartition transform started with {} partitions", self.num_partitions);
         while let Some(msg) = rx.recv().await {
-            match &msg {
+            if cancel.is_cancelled() {
+                info!("Repartition cancelled");
+                break;
+            }
+            match msg {
                 Message::Batch(batch) => {
-                    info!("SqlTransform: received batch with {} rows", batch.num_rows());
-                    batch_buffer.push(batch.clone());
-                    
-                    // Process when we have enough data or on watermark
-                    if batch_buffer.len() >= self.window_size {
-                        let combined_batch = self.combine_batches(&batch_buffer)?;
-                        let transformed_batch = self.execute_sql_query(&combined_batch).await?;
-                        
-                        if tx.send(Message::Batch(transformed_batch)).await.is_err() {
-                            error!("SqlTransform: failed to send transformed batch");
-                            break;
+                    info!("Repartition: processing batch with {} rows", batch.num_rows());
+                    for (partition, sub_batch) in self.partition_batch(&batch)?.into_iter().enumerate() {
+                        if let Some(sub_batch) = sub_batch {
+                            if txs[partition].send(Message::Batch(sub_batch)).await.is_err() {
+                                warn!("Repartition: partition {partition} receiver dropped");
+                            }
                         }
-                        
-                        batch_buffer.clear();
                     }
                 }
-                Message::Watermark(_) => {
-                    info!("SqlTransform: received watermark");
-                    
-                    // Process remaining data
-                    if !batch_buffer.is_empty() {
-                        let combined_batch = self.combine_batches(&batch_buffer)?;
-                        let transformed_batch = self.execute_sql_query(&combined_batch).await?;
-                        
-                        if tx.send(Message::Batch(transformed_batch)).await.is_err() {
-                            error!("SqlTransform: failed to send transformed batch");
-                            break;
-                        }
-                        
-                        batch_buffer.clear();
-                    }
-                    
-                    if tx.send(msg).await.is_err() {
-                        break;
+                Message::Watermark(ts) => {
+                    info!("Repartition: broadcasting watermark to all partitions");
+                    for tx in &txs {
+                        let _ = tx.send(Message::Watermark(ts)).await;
                     }
                 }
                 Message::Eos => {
-                    info!("SqlTransform: received EOS");
-                    
-                    // Process remaining data
-                    if !batch_buffer.is_empty() {
-                        let combined_batch = self.combine_batches(&batch_buffer)?;
-                        let transformed_batch = self.execute_sql_query(&combined_batch).await?;
-                        
-                        if tx.send(Message::Batch(transformed_batch)).await.is_err() {
-                            error!("SqlTransform: failed to send transformed batch");
-                            break;
-                        }
-                    }
-                    
-                    if tx.send(msg).await.is_err() {
-                        break;
+                    info!("Repartition: broadcasting EOS to all partitions");
+                    for tx in &txs {
+                        let _ = tx.send(Message::Eos).await;
                     }
                 }
             }
         }
-        
-        info!("SqlTransform finished");
+        info!("Repartition transform finished");
         Ok(())
     }
-}
 
-impl SqlTransform {
-    fn combine_batches(&self, batches: &[RecordBatch]) -> anyhow::Result<RecordBatch> {
-        if batches.is_empty() {
-            return Ok(RecordBatch::new_empty(self.schema.clone()));
-        }
-        
-        if batches.len() == 1 {
-            return Ok(batches[0].clone());
+    /// Buckets `batch`'s rows by `hash(key_columns) % num_partitions` and
+    /// slices out one sub-batch per non-empty bucket via `arrow::compute::take`
+    fn partition_batch(&self, batch: &RecordBatch) -> anyhow::Result<Vec<Option<RecordBatch>>> {
+        let mut buckets: Vec<Vec<u32>> = vec![Vec::new(); self.num_partitions];
+        for row_idx in 0..batch.num_rows() {
+            let hash = self.hash_row(batch, row_idx)?;
+            let bucket = (hash % self.num_partitions as u64) as usize;
+            buckets[bucket].push(row_idx as u32);
         }
-        
-        // Combine multiple batches into one
-        // This is a simplified implementation - in real implementation, handle schema merging
-        Ok(batches[0].clone())
+
+        buckets
+            .into_iter()
+            .map(|indices| {
+                if indices.is_empty() {
+                    return Ok(None);
+                }
+                let index_array = datafusion::arrow::array::UInt32Array::from(indices);
+                let columns = batch
+                    .columns()
+                    .iter()
+                    .map(|col| datafusion::arrow::compute::take(col, &index_array, None))
+                    .collect::<Result<Vec<ArrayRef>, _>>()?;
+                Ok(Some(RecordBatch::try_new(batch.schema(), columns)?))
+            })
+            .collect()
     }
-}
 
-/// Data cleaning transform
-pub struct CleanData {
-    id: String,
-    schema: SchemaRef,
-    remove_nulls: bool,
-    trim_strings: bool,
-    normalize_case: Option<String>,
-}
+    /// Mixes each key column's JSON-serialized value for `row_idx` into a
+    /// running FNV-1a hash
+    fn hash_row(&self, batch: &RecordBatch, row_idx: usize) -> anyhow::Result<u64> {
+        const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
 
-impl CleanData {
-    pub fn new(
-        id: String, 
-        schema: SchemaRef, 
-        remove_nulls: bool, 
-        trim_strings: bool, 
-        normalize_case: Option<String>
-    ) -> Self {
-        Self {
-            id,
-            schema,
-            remove_nulls,
-            trim_strings,
-            normalize_case,
+        let mut hash = FNV_OFFSET;
+        for key in &self.key_columns {
+            let col_idx = batch
+                .schema()
+                .index_of(key)
+                .with_context(|| format!("Repartition: unknown key column '{key}'"))?;
+            let value = array_value_to_json(batch.column(col_idx), row_idx)?;
+            for byte in value.to_string().as_bytes() {
+                hash ^= *byte as u64;
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
         }
+        Ok(hash)
     }
 }
 
 #[async_trait]
-impl Operator for CleanData {
+impl Operator for Repartition {
     fn name(&self) -> &str {
         &self.id
     }
@@ -794,21 +936,1746 @@ impl Operator for CleanData {
     }
 }
 
-#[async_trait]
-impl Transform for CleanData {
-    async fn run(
-        &mut self,
-        mut rx: BatchRx,
-        tx: BatchTx,
-        _cancel: CancellationToken,
-    ) -> anyhow::Result<()> {
-        info!("CleanData transform started");
-        while let Some(msg) = rx.recv().await {
-            match &msg {
-                Message::Batch(batch) => {
-                    info!("CleanData: processing batch with {} rows", batch.num_rows());
-                    
-                    let cleaned_batch = self.clean_batch(batch)?;
+/// Fans multiple upstream streams, assumed to share `schema` up to column
+/// order and nullability, into one ordered output stream — `UNION ALL`
+/// semantics applied to the streaming pipeline (DataFusion's own union
+/// execution plan does the batch-level equivalent).
+///
+/// Like [`Repartition`], `Union` is N-input rather than the single-input
+/// [`Transform`] trait, so it exposes its own `run` and is driven directly
+/// rather than through [`create_transform`]; there is deliberately no
+/// `TransformSpec::Union` variant either, for the same reason as
+/// `Repartition` — `main.rs`'s pipeline construction is still a strictly
+/// linear source->transforms->sink chain with one channel per positional
+/// edge, so there is no way for a fan-in operator to receive more than one
+/// upstream channel. That needs `PipelineSpec`/`EdgeSpec` to model a real
+/// multi-predecessor DAG first.
+///
+/// `#[doc(hidden)]` until that DAG support lands, so this doesn't show up
+/// in generated docs as a pipeline feature users can reach today.
+///
+/// Status: blocked, not done. A YAML pipeline cannot reach this operator;
+/// closing this out as delivered would overstate it. Unblocking it is
+/// scoped to adding `PipelineSpec`/`EdgeSpec` DAG wiring to `main.rs`.
+#[doc(hidden)]
+pub struct Union {
+    id: String,
+    schema: SchemaRef,
+    /// Per-input column indices into `schema`, precomputed once so batches
+    /// whose order already matches `schema` are recognized without
+    /// recomputing it on every batch
+    input_column_order: Vec<Vec<usize>>,
+}
+
+impl Union {
+    /// Verifies every schema in `input_schemas` carries every field name of
+    /// `schema` (extra input columns are allowed and dropped; column order
+    /// and nullability may differ; this does not
+    /// verify data types line up, since a per-column `cast` at batch time
+    /// can reconcile a merely-widenable mismatch) and precomputes the
+    /// column reordering needed for each input.
+    pub fn new(id: String, schema: SchemaRef, input_schemas: &[SchemaRef]) -> anyhow::Result<Self> {
+        let input_column_order = input_schemas
+            .iter()
+            .enumerate()
+            .map(|(input_idx, input_schema)| column_order_for(&schema, input_schema, input_idx))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            id,
+            schema,
+            input_column_order,
+        })
+    }
+
+    /// Merges `rxs` into `tx` in arrival order. Watermarks are forwarded as
+    /// the minimum watermark seen across all inputs so far (an input that
+    /// hasn't emitted one yet holds the output watermark back); EOS is only
+    /// forwarded once every input has signaled it.
+    pub async fn run(&mut self, mut rxs: Vec<BatchRx>, tx: BatchTx, cancel: CancellationToken) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            rxs.len() == self.input_column_order.len(),
+            "Union: expected {} input channels, got {}",
+            self.input_column_order.len(),
+            rxs.len()
+        );
+        let num_inputs = rxs.len();
+        let mut watermarks = vec![i64::MIN; num_inputs];
+        let mut last_emitted_watermark = i64::MIN;
+        let mut eos_received = vec![false; num_inputs];
+        info!("Union transform started with {num_inputs} inputs");
+
+        let all_done = loop {
+            if cancel.is_cancelled() {
+                info!("Union cancelled");
+                break false;
+            }
+            let pending: Vec<usize> = (0..num_inputs).filter(|&i| !eos_received[i]).collect();
+            if pending.is_empty() {
+                break true;
+            }
+
+            let futs = pending.iter().map(|&i| Box::pin(rxs[i].recv())).collect::<Vec<_>>();
+            let (result, idx_in_pending, _) = futures::future::select_all(futs).await;
+            let input_idx = pending[idx_in_pending];
+
+            match result {
+                Some(Message::Batch(batch)) => {
+                    let reconciled = self.reconcile_batch(input_idx, &batch)?;
+                    if tx.send(Message::Batch(reconciled)).await.is_err() {
+                        error!("Union: failed to send merged batch");
+                        break false;
+                    }
+                }
+                Some(Message::Watermark(ts)) => {
+                    watermarks[input_idx] = watermarks[input_idx].max(ts);
+                    let min_watermark = *watermarks.iter().min().unwrap();
+                    if min_watermark > last_emitted_watermark {
+                        last_emitted_watermark = min_watermark;
+                        if tx.send(Message::Watermark(min_watermark)).await.is_err() {
+                            break false;
+                        }
+                    }
+                }
+                Some(Message::Eos) => {
+                    eos_received[input_idx] = true;
+                    info!("Union: input {input_idx} signaled EOS");
+                }
+                None => {
+                    warn!("Union: input {input_idx} channel closed without EOS");
+                    eos_received[input_idx] = true;
+                }
+            }
+        };
+
+        if all_done {
+            let _ = tx.send(Message::Eos).await;
+        }
+        info!("Union transform finished");
+        Ok(())
+    }
+
+    /// Reorders (and, if needed, casts) `batch`'s columns from input
+    /// `input_idx`'s order into `self.schema`'s canonical order, coalescing
+    /// it through unchanged when order and types already match.
+    fn reconcile_batch(&self, input_idx: usize, batch: &RecordBatch) -> anyhow::Result<RecordBatch> {
+        let order = &self.input_column_order[input_idx];
+        if order.iter().enumerate().all(|(dst, &src)| dst == src) && batch.schema() == self.schema {
+            return Ok(batch.clone());
+        }
+
+        let columns = order
+            .iter()
+            .enumerate()
+            .map(|(dst, &src)| {
+                let array = batch.column(src);
+                let target_type = self.schema.field(dst).data_type();
+                if array.data_type() == target_type {
+                    Ok(array.clone())
+                } else {
+                    datafusion::arrow::compute::cast(array, target_type).with_context(|| {
+                        format!(
+                            "Union: casting column '{}' from input {input_idx}",
+                            self.schema.field(dst).name()
+                        )
+                    })
+                }
+            })
+            .collect::<anyhow::Result<Vec<ArrayRef>>>()?;
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+#[async_trait]
+impl Operator for Union {
+    fn name(&self) -> &str {
+        &self.id
+    }
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Maps each field in `schema` to its column index in `input_schema`,
+/// verifying `input_schema` carries every field `schema` does (column order
+/// and nullability may differ)
+fn column_order_for(schema: &SchemaRef, input_schema: &SchemaRef, input_idx: usize) -> anyhow::Result<Vec<usize>> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| {
+            input_schema
+                .index_of(field.name())
+                .with_context(|| format!("Union: input {input_idx} is missing field '{}'", field.name()))
+        })
+        .collect()
+}
+
+/// Returns every window's end (exclusive), keyed so panes can be stored in a
+/// `BTreeMap<window_end, _>`, that the event-time `ts` falls into for a
+/// window of `size_ms` sliding every `slide_ms` (a tumbling window is the
+/// `size_ms == slide_ms` case, yielding exactly one window per row)
+fn window_ends_for(ts: i64, size_ms: i64, slide_ms: i64) -> Vec<i64> {
+    let last_slide_start = ts.div_euclid(slide_ms) * slide_ms;
+    let num_windows = size_ms / slide_ms;
+    (0..num_windows)
+        .map(|i| last_slide_start - i * slide_ms)
+        .filter(|&start| ts >= start && ts < start + size_ms)
+        .map(|start| start + size_ms)
+        .collect()
+}
+
+/// Parses the `func` string of a [`rde_core::WindowAggregateSpec`] into an
+/// [`AggFunc`]
+fn parse_agg_func(func: &str) -> anyhow::Result<AggFunc> {
+    match func.to_lowercase().as_str() {
+        "count" => Ok(AggFunc::Count),
+        "sum" => Ok(AggFunc::Sum),
+        "min" => Ok(AggFunc::Min),
+        "max" => Ok(AggFunc::Max),
+        "avg" => Ok(AggFunc::Avg),
+        other => anyhow::bail!("WindowAggregate: unknown aggregate function '{other}'"),
+    }
+}
+
+/// Watermark-triggered event-time windowed aggregation.
+///
+/// Unlike [`SqlTransform`]'s event-time panes (which just buffer rows for a
+/// later re-run of a SQL query), this operator maintains a running
+/// [`AggState`] per `(window_end, group-by key)` directly — the same
+/// accumulator machinery [`SqlTransform::apply_incremental_batch`] uses for
+/// incremental `GROUP BY` maintenance. A `Message::Watermark(ts)` evicts and
+/// emits every window whose end is `<= ts`; `Message::Eos` flushes whatever
+/// remains. Unlike `SqlTransform`/`CleanData`, which forward watermarks
+/// untouched, this is the operator that actually gives them meaning.
+///
+/// The declared output `schema` must contain every `group_by` column, every
+/// aggregate's `alias`, and a `window_end` (`Int64`, window end exclusive,
+/// milliseconds) column.
+pub struct WindowAggregate {
+    id: String,
+    schema: SchemaRef,
+    timestamp_column: String,
+    window: rde_core::WindowSpec,
+    group_by: Vec<String>,
+    aggregates: Vec<IncrementalAggregate>,
+    windows: std::collections::BTreeMap<i64, HashMap<Vec<datafusion::scalar::ScalarValue>, AggState>>,
+    /// Highest watermark seen so far; rows with `ts < current_watermark` are
+    /// counted in `late_dropped` and discarded rather than assigned to a window
+    current_watermark: i64,
+    late_dropped: u64,
+}
+
+impl WindowAggregate {
+    pub fn new(
+        id: String,
+        schema: SchemaRef,
+        timestamp_column: String,
+        window: rde_core::WindowSpec,
+        group_by: Vec<String>,
+        aggregate_specs: &[rde_core::WindowAggregateSpec],
+    ) -> anyhow::Result<Self> {
+        let aggregates = aggregate_specs
+            .iter()
+            .map(|spec| {
+                Ok(IncrementalAggregate {
+                    alias: spec.alias.clone(),
+                    func: parse_agg_func(&spec.func)?,
+                    arg_column: if spec.column.is_empty() { "*".to_string() } else { spec.column.clone() },
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            id,
+            schema,
+            timestamp_column,
+            window,
+            group_by,
+            aggregates,
+            windows: std::collections::BTreeMap::new(),
+            current_watermark: i64::MIN,
+            late_dropped: 0,
+        })
+    }
+
+    /// Assigns every row of `batch` to each window it falls into (per
+    /// [`window_ends_for`] — more than one for a sliding window), updating
+    /// that window's per-group-key [`AggState`]
+    fn assign_to_windows(&mut self, batch: &RecordBatch) -> anyhow::Result<()> {
+        let schema = batch.schema();
+        let ts_idx = schema
+            .index_of(&self.timestamp_column)
+            .with_context(|| format!("WindowAggregate: unknown timestamp column '{}'", self.timestamp_column))?;
+        let group_by_idx: Vec<usize> = self
+            .group_by
+            .iter()
+            .map(|name| schema.index_of(name).with_context(|| format!("WindowAggregate: unknown group-by column '{name}'")))
+            .collect::<anyhow::Result<_>>()?;
+        let arg_idx: Vec<Option<usize>> = self
+            .aggregates
+            .iter()
+            .map(|agg| {
+                if agg.arg_column == "*" {
+                    Ok(None)
+                } else {
+                    schema
+                        .index_of(&agg.arg_column)
+                        .map(Some)
+                        .with_context(|| format!("WindowAggregate: unknown aggregate column '{}'", agg.arg_column))
+                }
+            })
+            .collect::<anyhow::Result<_>>()?;
+        let (size_ms, slide_ms) = self.window.size_and_slide_ms();
+
+        for row in 0..batch.num_rows() {
+            let ts = array_value_to_json(batch.column(ts_idx), row)?
+                .as_i64()
+                .with_context(|| format!("WindowAggregate: '{}' is not an integer timestamp", self.timestamp_column))?;
+
+            if ts < self.current_watermark {
+                self.late_dropped += 1;
+                continue;
+            }
+
+            let key: Vec<datafusion::scalar::ScalarValue> = group_by_idx
+                .iter()
+                .map(|&col| datafusion::scalar::ScalarValue::try_from_array(batch.column(col), row))
+                .collect::<Result<_, _>>()?;
+
+            for window_end in window_ends_for(ts, size_ms, slide_ms) {
+                let state = self
+                    .windows
+                    .entry(window_end)
+                    .or_default()
+                    .entry(key.clone())
+                    .or_insert_with(|| AggState(self.aggregates.iter().map(|agg| AggAccumulator::new(agg.func)).collect()));
+
+                for (acc, col_idx) in state.0.iter_mut().zip(arg_idx.iter()) {
+                    let value = match col_idx {
+                        None => Some(0.0), // COUNT(*): every row counts
+                        Some(col) => scalar_to_f64(&datafusion::scalar::ScalarValue::try_from_array(batch.column(*col), row)?),
+                    };
+                    acc.update(value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes and builds a completed-aggregate batch for every window whose
+    /// end is `<= watermark` (or every remaining window, if `watermark` is
+    /// `None`, for EOS), in window-end order
+    fn evict_windows(&mut self, watermark: Option<i64>) -> anyhow::Result<Vec<RecordBatch>> {
+        let window_ends: Vec<i64> = match watermark {
+            Some(w) => self.windows.range(..=w).map(|(&end, _)| end).collect(),
+            None => self.windows.keys().copied().collect(),
+        };
+
+        let mut results = Vec::with_capacity(window_ends.len());
+        for window_end in window_ends {
+            let state = self.windows.remove(&window_end).unwrap_or_default();
+            if !state.is_empty() {
+                results.push(self.build_window_batch(window_end, &state)?);
+            }
+        }
+        Ok(results)
+    }
+
+    /// Builds one output batch for `window_end`'s per-group `state`,
+    /// resolving each field of `self.schema` as the `window_end` column, a
+    /// `group_by` column, or a recognized aggregate alias
+    fn build_window_batch(
+        &self,
+        window_end: i64,
+        state: &HashMap<Vec<datafusion::scalar::ScalarValue>, AggState>,
+    ) -> anyhow::Result<RecordBatch> {
+        let keys: Vec<&Vec<datafusion::scalar::ScalarValue>> = state.keys().collect();
+
+        let mut columns: Vec<ArrayRef> = Vec::with_capacity(self.schema.fields().len());
+        for field in self.schema.fields() {
+            if field.name() == "window_end" {
+                columns.push(Arc::new(Int64Array::from(vec![window_end; keys.len()])));
+            } else if let Some(group_idx) = self.group_by.iter().position(|g| g == field.name()) {
+                let values = keys.iter().map(|key| key[group_idx].clone());
+                columns.push(datafusion::scalar::ScalarValue::iter_to_array(values)?);
+            } else if let Some(agg_idx) = self.aggregates.iter().position(|agg| &agg.alias == field.name()) {
+                let values: Float64Array = keys.iter().map(|key| state[*key].0[agg_idx].value()).collect();
+                columns.push(Arc::new(values));
+            } else {
+                anyhow::bail!(
+                    "WindowAggregate: field '{}' is neither 'window_end', a group-by column, nor a recognized aggregate alias",
+                    field.name()
+                );
+            }
+        }
+
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+#[async_trait]
+impl Operator for WindowAggregate {
+    fn name(&self) -> &str {
+        &self.id
+    }
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[async_trait]
+impl Transform for WindowAggregate {
+    async fn run(&mut self, mut rx: BatchRx, tx: BatchTx, _cancel: CancellationToken) -> anyhow::Result<()> {
+        info!("WindowAggregate started");
+
+        while let Some(msg) = rx.recv().await {
+            match msg {
+                Message::Batch(batch) => {
+                    info!("WindowAggregate: received batch with {} rows", batch.num_rows());
+                    self.assign_to_windows(&batch)?;
+                }
+                Message::Watermark(ts) => {
+                    info!("WindowAggregate: watermark {}, evicting closed windows", ts);
+                    self.current_watermark = self.current_watermark.max(ts);
+                    if self.late_dropped > 0 {
+                        info!("WindowAggregate: dropped {} late row(s) so far", self.late_dropped);
+                    }
+                    for result in self.evict_windows(Some(ts))? {
+                        if tx.send(Message::Batch(result)).await.is_err() {
+                            error!("WindowAggregate: failed to send windowed aggregate batch");
+                            return Ok(());
+                        }
+                    }
+                    if tx.send(Message::Watermark(ts)).await.is_err() {
+                        break;
+                    }
+                }
+                Message::Eos => {
+                    info!("WindowAggregate: EOS, flushing all remaining windows");
+                    for result in self.evict_windows(None)? {
+                        if tx.send(Message::Batch(result)).await.is_err() {
+                            error!("WindowAggregate: failed to send windowed aggregate batch");
+                            return Ok(());
+                        }
+                    }
+                    let _ = tx.send(Message::Eos).await;
+                    break;
+                }
+            }
+        }
+
+        info!("WindowAggregate finished");
+        Ok(())
+    }
+}
+
+/// Builds the `columns` metadata table: one row per `(operator, field)`
+/// pair, describing that operator's current (post-evolution) Arrow schema.
+/// `operators` pairs each operator with a human-readable kind label (e.g.
+/// `"sql_transform"`), since [`Operator`] itself doesn't carry one.
+fn columns_batch(operators: &[(&str, &dyn Operator)]) -> anyhow::Result<RecordBatch> {
+    let mut operator_ids = Vec::new();
+    let mut operator_kinds = Vec::new();
+    let mut field_names = Vec::new();
+    let mut data_types = Vec::new();
+    let mut nullables = Vec::new();
+
+    for (kind, operator) in operators {
+        for field in operator.schema().fields() {
+            operator_ids.push(operator.name().to_string());
+            operator_kinds.push(kind.to_string());
+            field_names.push(field.name().clone());
+            data_types.push(format!("{:?}", field.data_type()));
+            nullables.push(field.is_nullable());
+        }
+    }
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("operator_id", DataType::Utf8, false),
+        Field::new("operator_kind", DataType::Utf8, false),
+        Field::new("field_name", DataType::Utf8, false),
+        Field::new("data_type", DataType::Utf8, false),
+        Field::new("nullable", DataType::Boolean, false),
+    ]));
+
+    Ok(RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(StringArray::from(operator_ids)),
+            Arc::new(StringArray::from(operator_kinds)),
+            Arc::new(StringArray::from(field_names)),
+            Arc::new(StringArray::from(data_types)),
+            Arc::new(BooleanArray::from(nullables)),
+        ],
+    )?)
+}
+
+/// Read-only `SchemaProvider` exposing the operator metadata table built by
+/// [`columns_batch`] as `schema.columns`, so it can be queried alongside
+/// `input_data` from `SqlTransform`'s `SessionContext`
+struct MetadataSchemaProvider {
+    columns_table: Arc<dyn TableProvider>,
+}
+
+impl MetadataSchemaProvider {
+    fn try_new(operators: &[(&str, &dyn Operator)]) -> anyhow::Result<Self> {
+        let batch = columns_batch(operators)?;
+        let table = MemTable::try_new(batch.schema(), vec![vec![batch]])?;
+        Ok(Self {
+            columns_table: Arc::new(table),
+        })
+    }
+}
+
+#[async_trait]
+impl SchemaProvider for MetadataSchemaProvider {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        vec!["columns".to_string()]
+    }
+
+    async fn table(&self, name: &str) -> datafusion::error::Result<Option<Arc<dyn TableProvider>>> {
+        Ok((name == "columns").then(|| self.columns_table.clone()))
+    }
+
+    fn register_table(
+        &self,
+        name: String,
+        _table: Arc<dyn TableProvider>,
+    ) -> datafusion::error::Result<Option<Arc<dyn TableProvider>>> {
+        Err(datafusion::error::DataFusionError::Execution(format!(
+            "MetadataSchemaProvider is read-only, cannot register table '{name}'"
+        )))
+    }
+
+    fn deregister_table(&self, name: &str) -> datafusion::error::Result<Option<Arc<dyn TableProvider>>> {
+        Err(datafusion::error::DataFusionError::Execution(format!(
+            "MetadataSchemaProvider is read-only, cannot deregister table '{name}'"
+        )))
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        name == "columns"
+    }
+}
+
+/// Read-only `CatalogProvider` holding a single `schema` schema, so operator
+/// metadata is queryable as `rde.schema.columns`
+struct MetadataCatalogProvider {
+    schema: Arc<MetadataSchemaProvider>,
+}
+
+impl CatalogProvider for MetadataCatalogProvider {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn schema_names(&self) -> Vec<String> {
+        vec!["schema".to_string()]
+    }
+
+    fn schema(&self, name: &str) -> Option<Arc<dyn SchemaProvider>> {
+        (name == "schema").then(|| self.schema.clone() as Arc<dyn SchemaProvider>)
+    }
+}
+
+/// Query shape recognized by [`analyze_incremental_query`] for incremental
+/// maintenance. `RowByRow` covers plain filter/projection queries, which are
+/// already naturally incremental: each batch can simply be re-run through
+/// [`SqlTransform::execute_sql_query`] in isolation. `Aggregated` covers
+/// single-level `GROUP BY` aggregations, which need running state.
+#[derive(Debug, Clone)]
+enum IncrementalShape {
+    RowByRow,
+    Aggregated(IncrementalPlan),
+}
+
+/// Parsed shape of a `GROUP BY` query recognized for incremental maintenance
+#[derive(Debug, Clone)]
+struct IncrementalPlan {
+    group_by: Vec<String>,
+    aggregates: Vec<IncrementalAggregate>,
+}
+
+/// One aggregate expression from the `SELECT` list, e.g. `SUM(amount) AS total`
+#[derive(Debug, Clone)]
+struct IncrementalAggregate {
+    alias: String,
+    func: AggFunc,
+    arg_column: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum AggFunc {
+    Count,
+    Sum,
+    Min,
+    Max,
+    Avg,
+}
+
+/// Running accumulator state for one group key, one entry per aggregate in
+/// `IncrementalPlan::aggregates`, same order
+#[derive(Debug, Clone)]
+struct AggState(Vec<AggAccumulator>);
+
+#[derive(Debug, Clone, Copy)]
+enum AggAccumulator {
+    Count(i64),
+    Sum { sum: f64, seen: bool },
+    Min { min: f64, seen: bool },
+    Max { max: f64, seen: bool },
+    Avg { sum: f64, count: i64 },
+}
+
+impl AggAccumulator {
+    fn new(func: AggFunc) -> Self {
+        match func {
+            AggFunc::Count => AggAccumulator::Count(0),
+            AggFunc::Sum => AggAccumulator::Sum { sum: 0.0, seen: false },
+            AggFunc::Min => AggAccumulator::Min { min: f64::INFINITY, seen: false },
+            AggFunc::Max => AggAccumulator::Max { max: f64::NEG_INFINITY, seen: false },
+            AggFunc::Avg => AggAccumulator::Avg { sum: 0.0, count: 0 },
+        }
+    }
+
+    /// `value` is `None` for a null input row (skipped by every accumulator
+    /// except `Count`, which counts non-null rows for `COUNT(col)` and every
+    /// row for `COUNT(*)` — the caller passes `Some(0.0)` for `COUNT(*)`)
+    fn update(&mut self, value: Option<f64>) {
+        match self {
+            AggAccumulator::Count(n) => {
+                if value.is_some() {
+                    *n += 1;
+                }
+            }
+            AggAccumulator::Sum { sum, seen } => {
+                if let Some(v) = value {
+                    *sum += v;
+                    *seen = true;
+                }
+            }
+            AggAccumulator::Min { min, seen } => {
+                if let Some(v) = value {
+                    *min = min.min(v);
+                    *seen = true;
+                }
+            }
+            AggAccumulator::Max { max, seen } => {
+                if let Some(v) = value {
+                    *max = max.max(v);
+                    *seen = true;
+                }
+            }
+            AggAccumulator::Avg { sum, count } => {
+                if let Some(v) = value {
+                    *sum += v;
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    /// `COUNT` is the one aggregate SQL never nulls out (an empty/all-null
+    /// group counts to zero); every other aggregate over a group with no
+    /// non-null input matches DataFusion's real aggregation and returns
+    /// `NULL`, so the incremental and batched-recompute paths agree.
+    fn value(&self) -> Option<f64> {
+        match *self {
+            AggAccumulator::Count(n) => Some(n as f64),
+            AggAccumulator::Sum { sum, seen } => seen.then_some(sum),
+            AggAccumulator::Min { min, seen } => seen.then_some(min),
+            AggAccumulator::Max { max, seen } => seen.then_some(max),
+            AggAccumulator::Avg { sum, count } => (count > 0).then_some(sum / count as f64),
+        }
+    }
+}
+
+/// Recognizes whether `query` matches an incremental-maintainable shape.
+/// This is a deliberately narrow text scan rather than a full SQL parser: it
+/// rejects any construct (`JOIN`, `HAVING`, `DISTINCT`, `ORDER BY`, `LIMIT`,
+/// window functions) it can't reason about incrementally, and falls back to
+/// `None` (meaning: use the batched recompute-from-scratch path) rather than
+/// risk misinterpreting the query. A `WHERE` clause is fine on its own (a
+/// plain filter is `RowByRow`, which re-runs the real query per batch) but
+/// is rejected when paired with `GROUP BY`: [`SqlTransform::apply_incremental_batch`]
+/// has no predicate-evaluation step, so an aggregated incremental query with
+/// a filter would silently fold in rows the filter should have excluded.
+fn analyze_incremental_query(query: &str) -> Option<IncrementalShape> {
+    let upper = query.to_uppercase();
+    for reject in ["JOIN", "HAVING", "DISTINCT", "ORDER BY", "LIMIT", "OVER("] {
+        if upper.contains(reject) {
+            return None;
+        }
+    }
+
+    let group_by_pos = upper.find("GROUP BY");
+    let Some(group_by_pos) = group_by_pos else {
+        return Some(IncrementalShape::RowByRow);
+    };
+
+    let select_pos = upper.find("SELECT")?;
+    let from_pos = upper.find(" FROM ")?;
+    if select_pos > from_pos || from_pos > group_by_pos {
+        return None;
+    }
+    if let Some(where_pos) = upper.find(" WHERE ") {
+        if where_pos > from_pos && where_pos < group_by_pos {
+            return None;
+        }
+    }
+
+    let select_list = &query[select_pos + "SELECT".len()..from_pos];
+    let group_by_list = &query[group_by_pos + "GROUP BY".len()..];
+
+    let group_by: Vec<String> = group_by_list
+        .split(',')
+        .map(|c| c.trim().trim_end_matches(';').to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+    if group_by.is_empty() {
+        return None;
+    }
+
+    let mut aggregates = Vec::new();
+    for item in select_list.split(',') {
+        let item = item.trim();
+        if item.is_empty() {
+            continue;
+        }
+        if group_by.iter().any(|g| g.eq_ignore_ascii_case(item)) {
+            continue;
+        }
+        aggregates.push(parse_aggregate_item(item)?);
+    }
+    if aggregates.is_empty() {
+        return None;
+    }
+
+    Some(IncrementalShape::Aggregated(IncrementalPlan { group_by, aggregates }))
+}
+
+/// Parses one `SELECT`-list item into an [`IncrementalAggregate`], e.g.
+/// `COUNT(*) AS n` or `SUM(amount)` (alias defaults to the full expression
+/// text). Returns `None` for anything that isn't a bare recognized aggregate
+/// call, which bubbles up as "unsupported query shape" in the caller.
+fn parse_aggregate_item(item: &str) -> Option<IncrementalAggregate> {
+    let upper = item.to_uppercase();
+    let (expr_part, alias) = match upper.find(" AS ") {
+        Some(pos) => (&item[..pos], item[pos + " AS ".len()..].trim().to_string()),
+        None => (item, item.to_string()),
+    };
+    let expr_part = expr_part.trim();
+    let expr_upper = expr_part.to_uppercase();
+
+    let (func, rest) = if let Some(rest) = expr_upper.strip_prefix("COUNT(") {
+        (AggFunc::Count, rest)
+    } else if let Some(rest) = expr_upper.strip_prefix("SUM(") {
+        (AggFunc::Sum, rest)
+    } else if let Some(rest) = expr_upper.strip_prefix("MIN(") {
+        (AggFunc::Min, rest)
+    } else if let Some(rest) = expr_upper.strip_prefix("MAX(") {
+        (AggFunc::Max, rest)
+    } else {
+        return None;
+    };
+    let arg = rest.strip_suffix(')')?.trim();
+    let arg_column = if arg == "*" {
+        "*".to_string()
+    } else {
+        // Recover original casing from `expr_part` rather than `expr_upper`
+        let open = expr_part.find('(')? + 1;
+        expr_part[open..expr_part.len() - 1].trim().to_string()
+    };
+
+    Some(IncrementalAggregate { alias, func, arg_column })
+}
+
+/// Parses `query` with `sqlparser`, rejecting multi-statement input and
+/// anything other than a single `SELECT`, then verifies every column it can
+/// confidently identify as referencing `input_schema` actually exists there.
+/// Returns the referenced-column set so the caller can project batches down
+/// to just those columns before registering/executing, or `None` when the
+/// query shape (wildcard, join, subquery, set operation, ...) makes that
+/// extraction unsafe to attempt — such queries still get the statement-count
+/// and statement-kind checks above, but no column-existence check and no
+/// projection pushdown.
+fn parse_and_validate_query(query: &str, input_schema: &SchemaRef) -> anyhow::Result<Option<Vec<String>>> {
+    use datafusion::sql::sqlparser::dialect::GenericDialect;
+    use datafusion::sql::sqlparser::parser::Parser as SqlParser;
+
+    let statements = SqlParser::parse_sql(&GenericDialect {}, query)
+        .with_context(|| format!("SqlTransform: failed to parse query: {query}"))?;
+    if statements.len() != 1 {
+        anyhow::bail!("SqlTransform: query must be a single SQL statement, found {}", statements.len());
+    }
+    let datafusion::sql::sqlparser::ast::Statement::Query(parsed_query) = &statements[0] else {
+        anyhow::bail!("SqlTransform: query must be a single SELECT statement");
+    };
+
+    let referenced_columns = extract_referenced_columns(query, parsed_query);
+
+    if let Some(columns) = &referenced_columns {
+        for column in columns {
+            input_schema
+                .index_of(column)
+                .with_context(|| format!("SqlTransform: query references unknown column '{column}'"))?;
+        }
+    }
+
+    Ok(referenced_columns)
+}
+
+/// Best-effort extraction of the base columns `query` references against the
+/// single `input_data` table, for projection pushdown. Deliberately
+/// conservative: bails to `None` on anything it isn't sure it's reading
+/// correctly (joins, wildcards, set operations, CTEs, function calls,
+/// subqueries, ...) rather than risk silently dropping a column the query
+/// actually needs.
+fn extract_referenced_columns(
+    query_text: &str,
+    query: &datafusion::sql::sqlparser::ast::Query,
+) -> Option<Vec<String>> {
+    use datafusion::sql::sqlparser::ast::{SelectItem, SetExpr, TableFactor};
+
+    let upper = query_text.to_uppercase();
+    for reject in ["GROUP BY", "HAVING", "ORDER BY", "JOIN", "UNION", "WITH "] {
+        if upper.contains(reject) {
+            return None;
+        }
+    }
+
+    if query.with.is_some() {
+        return None;
+    }
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return None;
+    };
+    if select.from.len() != 1 || !select.from[0].joins.is_empty() {
+        return None;
+    }
+    let TableFactor::Table { name, .. } = &select.from[0].relation else {
+        return None;
+    };
+    if !name.to_string().eq_ignore_ascii_case("input_data") {
+        // Queries against the operator catalog or a registered reference
+        // table aren't shaped against `input_schema`
+        return None;
+    }
+
+    let mut columns = std::collections::BTreeSet::new();
+    for item in &select.projection {
+        let expr = match item {
+            SelectItem::UnnamedExpr(expr) => expr,
+            SelectItem::ExprWithAlias { expr, .. } => expr,
+            SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(_, _) => return None,
+        };
+        if !collect_columns(expr, &mut columns) {
+            return None;
+        }
+    }
+    if let Some(selection) = &select.selection {
+        if !collect_columns(selection, &mut columns) {
+            return None;
+        }
+    }
+
+    Some(columns.into_iter().collect())
+}
+
+/// Walks `expr` collecting plain column references into `columns`, returning
+/// `false` for any construct not explicitly handled (function calls, casts,
+/// subqueries, `CASE`, ...) so the caller can fall back to no pruning rather
+/// than guess.
+fn collect_columns(expr: &datafusion::sql::sqlparser::ast::Expr, columns: &mut std::collections::BTreeSet<String>) -> bool {
+    use datafusion::sql::sqlparser::ast::Expr;
+    match expr {
+        Expr::Identifier(ident) => {
+            columns.insert(ident.value.clone());
+            true
+        }
+        Expr::CompoundIdentifier(idents) => match idents.last() {
+            Some(ident) => {
+                columns.insert(ident.value.clone());
+                true
+            }
+            None => false,
+        },
+        Expr::Value(_) => true,
+        Expr::BinaryOp { left, right, .. } => collect_columns(left, columns) && collect_columns(right, columns),
+        Expr::UnaryOp { expr, .. } => collect_columns(expr, columns),
+        Expr::Nested(expr) => collect_columns(expr, columns),
+        Expr::IsNull(expr) | Expr::IsNotNull(expr) => collect_columns(expr, columns),
+        Expr::Between { expr, low, high, .. } => {
+            collect_columns(expr, columns) && collect_columns(low, columns) && collect_columns(high, columns)
+        }
+        Expr::InList { expr, list, .. } => collect_columns(expr, columns) && list.iter().all(|e| collect_columns(e, columns)),
+        _ => false,
+    }
+}
+
+/// SQL transformation using DataFusion
+///
+/// Buffers incoming batches and periodically runs `query` over them. Without
+/// a `window`, buffering is purely count-based (`window_size` batches per
+/// flush). With a `window` and `timestamp_column` set, rows are instead
+/// assigned to event-time panes keyed by the window they close into, and a
+/// pane is only queried once a `Message::Watermark` reaches its end
+/// (window end `<=` watermark).
+pub struct SqlTransform {
+    id: String,
+    schema: SchemaRef,
+    query: String,
+    window_size: usize,
+    window: Option<rde_core::WindowSpec>,
+    timestamp_column: Option<String>,
+    ctx: datafusion::prelude::SessionContext,
+    /// Event-time panes, keyed by window end (exclusive), awaiting a
+    /// watermark past their end before being queried
+    panes: std::collections::BTreeMap<i64, Vec<RecordBatch>>,
+    /// Columns `query` was found to reference against `input_data`, computed
+    /// once at construction by [`parse_and_validate_query`]; `None` if the
+    /// query's shape made that extraction unsafe, in which case batches pass
+    /// through unpruned
+    projected_columns: Option<Vec<String>>,
+    incremental: bool,
+    /// The query's recognized incremental shape, computed once by
+    /// `with_incremental`; `None` if incremental mode is off, or if it's on
+    /// but the query doesn't match a shape `analyze_incremental_query`
+    /// recognizes (in which case `run` falls back to the batched path)
+    incremental_shape: Option<IncrementalShape>,
+    /// Running per-group-key aggregator state for `IncrementalShape::Aggregated`
+    incremental_state: HashMap<Vec<datafusion::scalar::ScalarValue>, AggState>,
+}
+
+impl SqlTransform {
+    pub fn new(id: String, schema: SchemaRef, query: String, window_size: usize) -> anyhow::Result<Self> {
+        Self::with_window(id, schema, query, window_size, None, None)
+    }
+
+    pub fn with_window(
+        id: String,
+        schema: SchemaRef,
+        query: String,
+        window_size: usize,
+        window: Option<rde_core::WindowSpec>,
+        timestamp_column: Option<String>,
+    ) -> anyhow::Result<Self> {
+        let projected_columns = parse_and_validate_query(&query, &schema)?;
+        let ctx = datafusion::prelude::SessionContext::new();
+
+        Ok(Self {
+            id,
+            schema,
+            query,
+            window_size,
+            window,
+            timestamp_column,
+            ctx,
+            panes: std::collections::BTreeMap::new(),
+            projected_columns,
+            incremental: false,
+            incremental_shape: None,
+            incremental_state: HashMap::new(),
+        })
+    }
+
+    /// Enables incremental maintenance for query shapes
+    /// [`analyze_incremental_query`] recognizes: plain filter/projection
+    /// queries (no state needed, each batch is self-contained) and single-
+    /// level `GROUP BY` aggregations using only `COUNT`/`SUM`/`MIN`/`MAX`
+    /// (maintained in `self.incremental_state` and emitted as deltas). Any
+    /// other query shape falls back to [`Transform::run`]'s batched
+    /// recompute-from-scratch path.
+    pub fn with_incremental(mut self, incremental: bool) -> Self {
+        self.incremental_shape = if incremental { analyze_incremental_query(&self.query) } else { None };
+        self.incremental = incremental;
+        self
+    }
+
+    /// Registers a scalar UDF (e.g. geo, parsing, enrichment helpers) for
+    /// `query` to call. Survives the per-batch `input_data` re-registration
+    /// in [`Self::execute_sql_query`] since it lives under its own name.
+    pub fn with_scalar_udf(self, udf: datafusion::logical_expr::ScalarUDF) -> Self {
+        self.ctx.register_udf(udf);
+        self
+    }
+
+    /// Registers an aggregate UDF for `query` to call
+    pub fn with_aggregate_udf(self, udf: datafusion::logical_expr::AggregateUDF) -> Self {
+        self.ctx.register_udaf(udf);
+        self
+    }
+
+    /// Registers a static reference/dimension table that `query` can join
+    /// against, under `name`
+    pub fn with_reference_table(self, name: impl Into<String>, batch: RecordBatch) -> anyhow::Result<Self> {
+        self.ctx.register_batch(&name.into(), batch)?;
+        Ok(self)
+    }
+
+    /// Registers a static reference/dimension table read from a CSV file,
+    /// for `query` to join against under `name`
+    pub async fn with_reference_csv(self, name: impl Into<String>, path: impl AsRef<str>) -> anyhow::Result<Self> {
+        self.ctx
+            .register_csv(&name.into(), path.as_ref(), datafusion::prelude::CsvReadOptions::default())
+            .await?;
+        Ok(self)
+    }
+
+    /// Registers an `rde` catalog exposing `rde.schema.columns`, an
+    /// `information_schema`-style table listing every field (name, data
+    /// type, nullability) of each operator in `operators`, built from their
+    /// [`Operator::name`]/[`Operator::schema`]. Queries can then introspect
+    /// the pipeline's operators, e.g. `SELECT * FROM rde.schema.columns
+    /// WHERE operator_id = '...'`. This is a snapshot taken at call time;
+    /// call it again after the pipeline topology changes to refresh it.
+    pub fn with_operator_catalog(self, operators: &[(&str, &dyn Operator)]) -> anyhow::Result<Self> {
+        let schema = Arc::new(MetadataSchemaProvider::try_new(operators)?);
+        self.ctx.register_catalog("rde", Arc::new(MetadataCatalogProvider { schema }));
+        Ok(self)
+    }
+
+    /// Assigns `batch`'s rows to the event-time panes they fall into
+    /// (possibly more than one pane per row for sliding windows), appending
+    /// one sub-batch per pane to `self.panes`
+    fn assign_to_panes(&mut self, batch: &RecordBatch) -> anyhow::Result<()> {
+        let (window, timestamp_column) = match (&self.window, &self.timestamp_column) {
+            (Some(w), Some(col)) => (w, col),
+            _ => anyhow::bail!("SqlTransform: assign_to_panes called without window/timestamp_column configured"),
+        };
+        let (size_ms, slide_ms) = window.size_and_slide_ms();
+        let ts_col_idx = batch
+            .schema()
+            .index_of(timestamp_column)
+            .with_context(|| format!("SqlTransform: unknown timestamp column '{timestamp_column}'"))?;
+        let ts_array = batch.column(ts_col_idx);
+
+        let mut rows_by_window_end: HashMap<i64, Vec<u32>> = HashMap::new();
+        for row_idx in 0..batch.num_rows() {
+            let ts = array_value_to_json(ts_array, row_idx)?
+                .as_i64()
+                .with_context(|| format!("SqlTransform: '{timestamp_column}' is not an integer timestamp"))?;
+            for window_end in window_ends_for(ts, size_ms, slide_ms) {
+                rows_by_window_end.entry(window_end).or_default().push(row_idx as u32);
+            }
+        }
+
+        for (window_end, indices) in rows_by_window_end {
+            let index_array = datafusion::arrow::array::UInt32Array::from(indices);
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|col| datafusion::arrow::compute::take(col, &index_array, None))
+                .collect::<Result<Vec<ArrayRef>, _>>()?;
+            let pane_batch = RecordBatch::try_new(batch.schema(), columns)?;
+            self.panes.entry(window_end).or_default().push(pane_batch);
+        }
+        Ok(())
+    }
+
+    /// Drains every pane whose window has closed below `watermark` (or
+    /// every pane, if `watermark` is `None`, for EOS/final flush), combining
+    /// and querying each in window-end order
+    async fn drain_closed_panes(&mut self, watermark: Option<i64>) -> anyhow::Result<Vec<RecordBatch>> {
+        let closed: Vec<i64> = self
+            .panes
+            .range(..)
+            .map(|(&end, _)| end)
+            .filter(|&end| watermark.map(|w| end <= w).unwrap_or(true))
+            .collect();
+
+        let mut results = Vec::with_capacity(closed.len());
+        for window_end in closed {
+            if let Some(batches) = self.panes.remove(&window_end) {
+                let combined = self.combine_batches(&batches)?;
+                results.push(self.execute_sql_query(&combined).await?);
+            }
+        }
+        Ok(results)
+    }
+
+    // `input_data` is a single already-decoded `RecordBatch` handed to this
+    // transform by its upstream operator, not a scan against an external
+    // Iceberg table — there's nothing behind it for a `TableProvider::scan`
+    // with `supports_filters_pushdown` to avoid reading. Column pruning is
+    // instead done upfront via `project_to_referenced_columns`, the same
+    // goal a pushdown `TableProvider` would serve, scoped to what this
+    // architecture can actually act on (won't-do beyond that: there is no
+    // Iceberg-backed `TableProvider` for a per-batch streaming transform to
+    // wrap).
+    async fn execute_sql_query(&self, batch: &RecordBatch) -> anyhow::Result<RecordBatch> {
+        // Register the batch as a temporary table, pruned down to only the
+        // columns `self.query` was found to reference (see
+        // `parse_and_validate_query`/`projected_columns`) when that's known
+        let projected = self.project_to_referenced_columns(batch)?;
+        let table_name = "input_data";
+        // Convert our RecordBatch to DataFusion's RecordBatch
+        let df_batch = datafusion::arrow::array::RecordBatch::try_new(
+            projected.schema().clone(),
+            projected.columns().to_vec(),
+        )?;
+        self.ctx.register_batch(table_name, df_batch)?;
+        
+        // Execute the SQL query
+        let df = self.ctx.sql(&self.query).await?;
+        let output_schema = Arc::new(df.schema().as_arrow().clone());
+        let result = df.collect().await?;
+
+        if result.is_empty() {
+            // Return empty batch with same schema
+            return Ok(RecordBatch::new_empty(self.schema.clone()));
+        }
+
+        // `df.collect()` can split its output across multiple batches (one
+        // per DataFusion partition/poll, not necessarily one per logical
+        // result) -- concatenate them all rather than keeping only the
+        // first, or non-trivial queries would silently drop rows.
+        Ok(datafusion::arrow::compute::concat_batches(&output_schema, &result)?)
+    }
+
+    /// Projects `batch` down to `self.projected_columns` when it's known,
+    /// so `combine_batches`'s concatenation work and DataFusion's own
+    /// execution only ever materialize columns `self.query` actually uses
+    fn project_to_referenced_columns(&self, batch: &RecordBatch) -> anyhow::Result<RecordBatch> {
+        let Some(columns) = &self.projected_columns else {
+            return Ok(batch.clone());
+        };
+        let indices = columns
+            .iter()
+            .map(|name| {
+                batch
+                    .schema()
+                    .index_of(name)
+                    .with_context(|| format!("SqlTransform: projected column '{name}' missing from incoming batch"))
+            })
+            .collect::<anyhow::Result<Vec<usize>>>()?;
+        Ok(batch.project(&indices)?)
+    }
+}
+
+#[async_trait]
+impl Operator for SqlTransform {
+    fn name(&self) -> &str {
+        &self.id
+    }
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[async_trait]
+impl Transform for SqlTransform {
+    async fn run(
+        &mut self,
+        mut rx: BatchRx,
+        tx: BatchTx,
+        _cancel: CancellationToken,
+    ) -> anyhow::Result<()> {
+        info!("SqlTransform started with query: {}", self.query);
+
+        if self.incremental {
+            match self.incremental_shape.clone() {
+                Some(shape) => return self.run_incremental(rx, tx, shape).await,
+                None => warn!(
+                    "SqlTransform: incremental mode requested but query shape isn't supported, falling back to batched recompute"
+                ),
+            }
+        }
+
+        if self.window.is_some() && self.timestamp_column.is_some() {
+            return self.run_windowed(rx, tx).await;
+        }
+
+        let mut batch_buffer = Vec::new();
+
+        while let Some(msg) = rx.recv().await {
+            match &msg {
+                Message::Batch(batch) => {
+                    info!("SqlTransform: received batch with {} rows", batch.num_rows());
+                    batch_buffer.push(self.project_to_referenced_columns(batch)?);
+
+                    // Process when we have enough data or on watermark
+                    if batch_buffer.len() >= self.window_size {
+                        let combined_batch = self.combine_batches(&batch_buffer)?;
+                        let transformed_batch = self.execute_sql_query(&combined_batch).await?;
+
+                        if tx.send(Message::Batch(transformed_batch)).await.is_err() {
+                            error!("SqlTransform: failed to send transformed batch");
+                            break;
+                        }
+
+                        batch_buffer.clear();
+                    }
+                }
+                Message::Watermark(_) => {
+                    info!("SqlTransform: received watermark");
+
+                    // Process remaining data
+                    if !batch_buffer.is_empty() {
+                        let combined_batch = self.combine_batches(&batch_buffer)?;
+                        let transformed_batch = self.execute_sql_query(&combined_batch).await?;
+
+                        if tx.send(Message::Batch(transformed_batch)).await.is_err() {
+                            error!("SqlTransform: failed to send transformed batch");
+                            break;
+                        }
+
+                        batch_buffer.clear();
+                    }
+
+                    if tx.send(msg).await.is_err() {
+                        break;
+                    }
+                }
+                Message::Eos => {
+                    info!("SqlTransform: received EOS");
+
+                    // Process remaining data
+                    if !batch_buffer.is_empty() {
+                        let combined_batch = self.combine_batches(&batch_buffer)?;
+                        let transformed_batch = self.execute_sql_query(&combined_batch).await?;
+
+                        if tx.send(Message::Batch(transformed_batch)).await.is_err() {
+                            error!("SqlTransform: failed to send transformed batch");
+                            break;
+                        }
+                    }
+
+                    if tx.send(msg).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        info!("SqlTransform finished");
+        Ok(())
+    }
+}
+
+impl SqlTransform {
+    /// Event-time windowed run loop, used instead of [`Transform::run`]'s
+    /// count-based buffering when `window`/`timestamp_column` are configured
+    async fn run_windowed(&mut self, mut rx: BatchRx, tx: BatchTx) -> anyhow::Result<()> {
+        while let Some(msg) = rx.recv().await {
+            match msg {
+                Message::Batch(batch) => {
+                    info!("SqlTransform: received batch with {} rows for windowing", batch.num_rows());
+                    self.assign_to_panes(&batch)?;
+                }
+                Message::Watermark(w) => {
+                    info!("SqlTransform: watermark {} closing panes up to it", w);
+                    for result in self.drain_closed_panes(Some(w)).await? {
+                        if tx.send(Message::Batch(result)).await.is_err() {
+                            error!("SqlTransform: failed to send windowed result batch");
+                            return Ok(());
+                        }
+                    }
+                    if tx.send(Message::Watermark(w)).await.is_err() {
+                        break;
+                    }
+                }
+                Message::Eos => {
+                    info!("SqlTransform: EOS, closing all remaining panes");
+                    for result in self.drain_closed_panes(None).await? {
+                        if tx.send(Message::Batch(result)).await.is_err() {
+                            error!("SqlTransform: failed to send windowed result batch");
+                            return Ok(());
+                        }
+                    }
+                    let _ = tx.send(Message::Eos).await;
+                    break;
+                }
+            }
+        }
+
+        info!("SqlTransform finished");
+        Ok(())
+    }
+}
+
+impl SqlTransform {
+    /// Incremental run loop, used instead of [`Transform::run`]'s
+    /// buffer-and-recompute path when `incremental` is enabled and `shape`
+    /// was recognized by [`analyze_incremental_query`].
+    async fn run_incremental(&mut self, mut rx: BatchRx, tx: BatchTx, shape: IncrementalShape) -> anyhow::Result<()> {
+        while let Some(msg) = rx.recv().await {
+            match msg {
+                Message::Batch(batch) => {
+                    info!("SqlTransform: received batch with {} rows for incremental maintenance", batch.num_rows());
+                    let delta = match &shape {
+                        IncrementalShape::RowByRow => Some(self.execute_sql_query(&batch).await?),
+                        IncrementalShape::Aggregated(plan) => self.apply_incremental_batch(plan, &batch)?,
+                    };
+                    if let Some(delta) = delta {
+                        if tx.send(Message::Batch(delta)).await.is_err() {
+                            error!("SqlTransform: failed to send incremental delta batch");
+                            break;
+                        }
+                    }
+                }
+                Message::Watermark(w) => {
+                    if tx.send(Message::Watermark(w)).await.is_err() {
+                        break;
+                    }
+                }
+                Message::Eos => {
+                    info!("SqlTransform: EOS, flushing incremental state");
+                    if let IncrementalShape::Aggregated(plan) = &shape {
+                        if let Some(snapshot) = self.flush_incremental_state(plan)? {
+                            if tx.send(Message::Batch(snapshot)).await.is_err() {
+                                error!("SqlTransform: failed to send incremental state flush");
+                                return Ok(());
+                            }
+                        }
+                    }
+                    let _ = tx.send(Message::Eos).await;
+                    break;
+                }
+            }
+        }
+
+        info!("SqlTransform finished");
+        Ok(())
+    }
+
+    /// Updates `self.incremental_state` for every row in `batch` and returns
+    /// a delta batch containing only the groups that changed, tagged via a
+    /// trailing `__op` column (`"insert"` for a group seen for the first
+    /// time, `"update"` otherwise). Returns `Ok(None)` if `batch` is empty.
+    fn apply_incremental_batch(
+        &mut self,
+        plan: &IncrementalPlan,
+        batch: &RecordBatch,
+    ) -> anyhow::Result<Option<RecordBatch>> {
+        if batch.num_rows() == 0 {
+            return Ok(None);
+        }
+
+        let schema = batch.schema();
+        let group_by_idx: Vec<usize> = plan
+            .group_by
+            .iter()
+            .map(|name| schema.index_of(name).with_context(|| format!("incremental GROUP BY column '{name}' not found in input batch")))
+            .collect::<anyhow::Result<_>>()?;
+        // `None` stands for `COUNT(*)`, which has no source column
+        let arg_idx: Vec<Option<usize>> = plan
+            .aggregates
+            .iter()
+            .map(|agg| {
+                if agg.arg_column == "*" {
+                    Ok(None)
+                } else {
+                    schema
+                        .index_of(&agg.arg_column)
+                        .map(Some)
+                        .with_context(|| format!("incremental aggregate column '{}' not found in input batch", agg.arg_column))
+                }
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        let mut touched_keys = Vec::new();
+        let mut newly_inserted = std::collections::HashSet::new();
+
+        for row in 0..batch.num_rows() {
+            let key: Vec<datafusion::scalar::ScalarValue> = group_by_idx
+                .iter()
+                .map(|&col| datafusion::scalar::ScalarValue::try_from_array(batch.column(col), row))
+                .collect::<Result<_, _>>()?;
+
+            let is_new = !self.incremental_state.contains_key(&key);
+            let state = self
+                .incremental_state
+                .entry(key.clone())
+                .or_insert_with(|| AggState(plan.aggregates.iter().map(|agg| AggAccumulator::new(agg.func)).collect()));
+
+            for (acc, col_idx) in state.0.iter_mut().zip(arg_idx.iter()) {
+                let value = match col_idx {
+                    None => Some(0.0), // COUNT(*): every row counts
+                    Some(col) => scalar_to_f64(&datafusion::scalar::ScalarValue::try_from_array(batch.column(*col), row)?),
+                };
+                acc.update(value);
+            }
+
+            if is_new {
+                newly_inserted.insert(touched_keys.len());
+            }
+            if !touched_keys.contains(&key) {
+                touched_keys.push(key);
+            }
+        }
+
+        self.build_delta_batch(plan, &touched_keys, &newly_inserted)
+    }
+
+    /// Builds an output batch for `keys`, resolving each field of
+    /// `self.schema` as either a `GROUP BY` column (via
+    /// `ScalarValue::iter_to_array`) or a recognized aggregate alias (via its
+    /// accumulated `value()`s), with a trailing `__op` column. `newly_inserted`
+    /// holds indices into `keys` that are new groups ("insert"); every other
+    /// index is an existing group that changed ("update").
+    fn build_delta_batch(
+        &self,
+        plan: &IncrementalPlan,
+        keys: &[Vec<datafusion::scalar::ScalarValue>],
+        newly_inserted: &std::collections::HashSet<usize>,
+    ) -> anyhow::Result<Option<RecordBatch>> {
+        if keys.is_empty() {
+            return Ok(None);
+        }
+
+        let mut columns: Vec<ArrayRef> = Vec::with_capacity(self.schema.fields().len() + 1);
+        for field in self.schema.fields() {
+            if let Some(group_idx) = plan.group_by.iter().position(|g| g == field.name()) {
+                let values = keys.iter().map(|key| key[group_idx].clone());
+                columns.push(datafusion::scalar::ScalarValue::iter_to_array(values)?);
+            } else if let Some(agg_idx) = plan.aggregates.iter().position(|agg| &agg.alias == field.name()) {
+                let values: Float64Array = keys.iter().map(|key| self.incremental_state[key].0[agg_idx].value()).collect();
+                columns.push(Arc::new(values));
+            } else {
+                anyhow::bail!("incremental delta: field '{}' is neither a GROUP BY column nor a recognized aggregate alias", field.name());
+            }
+        }
+
+        let ops: StringArray = (0..keys.len())
+            .map(|i| Some(if newly_inserted.contains(&i) { "insert" } else { "update" }))
+            .collect();
+
+        let mut fields: Vec<Field> = self.schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+        fields.push(Field::new("__op", DataType::Utf8, false));
+        columns.push(Arc::new(ops));
+
+        Ok(Some(RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?))
+    }
+
+    /// Dumps the entire running state as a full snapshot (every group tagged
+    /// `"update"`), emitted once on `Message::Eos`.
+    fn flush_incremental_state(&self, plan: &IncrementalPlan) -> anyhow::Result<Option<RecordBatch>> {
+        let keys: Vec<Vec<datafusion::scalar::ScalarValue>> = self.incremental_state.keys().cloned().collect();
+        self.build_delta_batch(plan, &keys, &std::collections::HashSet::new())
+    }
+}
+
+/// Extracts a numeric value from a `ScalarValue` for aggregation, treating
+/// SQL `NULL` as `None` (skipped by every accumulator except `COUNT(*)`)
+fn scalar_to_f64(value: &datafusion::scalar::ScalarValue) -> Option<f64> {
+    use datafusion::scalar::ScalarValue;
+    match value {
+        ScalarValue::Int8(v) => v.map(|v| v as f64),
+        ScalarValue::Int16(v) => v.map(|v| v as f64),
+        ScalarValue::Int32(v) => v.map(|v| v as f64),
+        ScalarValue::Int64(v) => v.map(|v| v as f64),
+        ScalarValue::UInt8(v) => v.map(|v| v as f64),
+        ScalarValue::UInt16(v) => v.map(|v| v as f64),
+        ScalarValue::UInt32(v) => v.map(|v| v as f64),
+        ScalarValue::UInt64(v) => v.map(|v| v as f64),
+        ScalarValue::Float32(v) => v.map(|v| v as f64),
+        ScalarValue::Float64(v) => *v,
+        _ => None,
+    }
+}
+
+impl SqlTransform {
+    /// Concatenates `batches` into one, tolerating schema drift across them:
+    /// builds a merged schema over the union of every batch's columns (see
+    /// [`merge_schemas`]), then for each batch casts columns that are
+    /// present but differently typed, null-fills columns it's missing
+    /// entirely, and concatenates each column across batches.
+    fn combine_batches(&self, batches: &[RecordBatch]) -> anyhow::Result<RecordBatch> {
+        if batches.is_empty() {
+            return Ok(RecordBatch::new_empty(self.schema.clone()));
+        }
+
+        if batches.len() == 1 {
+            return Ok(batches[0].clone());
+        }
+
+        let merged_schema = Arc::new(merge_schemas(batches)?);
+        let mut columns = Vec::with_capacity(merged_schema.fields().len());
+        for field in merged_schema.fields() {
+            let mut arrays = Vec::with_capacity(batches.len());
+            for batch in batches {
+                let array: ArrayRef = match batch.schema().index_of(field.name()) {
+                    Ok(col_idx) => {
+                        let source = batch.column(col_idx);
+                        if source.data_type() == field.data_type() {
+                            source.clone()
+                        } else {
+                            datafusion::arrow::compute::cast(source, field.data_type()).with_context(|| {
+                                format!("SqlTransform: casting column '{}' while combining batches", field.name())
+                            })?
+                        }
+                    }
+                    Err(_) => datafusion::arrow::array::new_null_array(field.data_type(), batch.num_rows()),
+                };
+                arrays.push(array);
+            }
+            let refs: Vec<&dyn datafusion::arrow::array::Array> = arrays.iter().map(|a| a.as_ref()).collect();
+            columns.push(
+                datafusion::arrow::compute::concat(&refs)
+                    .with_context(|| format!("SqlTransform: concatenating column '{}'", field.name()))?,
+            );
+        }
+        Ok(RecordBatch::try_new(merged_schema, columns)?)
+    }
+}
+
+/// Builds a unified schema over every column name seen across `batches`,
+/// widening conflicting types per [`merge_data_type`] and preserving each
+/// column's first-seen order. A column missing from some batch is marked
+/// nullable, since [`SqlTransform::combine_batches`] null-fills it there.
+fn merge_schemas(batches: &[RecordBatch]) -> anyhow::Result<Schema> {
+    let mut order: Vec<String> = Vec::new();
+    let mut merged_type: HashMap<String, DataType> = HashMap::new();
+    let mut any_nullable: HashMap<String, bool> = HashMap::new();
+
+    for batch in batches {
+        for field in batch.schema().fields() {
+            match merged_type.get(field.name()) {
+                Some(existing) => {
+                    let widened = merge_data_type(existing, field.data_type())
+                        .with_context(|| format!("SqlTransform: merging column '{}' across batches", field.name()))?;
+                    merged_type.insert(field.name().clone(), widened);
+                }
+                None => {
+                    order.push(field.name().clone());
+                    merged_type.insert(field.name().clone(), field.data_type().clone());
+                }
+            }
+            *any_nullable.entry(field.name().clone()).or_insert(false) |= field.is_nullable();
+        }
+    }
+
+    let fields = order
+        .into_iter()
+        .map(|name| {
+            let present_everywhere = batches.iter().all(|b| b.schema().index_of(&name).is_ok());
+            let is_nullable = any_nullable[&name] || !present_everywhere;
+            let data_type = merged_type.remove(&name).expect("every ordered field has a merged type");
+            Field::new(name, data_type, is_nullable)
+        })
+        .collect();
+    Ok(Schema::new(fields))
+}
+
+/// Computes the least-general Arrow type covering both `a` and `b`:
+/// integer widths widen to the wider one, any integer/float combination
+/// widens to `Float64`, a `Dictionary`'s value type unwraps when it matches
+/// the other side, and `Struct`s merge member-by-member (recursing into
+/// nested structs via [`merge_struct_fields`]). Bails when the two types
+/// have no common representation.
+fn merge_data_type(a: &DataType, b: &DataType) -> anyhow::Result<DataType> {
+    use DataType::*;
+    if a == b {
+        return Ok(a.clone());
+    }
+    Ok(match (a, b) {
+        (Dictionary(_, value), other) | (other, Dictionary(_, value)) if value.as_ref() == other => other.clone(),
+        (Struct(a_fields), Struct(b_fields)) => Struct(merge_struct_fields(a_fields, b_fields)?),
+        (a, b) if a.is_integer() && b.is_integer() => {
+            if int_width(a) >= int_width(b) {
+                a.clone()
+            } else {
+                b.clone()
+            }
+        }
+        (a, b) if a.is_numeric() && b.is_numeric() => Float64,
+        (a, b) => anyhow::bail!("no common type for {a:?} and {b:?}"),
+    })
+}
+
+/// Bit width of an integer `DataType`, used to pick the wider of two
+/// integer types in [`merge_data_type`]; non-integer types rank 0
+fn int_width(data_type: &DataType) -> u8 {
+    use DataType::*;
+    match data_type {
+        Int8 | UInt8 => 8,
+        Int16 | UInt16 => 16,
+        Int32 | UInt32 => 32,
+        Int64 | UInt64 => 64,
+        _ => 0,
+    }
+}
+
+/// Merges two struct field lists by name, preserving `a`'s field order then
+/// appending any fields only present in `b`, widening shared fields' types
+/// via [`merge_data_type`]
+fn merge_struct_fields(a: &datafusion::arrow::datatypes::Fields, b: &datafusion::arrow::datatypes::Fields) -> anyhow::Result<datafusion::arrow::datatypes::Fields> {
+    let mut order: Vec<String> = Vec::new();
+    let mut by_name: HashMap<String, Field> = HashMap::new();
+
+    for field in a.iter().chain(b.iter()) {
+        match by_name.get(field.name()) {
+            Some(existing) => {
+                let merged_type = merge_data_type(existing.data_type(), field.data_type())?;
+                let nullable = existing.is_nullable() || field.is_nullable();
+                by_name.insert(field.name().clone(), Field::new(field.name(), merged_type, nullable));
+            }
+            None => {
+                order.push(field.name().clone());
+                by_name.insert(field.name().clone(), field.as_ref().clone());
+            }
+        }
+    }
+
+    Ok(order
+        .into_iter()
+        .map(|name| Arc::new(by_name.remove(&name).expect("every ordered field was inserted")))
+        .collect())
+}
+
+/// Data cleaning transform
+pub struct CleanData {
+    id: String,
+    schema: SchemaRef,
+    remove_nulls: bool,
+    remove_nulls_columns: Option<Vec<String>>,
+    trim_strings: bool,
+    normalize_case: Option<String>,
+    normalize_nan_inf: bool,
+}
+
+impl CleanData {
+    pub fn new(
+        id: String,
+        schema: SchemaRef,
+        remove_nulls: bool,
+        remove_nulls_columns: Option<Vec<String>>,
+        trim_strings: bool,
+        normalize_case: Option<String>,
+        normalize_nan_inf: bool,
+    ) -> Self {
+        Self {
+            id,
+            schema,
+            remove_nulls,
+            remove_nulls_columns,
+            trim_strings,
+            normalize_case,
+            normalize_nan_inf,
+        }
+    }
+
+    /// Lowercases/uppercases/title-cases and/or trims a single string value
+    /// per `self.trim_strings`/`self.normalize_case`
+    fn clean_string(&self, s: &str) -> String {
+        let mut cleaned = s.to_string();
+        if self.trim_strings {
+            cleaned = cleaned.trim().to_string();
+        }
+        if let Some(case) = &self.normalize_case {
+            match case.as_str() {
+                "lower" => cleaned = cleaned.to_lowercase(),
+                "upper" => cleaned = cleaned.to_uppercase(),
+                "title" => {
+                    cleaned = cleaned
+                        .split_whitespace()
+                        .map(|word| {
+                            let mut chars = word.chars();
+                            match chars.next() {
+                                None => String::new(),
+                                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                }
+                _ => {}
+            }
+        }
+        cleaned
+    }
+
+    /// Applies [`Self::clean_string`] to every value of a `Utf8`/`LargeUtf8`
+    /// array
+    fn clean_generic_string_array<OffsetSize: datafusion::arrow::array::OffsetSizeTrait>(
+        &self,
+        array: &ArrayRef,
+    ) -> anyhow::Result<ArrayRef> {
+        let string_array = array
+            .as_any()
+            .downcast_ref::<datafusion::arrow::array::GenericStringArray<OffsetSize>>()
+            .context("CleanData: expected a generic string array")?;
+        let cleaned: Vec<Option<String>> = string_array.iter().map(|opt_str| opt_str.map(|s| self.clean_string(s))).collect();
+        Ok(Arc::new(datafusion::arrow::array::GenericStringArray::<OffsetSize>::from(cleaned)))
+    }
+
+    /// Drops every row where any of `self.remove_nulls_columns` (or, if
+    /// unset, any column at all) is null, via
+    /// `arrow::compute::filter_record_batch`
+    fn filter_null_rows(&self, batch: &RecordBatch) -> anyhow::Result<RecordBatch> {
+        let considered: Vec<usize> = match &self.remove_nulls_columns {
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    batch
+                        .schema()
+                        .index_of(name)
+                        .with_context(|| format!("CleanData: unknown remove_nulls column '{name}'"))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            None => (0..batch.num_columns()).collect(),
+        };
+
+        let mut mask = vec![true; batch.num_rows()];
+        for col_idx in considered {
+            let array = batch.column(col_idx);
+            for (row_idx, keep) in mask.iter_mut().enumerate() {
+                if array.is_null(row_idx) {
+                    *keep = false;
+                }
+            }
+        }
+
+        Ok(datafusion::arrow::compute::filter_record_batch(
+            batch,
+            &BooleanArray::from(mask),
+        )?)
+    }
+}
+
+/// Replaces non-finite (`NaN`, `+Infinity`, `-Infinity`) values with null
+fn normalize_nan_inf_f64(array: &ArrayRef) -> ArrayRef {
+    let arr = array.as_any().downcast_ref::<Float64Array>().unwrap();
+    Arc::new(Float64Array::from(arr.iter().map(|v| v.filter(|x| x.is_finite())).collect::<Vec<_>>()))
+}
+
+/// Replaces non-finite (`NaN`, `+Infinity`, `-Infinity`) values with null
+fn normalize_nan_inf_f32(array: &ArrayRef) -> ArrayRef {
+    let arr = array.as_any().downcast_ref::<Float32Array>().unwrap();
+    Arc::new(Float32Array::from(arr.iter().map(|v| v.filter(|x| x.is_finite())).collect::<Vec<_>>()))
+}
+
+#[async_trait]
+impl Operator for CleanData {
+    fn name(&self) -> &str {
+        &self.id
+    }
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[async_trait]
+impl Transform for CleanData {
+    async fn run(
+        &mut self,
+        mut rx: BatchRx,
+        tx: BatchTx,
+        _cancel: CancellationToken,
+    ) -> anyhow::Result<()> {
+        info!("CleanData transform started");
+        while let Some(msg) = rx.recv().await {
+            match &msg {
+                Message::Batch(batch) => {
+                    info!("CleanData: processing batch with {} rows", batch.num_rows());
+                    
+                    let cleaned_batch = self.clean_batch(batch)?;
                     let cleaned_msg = Message::Batch(cleaned_batch);
                     
                     if tx.send(cleaned_msg).await.is_err() {
@@ -839,69 +2706,72 @@ impl CleanData {
     fn clean_batch(&self, batch: &RecordBatch) -> anyhow::Result<RecordBatch> {
         let mut cleaned_columns = Vec::new();
         let mut cleaned_fields = Vec::new();
-        
+
         for (col_idx, field) in batch.schema().fields().iter().enumerate() {
             let array = batch.column(col_idx);
             let cleaned_array = self.clean_array(array, field)?;
             cleaned_columns.push(cleaned_array);
             cleaned_fields.push(field.clone());
         }
-        
+
         let cleaned_schema = Arc::new(Schema::new(cleaned_fields));
-        Ok(RecordBatch::try_new(cleaned_schema, cleaned_columns)?)
+        let cleaned_batch = RecordBatch::try_new(cleaned_schema, cleaned_columns)?;
+
+        if self.remove_nulls {
+            self.filter_null_rows(&cleaned_batch)
+        } else {
+            Ok(cleaned_batch)
+        }
     }
 
     fn clean_array(&self, array: &ArrayRef, field: &Field) -> anyhow::Result<ArrayRef> {
-        // Simplified cleaning - in real implementation, handle all Arrow types
         match field.data_type() {
-            DataType::Utf8 => {
-                if self.trim_strings || self.normalize_case.is_some() {
-                    // Apply string cleaning
-                    let string_array = array.as_any().downcast_ref::<StringArray>().unwrap();
-                    let cleaned_strings: Vec<Option<String>> = string_array
-                        .iter()
-                        .map(|opt_str| {
-                            opt_str.map(|s| {
-                                let mut cleaned = s.to_string();
-                                if self.trim_strings {
-                                    cleaned = cleaned.trim().to_string();
-                                }
-                                if let Some(case) = &self.normalize_case {
-                                    match case.as_str() {
-                                        "lower" => cleaned = cleaned.to_lowercase(),
-                                        "upper" => cleaned = cleaned.to_uppercase(),
-                                        "title" => {
-                                            // Simple title case implementation
-                                            cleaned = cleaned
-                                                .split_whitespace()
-                                                .map(|word| {
-                                                    let mut chars = word.chars();
-                                                    match chars.next() {
-                                                        None => String::new(),
-                                                        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
-                                                    }
-                                                })
-                                                .collect::<Vec<_>>()
-                                                .join(" ");
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                                cleaned
-                            })
-                        })
-                        .collect();
-                    
-                    Ok(Arc::new(StringArray::from(cleaned_strings)))
-                } else {
-                    Ok(array.clone())
-                }
+            DataType::Utf8 if self.trim_strings || self.normalize_case.is_some() => {
+                self.clean_generic_string_array::<i32>(array)
+            }
+            DataType::LargeUtf8 if self.trim_strings || self.normalize_case.is_some() => {
+                self.clean_generic_string_array::<i64>(array)
             }
+            DataType::Dictionary(_, value_type)
+                if value_type.as_ref() == &DataType::Utf8 && (self.trim_strings || self.normalize_case.is_some()) =>
+            {
+                let as_utf8 = datafusion::arrow::compute::cast(array, &DataType::Utf8)
+                    .context("CleanData: casting dictionary column to Utf8 for cleaning")?;
+                let cleaned = self.clean_generic_string_array::<i32>(&as_utf8)?;
+                datafusion::arrow::compute::cast(&cleaned, field.data_type())
+                    .context("CleanData: casting cleaned column back to dictionary")
+            }
+            DataType::Float64 if self.normalize_nan_inf => Ok(normalize_nan_inf_f64(array)),
+            DataType::Float32 if self.normalize_nan_inf => Ok(normalize_nan_inf_f32(array)),
             _ => Ok(array.clone()),
         }
     }
 }
 
+/// Builds the output schema for a `TransformSpec::WindowAggregate`: an
+/// `Int64` `window_end` column, followed by each `group_by` column (keeping
+/// its type from `input_schema`), followed by each aggregate's `alias` as a
+/// nullable `Float64` column. This is what `WindowAggregate::build_window_batch`
+/// expects `schema` to look like; it is not just `input_schema` since the
+/// operator changes row shape entirely (one row per window per group).
+fn window_aggregate_output_schema(
+    input_schema: &SchemaRef,
+    group_by: &[String],
+    aggregates: &[rde_core::WindowAggregateSpec],
+) -> anyhow::Result<SchemaRef> {
+    let mut fields = vec![Field::new("window_end", DataType::Int64, false)];
+    for name in group_by {
+        let input_field = input_schema
+            .field_with_name(name)
+            .with_context(|| format!("window_aggregate: unknown group_by column '{name}'"))?;
+        fields.push(input_field.clone());
+    }
+    for agg in aggregates {
+        fields.push(Field::new(&agg.alias, DataType::Float64, true));
+    }
+    Ok(Arc::new(Schema::new(fields)))
+}
+
 // Factory function to create transforms based on configuration
 pub fn create_transform(
     spec: &rde_core::TransformSpec,
@@ -930,22 +2800,666 @@ pub fn create_transform(
                 partition_format.clone()
             )))
         }
-        rde_core::TransformSpec::SqlTransform { id, query, window_size } => {
-            Ok(Box::new(SqlTransform::new(
-                id.clone(), 
-                input_schema, 
-                query.clone(), 
-                *window_size
+        rde_core::TransformSpec::SqlTransform { id, query, window_size, window, timestamp_column, incremental } => {
+            Ok(Box::new(
+                SqlTransform::with_window(
+                    id.clone(),
+                    input_schema,
+                    query.clone(),
+                    *window_size,
+                    window.clone(),
+                    timestamp_column.clone(),
+                )?
+                .with_incremental(*incremental),
+            ))
+        }
+        rde_core::TransformSpec::CleanData {
+            id,
+            remove_nulls,
+            remove_nulls_columns,
+            trim_strings,
+            normalize_case,
+            normalize_nan_inf,
+        } => Ok(Box::new(CleanData::new(
+            id.clone(),
+            input_schema,
+            *remove_nulls,
+            remove_nulls_columns.clone(),
+            *trim_strings,
+            normalize_case.clone(),
+            *normalize_nan_inf,
+        ))),
+        rde_core::TransformSpec::WindowAggregate { id, timestamp_column, window, group_by, aggregates } => {
+            let output_schema = window_aggregate_output_schema(&input_schema, group_by, aggregates)?;
+            Ok(Box::new(WindowAggregate::new(
+                id.clone(),
+                output_schema,
+                timestamp_column.clone(),
+                window.clone(),
+                group_by.clone(),
+                aggregates,
             )?))
         }
-        rde_core::TransformSpec::CleanData { id, remove_nulls, trim_strings, normalize_case } => {
-            Ok(Box::new(CleanData::new(
-                id.clone(), 
-                input_schema, 
-                *remove_nulls, 
-                *trim_strings, 
-                normalize_case.clone()
-            )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! `Repartition` and `Union` are N-output/N-input respectively, so they
+    //! can't be driven through `create_transform` and the `tests/golden.rs`
+    //! harness built around the single-in/single-out `Transform` trait.
+    //! These tests drive their `run` loops directly over real
+    //! `tokio::sync::mpsc` channels instead, the same way `golden.rs` drives
+    //! `Transform::run`.
+
+    use super::{Repartition, Union};
+    use datafusion::arrow::array::{Int64Array, RecordBatch, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use rde_core::Message;
+    use std::sync::Arc;
+    use tokio_util::sync::CancellationToken;
+
+    fn schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]))
+    }
+
+    fn batch(schema: &Arc<Schema>, ids: &[i64], names: &[&str]) -> RecordBatch {
+        RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from(ids.to_vec())), Arc::new(StringArray::from(names.to_vec()))],
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn repartition_routes_rows_by_key_hash_and_forwards_watermark_and_eos() {
+        let schema = schema();
+        let mut repartition = Repartition::new("repartition-1".to_string(), schema.clone(), vec!["id".to_string()], 3);
+
+        let (in_tx, in_rx) = tokio::sync::mpsc::channel(4);
+        let mut out_rxs = Vec::new();
+        let mut out_txs = Vec::new();
+        for _ in 0..3 {
+            let (tx, rx) = tokio::sync::mpsc::channel(4);
+            out_txs.push(tx);
+            out_rxs.push(rx);
+        }
+
+        let run_handle =
+            tokio::spawn(async move { repartition.run(in_rx, out_txs, CancellationToken::new()).await });
+
+        let input = batch(&schema, &[1, 2, 3, 4], &["a", "b", "c", "d"]);
+        in_tx.send(Message::Batch(input)).await.unwrap();
+        in_tx.send(Message::Watermark(42)).await.unwrap();
+        in_tx.send(Message::Eos).await.unwrap();
+        drop(in_tx);
+
+        run_handle.await.unwrap().unwrap();
+
+        let mut total_rows = 0;
+        for mut rx in out_rxs {
+            let mut saw_watermark = false;
+            let mut saw_eos = false;
+            while let Some(msg) = rx.recv().await {
+                match msg {
+                    Message::Batch(batch) => total_rows += batch.num_rows(),
+                    Message::Watermark(ts) => {
+                        assert_eq!(ts, 42);
+                        saw_watermark = true;
+                    }
+                    Message::Eos => {
+                        saw_eos = true;
+                        break;
+                    }
+                }
+            }
+            assert!(saw_watermark, "every partition should see the watermark");
+            assert!(saw_eos, "every partition should see EOS");
+        }
+        assert_eq!(total_rows, 4, "every input row should land in exactly one partition");
+    }
+
+    #[tokio::test]
+    async fn union_merges_inputs_and_emits_min_watermark() {
+        let schema = schema();
+        let mut union = Union::new("union-1".to_string(), schema.clone(), &[schema.clone(), schema.clone()]).unwrap();
+
+        let (tx_a, rx_a) = tokio::sync::mpsc::channel(4);
+        let (tx_b, rx_b) = tokio::sync::mpsc::channel(4);
+        let (out_tx, mut out_rx) = tokio::sync::mpsc::channel(8);
+
+        let run_handle = tokio::spawn(async move { union.run(vec![rx_a, rx_b], out_tx, CancellationToken::new()).await });
+
+        tx_a.send(Message::Batch(batch(&schema, &[1], &["a"]))).await.unwrap();
+        tx_a.send(Message::Watermark(10)).await.unwrap();
+        tx_b.send(Message::Batch(batch(&schema, &[2], &["b"]))).await.unwrap();
+        tx_b.send(Message::Watermark(5)).await.unwrap();
+        tx_a.send(Message::Eos).await.unwrap();
+        tx_b.send(Message::Eos).await.unwrap();
+        drop(tx_a);
+        drop(tx_b);
+
+        let mut total_rows = 0;
+        let mut watermarks = Vec::new();
+        while let Some(msg) = out_rx.recv().await {
+            match msg {
+                Message::Batch(batch) => total_rows += batch.num_rows(),
+                Message::Watermark(ts) => watermarks.push(ts),
+                Message::Eos => break,
+            }
+        }
+        run_handle.await.unwrap().unwrap();
+
+        assert_eq!(total_rows, 2, "rows from both inputs should be merged");
+        // The output watermark only advances to the minimum across inputs
+        // seen so far (5), never to 10, since input b never emits past 5.
+        assert_eq!(watermarks, vec![5]);
+    }
+}
+
+#[cfg(test)]
+mod incremental_query_analysis_tests {
+    use super::{analyze_incremental_query, IncrementalShape};
+
+    #[test]
+    fn recognizes_plain_filter_as_row_by_row() {
+        assert!(matches!(
+            analyze_incremental_query("SELECT id FROM input_data WHERE amount > 1"),
+            Some(IncrementalShape::RowByRow)
+        ));
+    }
+
+    #[test]
+    fn recognizes_group_by_aggregates() {
+        let shape = analyze_incremental_query("SELECT id, COUNT(*) AS n, SUM(amount) AS total FROM input_data GROUP BY id").unwrap();
+        let IncrementalShape::Aggregated(plan) = shape else { panic!("expected Aggregated shape") };
+        assert_eq!(plan.group_by, vec!["id"]);
+        assert_eq!(plan.aggregates.len(), 2);
+    }
+
+    #[test]
+    fn rejects_joins_and_distinct() {
+        assert!(analyze_incremental_query("SELECT id FROM input_data JOIN labels ON input_data.id = labels.id").is_none());
+        assert!(analyze_incremental_query("SELECT DISTINCT id FROM input_data").is_none());
+    }
+
+    #[test]
+    fn rejects_group_by_with_a_where_clause() {
+        assert!(analyze_incremental_query("SELECT id, COUNT(*) AS n FROM input_data WHERE active GROUP BY id").is_none());
+    }
+}
+
+#[cfg(test)]
+mod incremental_aggregation_tests {
+    use super::{analyze_incremental_query, IncrementalShape, SqlTransform};
+    use datafusion::arrow::array::{Float64Array, Int64Array, RecordBatch, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    fn schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false), Field::new("total", DataType::Float64, true)]))
+    }
+
+    fn transform() -> SqlTransform {
+        SqlTransform::new(
+            "agg".to_string(),
+            schema(),
+            "SELECT id, SUM(amount) AS total FROM input_data GROUP BY id".to_string(),
+            1,
+        )
+        .unwrap()
+        .with_incremental(true)
+    }
+
+    fn plan(t: &SqlTransform) -> super::IncrementalPlan {
+        match analyze_incremental_query(&t.query) {
+            Some(IncrementalShape::Aggregated(plan)) => plan,
+            other => panic!("expected Aggregated shape, got {other:?}"),
         }
     }
+
+    fn batch(ids: Vec<i64>, amounts: Vec<Option<f64>>) -> RecordBatch {
+        RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("id", DataType::Int64, false),
+                Field::new("amount", DataType::Float64, true),
+            ])),
+            vec![Arc::new(Int64Array::from(ids)), Arc::new(Float64Array::from(amounts))],
+        )
+        .unwrap()
+    }
+
+    fn column_as_strings(delta: &RecordBatch, name: &str) -> Vec<String> {
+        let idx = delta.schema().index_of(name).unwrap();
+        delta.column(idx).as_any().downcast_ref::<StringArray>().unwrap().iter().map(|v| v.unwrap().to_string()).collect()
+    }
+
+    #[test]
+    fn first_batch_for_a_key_is_tagged_insert() {
+        let mut t = transform();
+        let p = plan(&t);
+        let delta = t.apply_incremental_batch(&p, &batch(vec![1], vec![Some(10.0)])).unwrap().unwrap();
+        assert_eq!(column_as_strings(&delta, "__op"), vec!["insert"]);
+        let total = delta.column(delta.schema().index_of("total").unwrap()).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(total.value(0), 10.0);
+    }
+
+    #[test]
+    fn state_accumulates_across_batches_and_later_batches_are_updates() {
+        let mut t = transform();
+        let p = plan(&t);
+        t.apply_incremental_batch(&p, &batch(vec![1], vec![Some(10.0)])).unwrap();
+        let delta = t.apply_incremental_batch(&p, &batch(vec![1], vec![Some(5.0)])).unwrap().unwrap();
+        assert_eq!(column_as_strings(&delta, "__op"), vec!["update"]);
+        let total = delta.column(delta.schema().index_of("total").unwrap()).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(total.value(0), 15.0);
+    }
+
+    #[test]
+    fn all_null_group_reports_sql_null_not_a_sentinel() {
+        let mut t = transform();
+        let p = plan(&t);
+        let delta = t.apply_incremental_batch(&p, &batch(vec![1], vec![None])).unwrap().unwrap();
+        let total = delta.column(delta.schema().index_of("total").unwrap()).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert!(total.is_null(0), "expected SUM over an all-null group to be NULL, got {:?}", total.value(0));
+    }
+
+    #[test]
+    fn eos_flush_snapshots_every_group_as_update() {
+        let mut t = transform();
+        let p = plan(&t);
+        t.apply_incremental_batch(&p, &batch(vec![1, 2], vec![Some(10.0), Some(20.0)])).unwrap();
+        let snapshot = t.flush_incremental_state(&p).unwrap().unwrap();
+        assert_eq!(snapshot.num_rows(), 2);
+        assert_eq!(column_as_strings(&snapshot, "__op"), vec!["update", "update"]);
+    }
+}
+
+#[cfg(test)]
+mod sql_transform_query_validation_tests {
+    use super::parse_and_validate_query;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    fn schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("amount", DataType::Float64, true),
+        ]))
+    }
+
+    #[test]
+    fn rejects_multiple_statements() {
+        let err = parse_and_validate_query("SELECT id FROM input_data; SELECT amount FROM input_data", &schema()).unwrap_err();
+        assert!(err.to_string().contains("single SQL statement"));
+    }
+
+    #[test]
+    fn rejects_non_select_statements() {
+        let err = parse_and_validate_query("DELETE FROM input_data", &schema()).unwrap_err();
+        assert!(err.to_string().contains("single SELECT statement"));
+    }
+
+    #[test]
+    fn rejects_unknown_column() {
+        let err = parse_and_validate_query("SELECT nope FROM input_data", &schema()).unwrap_err();
+        assert!(err.to_string().contains("nope"));
+    }
+
+    #[test]
+    fn projects_referenced_columns_for_a_plain_filter() {
+        let columns = parse_and_validate_query("SELECT id FROM input_data WHERE amount > 1", &schema()).unwrap();
+        assert_eq!(columns, Some(vec!["amount".to_string(), "id".to_string()]));
+    }
+
+    #[test]
+    fn skips_projection_for_wildcard_but_still_parses() {
+        let columns = parse_and_validate_query("SELECT * FROM input_data", &schema()).unwrap();
+        assert_eq!(columns, None);
+    }
+
+    #[test]
+    fn skips_projection_for_group_by_but_still_validates_statement_shape() {
+        let columns = parse_and_validate_query("SELECT id, COUNT(*) FROM input_data GROUP BY id", &schema()).unwrap();
+        assert_eq!(columns, None);
+    }
+}
+
+#[cfg(test)]
+mod clean_data_tests {
+    use super::CleanData;
+    use datafusion::arrow::array::{DictionaryArray, Float64Array, Int32Array, LargeStringArray, RecordBatch};
+    use datafusion::arrow::datatypes::{DataType, Field, Int32Type, Schema};
+    use std::sync::Arc;
+
+    fn cleaner(remove_nulls: bool, remove_nulls_columns: Option<Vec<String>>, trim: bool, normalize_nan_inf: bool) -> CleanData {
+        CleanData::new(
+            "clean".to_string(),
+            Arc::new(Schema::empty()),
+            remove_nulls,
+            remove_nulls_columns,
+            trim,
+            Some("lower".to_string()).filter(|_| trim),
+            normalize_nan_inf,
+        )
+    }
+
+    #[test]
+    fn normalize_nan_inf_replaces_non_finite_floats_with_null() {
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Float64, true)]));
+        let array: Arc<dyn datafusion::arrow::array::Array> =
+            Arc::new(Float64Array::from(vec![Some(1.5), Some(f64::NAN), Some(f64::INFINITY), None]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![array]).unwrap();
+
+        let cleaned = cleaner(false, None, false, true).clean_batch(&batch).unwrap();
+        let values = cleaned.column(0).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(values.value(0), 1.5);
+        assert!(values.is_null(1), "NaN should become null");
+        assert!(values.is_null(2), "Infinity should become null");
+        assert!(values.is_null(3));
+    }
+
+    #[test]
+    fn clean_array_trims_and_lowercases_large_utf8() {
+        let schema = Arc::new(Schema::new(vec![Field::new("name", DataType::LargeUtf8, true)]));
+        let array: Arc<dyn datafusion::arrow::array::Array> = Arc::new(LargeStringArray::from(vec![Some(" Alice ")]));
+        let batch = RecordBatch::try_new(schema, vec![array]).unwrap();
+
+        let cleaned = cleaner(false, None, true, false).clean_batch(&batch).unwrap();
+        let values = cleaned.column(0).as_any().downcast_ref::<LargeStringArray>().unwrap();
+        assert_eq!(values.value(0), "alice");
+    }
+
+    #[test]
+    fn clean_array_trims_and_lowercases_dictionary_encoded_utf8() {
+        let value_field = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        let schema = Arc::new(Schema::new(vec![Field::new("name", value_field, true)]));
+        let keys = Int32Array::from(vec![Some(0)]);
+        let values = Arc::new(datafusion::arrow::array::StringArray::from(vec![" BOB "]));
+        let dict = DictionaryArray::<Int32Type>::try_new(keys, values).unwrap();
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(dict)]).unwrap();
+
+        let cleaned = cleaner(false, None, true, false).clean_batch(&batch).unwrap();
+        let dict_out = cleaned.column(0).as_any().downcast_ref::<DictionaryArray<Int32Type>>().unwrap();
+        let decoded = datafusion::arrow::compute::cast(&(Arc::new(dict_out.clone()) as Arc<dyn datafusion::arrow::array::Array>), &DataType::Utf8).unwrap();
+        let decoded = decoded.as_any().downcast_ref::<datafusion::arrow::array::StringArray>().unwrap();
+        assert_eq!(decoded.value(0), "bob");
+    }
+
+    #[test]
+    fn filter_null_rows_only_considers_configured_columns() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), None])),
+                Arc::new(Int32Array::from(vec![None, Some(2)])),
+            ],
+        )
+        .unwrap();
+
+        // Only `b` is considered, so the row with a null `a` (row 1) survives.
+        let cleaned = cleaner(true, Some(vec!["b".to_string()]), false, false).clean_batch(&batch).unwrap();
+        assert_eq!(cleaned.num_rows(), 1);
+        let a = cleaned.column(0).as_any().downcast_ref::<Int32Array>().unwrap();
+        assert!(a.is_null(0));
+    }
+}
+
+#[cfg(test)]
+mod combine_batches_tests {
+    use super::{merge_data_type, SqlTransform};
+    use datafusion::arrow::array::{Int32Array, Int64Array, RecordBatch, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    #[test]
+    fn merge_data_type_widens_int32_and_int64_to_int64() {
+        assert_eq!(merge_data_type(&DataType::Int32, &DataType::Int64).unwrap(), DataType::Int64);
+    }
+
+    #[test]
+    fn merge_data_type_widens_int_and_float_to_float64() {
+        assert_eq!(merge_data_type(&DataType::Int32, &DataType::Float64).unwrap(), DataType::Float64);
+    }
+
+    #[test]
+    fn merge_data_type_rejects_incompatible_types() {
+        assert!(merge_data_type(&DataType::Utf8, &DataType::Boolean).is_err());
+    }
+
+    #[tokio::test]
+    async fn combine_batches_concatenates_with_schema_drift_and_null_fills_missing_columns() {
+        let schema_a = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batch_a =
+            RecordBatch::try_new(schema_a, vec![Arc::new(Int32Array::from(vec![1])), Arc::new(StringArray::from(vec!["a"]))])
+                .unwrap();
+
+        // batch_b has a wider `id` type and lacks `name` entirely
+        let schema_b = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let batch_b = RecordBatch::try_new(schema_b, vec![Arc::new(Int64Array::from(vec![2]))]).unwrap();
+
+        let transform = SqlTransform::new(
+            "combine".to_string(),
+            Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)])),
+            "SELECT * FROM input_data".to_string(),
+            2,
+        )
+        .unwrap();
+
+        let combined = transform.combine_batches(&[batch_a, batch_b]).unwrap();
+        assert_eq!(combined.num_rows(), 2);
+        assert_eq!(combined.schema().field_with_name("id").unwrap().data_type(), &DataType::Int64);
+
+        let ids = combined.column(combined.schema().index_of("id").unwrap()).as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(ids.iter().flatten().collect::<Vec<_>>(), vec![1, 2]);
+
+        let name_idx = combined.schema().index_of("name").unwrap();
+        assert!(combined.column(name_idx).is_null(1), "name is absent from batch_b and should be null-filled");
+    }
+}
+
+#[cfg(test)]
+mod schema_evolution_tests {
+    use super::{infer_schema_with_policy, widen_data_type, SchemaEvolution};
+    use datafusion::arrow::array::{Int64Array, RecordBatch};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    #[test]
+    fn widen_data_type_widens_int_and_float_to_float() {
+        assert_eq!(widen_data_type(&DataType::Int64, &DataType::Float64, false).unwrap(), DataType::Float64);
+    }
+
+    #[test]
+    fn widen_data_type_widens_bool_and_int_to_int() {
+        assert_eq!(widen_data_type(&DataType::Boolean, &DataType::Int64, false).unwrap(), DataType::Int64);
+    }
+
+    #[test]
+    fn widen_data_type_widens_numeric_and_utf8_to_utf8() {
+        assert_eq!(widen_data_type(&DataType::Int64, &DataType::Utf8, false).unwrap(), DataType::Utf8);
+    }
+
+    #[test]
+    fn widen_data_type_falls_back_to_utf8_for_incompatible_types_unless_strict() {
+        assert_eq!(widen_data_type(&DataType::Boolean, &DataType::Utf8, false).unwrap(), DataType::Utf8);
+        assert!(widen_data_type(&DataType::Boolean, &DataType::Utf8, true).is_err());
+    }
+
+    #[test]
+    fn infer_schema_with_policy_preserves_first_seen_field_order() {
+        let rows = vec![
+            serde_json::json!({"b": 1, "a": "x"}),
+            serde_json::json!({"a": "y", "c": true}),
+        ];
+        let schema = infer_schema_with_policy(&rows, false).unwrap();
+        let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn infer_schema_with_policy_widens_conflicting_field_types_across_rows() {
+        let rows = vec![serde_json::json!({"n": 1}), serde_json::json!({"n": 2.5})];
+        let schema = infer_schema_with_policy(&rows, false).unwrap();
+        assert_eq!(schema.field_with_name("n").unwrap().data_type(), &DataType::Float64);
+    }
+
+    #[test]
+    fn check_declared_schema_rejects_unknown_fields_in_strict_mode() {
+        let declared = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let evolver = SchemaEvolution::new("ev".to_string(), declared.clone(), false, true);
+
+        let incoming_schema = Arc::new(Schema::new(vec![Field::new("extra", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(incoming_schema, vec![Arc::new(Int64Array::from(vec![1]))]).unwrap();
+
+        assert!(evolver.check_declared_schema(&batch).is_err());
+    }
+
+    #[test]
+    fn check_declared_schema_warns_but_passes_when_not_strict() {
+        let declared = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let evolver = SchemaEvolution::new("ev".to_string(), declared.clone(), false, false);
+
+        let incoming_schema = Arc::new(Schema::new(vec![Field::new("extra", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(incoming_schema, vec![Arc::new(Int64Array::from(vec![1]))]).unwrap();
+
+        assert!(evolver.check_declared_schema(&batch).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod metadata_catalog_tests {
+    use super::{columns_batch, Passthrough};
+    use datafusion::arrow::array::StringArray;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use rde_core::Operator;
+    use std::sync::Arc;
+
+    #[test]
+    fn columns_batch_has_one_row_per_operator_field() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let passthrough = Passthrough::new("op-1".to_string(), schema);
+
+        let batch = columns_batch(&[("passthrough", &passthrough as &dyn Operator)]).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+
+        let operator_ids = batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+        let field_names = batch.column(2).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(operator_ids.value(0), "op-1");
+        assert_eq!(operator_ids.value(1), "op-1");
+        assert_eq!(field_names.iter().flatten().collect::<Vec<_>>(), vec!["id", "name"]);
+    }
+}
+
+#[cfg(test)]
+mod sql_transform_registration_tests {
+    use super::SqlTransform;
+    use datafusion::arrow::array::{Int64Array, RecordBatch, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    fn input_schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]))
+    }
+
+    fn input_batch(ids: &[i64]) -> RecordBatch {
+        RecordBatch::try_new(input_schema(), vec![Arc::new(Int64Array::from(ids.to_vec()))]).unwrap()
+    }
+
+    /// A reference table registered via `with_reference_table` must survive
+    /// `execute_sql_query`'s per-call `register_batch("input_data", ...)`,
+    /// since that only re-registers `input_data`, not other tables
+    #[tokio::test]
+    async fn reference_table_persists_across_repeated_query_execution() {
+        let schema = input_schema();
+        let labels_schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("label", DataType::Utf8, false),
+        ]));
+        let labels = RecordBatch::try_new(
+            labels_schema,
+            vec![Arc::new(Int64Array::from(vec![1, 2])), Arc::new(StringArray::from(vec!["one", "two"]))],
+        )
+        .unwrap();
+
+        let transform = SqlTransform::new(
+            "join".to_string(),
+            schema.clone(),
+            "SELECT input_data.id, labels.label FROM input_data JOIN labels ON input_data.id = labels.id".to_string(),
+            1,
+        )
+        .unwrap()
+        .with_reference_table("labels", labels)
+        .unwrap();
+
+        let first = transform.execute_sql_query(&input_batch(&[1])).await.unwrap();
+        assert_eq!(first.num_rows(), 1);
+
+        // A second call re-registers `input_data`; `labels` must still be there.
+        let second = transform.execute_sql_query(&input_batch(&[2])).await.unwrap();
+        assert_eq!(second.num_rows(), 1);
+    }
+}
+
+#[cfg(test)]
+mod json_conversion_tests {
+    use super::{array_value_to_json, batch_to_json};
+    use datafusion::arrow::array::{Int64Array, ListArray, RecordBatch, StringArray, StructArray};
+    use datafusion::arrow::buffer::OffsetBuffer;
+    use datafusion::arrow::datatypes::{DataType, Field, Fields, Schema};
+    use std::sync::Arc;
+
+    #[test]
+    fn struct_column_round_trips_to_a_nested_json_object() {
+        let score_field = Arc::new(Field::new("score", DataType::Int64, true));
+        let struct_array = StructArray::from(vec![(
+            score_field,
+            Arc::new(Int64Array::from(vec![42])) as Arc<dyn datafusion::arrow::array::Array>,
+        )]);
+
+        let value = array_value_to_json(&(Arc::new(struct_array) as Arc<dyn datafusion::arrow::array::Array>), 0).unwrap();
+        assert_eq!(value, serde_json::json!({"score": 42}));
+    }
+
+    #[test]
+    fn list_column_round_trips_to_a_json_array() {
+        let values = StringArray::from(vec!["a", "b", "c"]);
+        let offsets = OffsetBuffer::new(vec![0, 2, 3].into());
+        let field = Arc::new(Field::new("item", DataType::Utf8, true));
+        let list_array = ListArray::new(field, offsets, Arc::new(values), None);
+
+        let first_row = array_value_to_json(&(Arc::new(list_array) as Arc<dyn datafusion::arrow::array::Array>), 0).unwrap();
+        assert_eq!(first_row, serde_json::json!(["a", "b"]));
+    }
+
+    #[test]
+    fn batch_to_json_converts_nested_struct_field() {
+        let score_field = Arc::new(Field::new("score", DataType::Int64, true));
+        let struct_array = StructArray::from(vec![(
+            score_field.clone(),
+            Arc::new(Int64Array::from(vec![7])) as Arc<dyn datafusion::arrow::array::Array>,
+        )]);
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "info",
+            DataType::Struct(Fields::from(vec![score_field.as_ref().clone()])),
+            true,
+        )]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(struct_array)]).unwrap();
+
+        let rows = batch_to_json(&batch).unwrap();
+        assert_eq!(rows, vec![serde_json::json!({"info": {"score": 7}})]);
+    }
 }
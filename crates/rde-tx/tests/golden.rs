@@ -0,0 +1,246 @@
+//! Declarative golden-file regression harness for transform operators,
+//! modeled loosely on sqllogictest.
+//!
+//! Each `tests/golden/*.test` file declares, in `section-name` / body blocks
+//! separated by blank lines:
+//!
+//! - `schema`: one `name:ArrowType[:nullable]` line per input column; a
+//!   column is nullable unless it has a modifier other than `nullable`
+//! - `transform`: a `TransformSpec` body, same YAML shape used in pipeline
+//!   config (a `type: <kind>` tag plus that variant's fields)
+//! - `input`: one or more CSV batches (no header row), separated by a line
+//!   containing only `---`
+//! - `output_schema` (optional): the schema of the expected result, same
+//!   line format as `schema`; defaults to `schema` itself when omitted
+//!   (operators like `Partition`/`JsonFlatten` add or rename columns, so
+//!   their golden files must declare this explicitly rather than relying on
+//!   the operator's own possibly-stale `Operator::schema()`)
+//! - `output`: the expected result, as CSV against `output_schema`
+//!
+//! The runner builds the operator via `create_transform`, drives it through
+//! its real `Transform::run` loop over `tokio::sync::mpsc` channels (every
+//! input batch, then `Message::Eos`), and diffs the emitted batches against
+//! `output` in a deterministic (sorted, typed) rendering, reporting the
+//! first mismatching row.
+
+use anyhow::{Context, Result};
+use datafusion::arrow::array::{BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray};
+use datafusion::arrow::csv::reader::ReaderBuilder;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use rde_core::{BatchRx, BatchTx, Message, TransformSpec};
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+#[tokio::test]
+async fn golden_files() -> Result<()> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let mut paths: Vec<_> = std::fs::read_dir(&dir)
+        .with_context(|| format!("reading golden test directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|ext| ext == "test").unwrap_or(false))
+        .collect();
+    paths.sort();
+    anyhow::ensure!(!paths.is_empty(), "no *.test files found under {}", dir.display());
+
+    let mut failures = Vec::new();
+    for path in &paths {
+        if let Err(err) = run_golden_file(path).await {
+            failures.push(format!("{}: {err:#}", path.display()));
+        }
+    }
+
+    if !failures.is_empty() {
+        anyhow::bail!("golden test failures ({}/{}):\n{}", failures.len(), paths.len(), failures.join("\n"));
+    }
+    Ok(())
+}
+
+async fn run_golden_file(path: &Path) -> Result<()> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let file = GoldenFile::parse(&text)?;
+
+    let input_schema: SchemaRef = Arc::new(file.schema);
+    let transform_spec: TransformSpec =
+        serde_yaml::from_str(&file.transform).context("parsing `transform` section as a TransformSpec")?;
+    let mut transform = rde_tx::create_transform(&transform_spec, input_schema.clone())
+        .context("constructing operator from TransformSpec")?;
+    let output_schema: SchemaRef = match file.output_schema {
+        Some(schema) => Arc::new(schema),
+        None => input_schema.clone(),
+    };
+
+    let input_batches = file
+        .input_blocks
+        .iter()
+        .map(|csv| parse_csv_batch(&input_schema, csv))
+        .collect::<Result<Vec<_>>>()
+        .context("parsing `input` section")?;
+    let expected_batch = parse_csv_batch(&output_schema, &file.output).context("parsing `output` section")?;
+
+    let (in_tx, in_rx): (BatchTx, BatchRx) = tokio::sync::mpsc::channel(input_batches.len().max(1) + 1);
+    let (out_tx, mut out_rx): (BatchTx, BatchRx) = tokio::sync::mpsc::channel(input_batches.len().max(1) + 1);
+
+    let run_handle = tokio::spawn(async move { transform.run(in_rx, out_tx, CancellationToken::new()).await });
+
+    for batch in input_batches {
+        in_tx.send(Message::Batch(batch)).await.ok();
+    }
+    in_tx.send(Message::Eos).await.ok();
+    drop(in_tx);
+
+    let mut actual_batches = Vec::new();
+    while let Some(msg) = out_rx.recv().await {
+        if let Message::Batch(batch) = msg {
+            actual_batches.push(batch);
+        }
+    }
+    run_handle.await.context("operator run loop panicked")??;
+
+    let actual_rows = render_rows(&output_schema, &actual_batches)?;
+    let expected_rows = render_rows(&output_schema, std::slice::from_ref(&expected_batch))?;
+
+    if actual_rows != expected_rows {
+        let mismatch = actual_rows.iter().zip(expected_rows.iter()).enumerate().find(|(_, (a, e))| a != e);
+        let detail = match mismatch {
+            Some((i, (a, e))) => format!("first mismatch at row {i}:\n  actual:   {a}\n  expected: {e}"),
+            None => format!("row count differs: {} actual vs {} expected", actual_rows.len(), expected_rows.len()),
+        };
+        anyhow::bail!(
+            "{detail}\nfull actual:\n{}\nfull expected:\n{}",
+            actual_rows.join("\n"),
+            expected_rows.join("\n")
+        );
+    }
+
+    Ok(())
+}
+
+struct GoldenFile {
+    schema: Schema,
+    output_schema: Option<Schema>,
+    transform: String,
+    input_blocks: Vec<String>,
+    output: String,
+}
+
+impl GoldenFile {
+    fn parse(text: &str) -> Result<Self> {
+        const SECTION_NAMES: &[&str] = &["schema", "output_schema", "transform", "input", "output"];
+
+        let mut sections: std::collections::HashMap<&str, Vec<String>> = std::collections::HashMap::new();
+        let mut current: Option<&str> = None;
+
+        for line in text.lines() {
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+            if let Some(&name) = SECTION_NAMES.iter().find(|&&name| line.trim() == name) {
+                current = Some(name);
+                sections.entry(name).or_default();
+                continue;
+            }
+            if let Some(name) = current {
+                sections.get_mut(name).unwrap().push(line.to_string());
+            }
+        }
+
+        let schema_lines = sections.remove("schema").context("missing `schema` section")?;
+        let schema = parse_schema(&schema_lines)?;
+        let output_schema = sections.remove("output_schema").map(|lines| parse_schema(&lines)).transpose()?;
+
+        let transform = sections.remove("transform").context("missing `transform` section")?.join("\n");
+
+        let input_blocks: Vec<String> = sections
+            .remove("input")
+            .context("missing `input` section")?
+            .split(|line: &String| line.trim() == "---")
+            .map(|block| block.join("\n"))
+            .filter(|block| !block.trim().is_empty())
+            .collect();
+        anyhow::ensure!(!input_blocks.is_empty(), "`input` section has no batches");
+
+        let output = sections.remove("output").context("missing `output` section")?.join("\n");
+
+        Ok(GoldenFile { schema, output_schema, transform, input_blocks, output })
+    }
+}
+
+fn parse_schema(lines: &[String]) -> Result<Schema> {
+    let mut fields = Vec::new();
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut parts = line.split(':');
+        let name = parts.next().with_context(|| format!("malformed schema line '{line}'"))?;
+        let type_name = parts.next().with_context(|| format!("schema line '{line}' is missing a type"))?;
+        let nullable = parts.next().map(|modifier| modifier == "nullable").unwrap_or(true);
+        let data_type = match type_name {
+            "Int64" => DataType::Int64,
+            "Float64" => DataType::Float64,
+            "Utf8" => DataType::Utf8,
+            "Boolean" => DataType::Boolean,
+            other => anyhow::bail!("unsupported golden-file schema type '{other}' (line '{line}')"),
+        };
+        fields.push(Field::new(name, data_type, nullable));
+    }
+    Ok(Schema::new(fields))
+}
+
+/// Parses a headerless CSV block against `schema` using Arrow's own CSV
+/// reader, so the harness's notion of "typed" matches the rest of the crate
+fn parse_csv_batch(schema: &SchemaRef, csv: &str) -> Result<RecordBatch> {
+    let trimmed = csv.trim();
+    if trimmed.is_empty() {
+        return Ok(RecordBatch::new_empty(schema.clone()));
+    }
+
+    let mut reader =
+        ReaderBuilder::new(schema.clone()).with_header(false).build(Cursor::new(trimmed.as_bytes().to_vec()))?;
+    let mut batches = Vec::new();
+    while let Some(batch) = reader.next() {
+        batches.push(batch?);
+    }
+
+    match batches.len() {
+        0 => Ok(RecordBatch::new_empty(schema.clone())),
+        1 => Ok(batches.remove(0)),
+        _ => {
+            let refs: Vec<&RecordBatch> = batches.iter().collect();
+            Ok(datafusion::arrow::compute::concat_batches(schema, &refs)?)
+        }
+    }
+}
+
+/// Renders every row of `batches` as a sorted list of `field=value|...`
+/// strings against `schema`, giving a deterministic, typed basis for comparison
+fn render_rows(schema: &SchemaRef, batches: &[RecordBatch]) -> Result<Vec<String>> {
+    let mut rows = Vec::new();
+    for batch in batches {
+        for row in 0..batch.num_rows() {
+            let mut cells = Vec::with_capacity(schema.fields().len());
+            for (col_idx, field) in schema.fields().iter().enumerate() {
+                let array = batch.column(col_idx);
+                let value = if array.is_null(row) {
+                    "null".to_string()
+                } else {
+                    match field.data_type() {
+                        DataType::Int64 => array.as_any().downcast_ref::<Int64Array>().unwrap().value(row).to_string(),
+                        DataType::Float64 => array.as_any().downcast_ref::<Float64Array>().unwrap().value(row).to_string(),
+                        DataType::Utf8 => array.as_any().downcast_ref::<StringArray>().unwrap().value(row).to_string(),
+                        DataType::Boolean => array.as_any().downcast_ref::<BooleanArray>().unwrap().value(row).to_string(),
+                        other => anyhow::bail!("golden harness: unsupported output column type {other:?}"),
+                    }
+                };
+                cells.push(format!("{}={value}", field.name()));
+            }
+            rows.push(cells.join("|"));
+        }
+    }
+    rows.sort();
+    Ok(rows)
+}